@@ -0,0 +1,102 @@
+//! Benchmarks of the wrapper overhead and of end-to-end solve times. Run them
+//! with `cargo bench`.
+
+use cadical::generators::{parity, pigeon_hole, random_ksat};
+use cadical::{CnfFormula, Solver};
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, Criterion};
+
+/// A small xorshift generator, so that the random assumptions are the same in
+/// every run.
+struct Random(u64);
+
+impl Random {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    fn lit(&mut self, vars: i32) -> i32 {
+        let var = (self.next() % vars as u64) as i32 + 1;
+        if self.next() & 1 == 0 {
+            var
+        } else {
+            -var
+        }
+    }
+}
+
+fn solver(formula: &CnfFormula) -> Solver {
+    let mut sat: Solver = Solver::new();
+    sat.add_formula(formula);
+    sat
+}
+
+fn ffi_overhead(c: &mut Criterion) {
+    let formula = random_ksat(1000, 2000, 3, 1);
+    c.bench_function("add_clause 3-SAT 2000", |b| {
+        b.iter(|| black_box(solver(&formula)))
+    });
+
+    let mut sat = solver(&formula);
+    assert_eq!(sat.solve(), Some(true));
+    c.bench_function("value 1000", |b| {
+        b.iter(|| {
+            (1..=1000)
+                .filter(|&lit| sat.value(lit) == Some(true))
+                .count()
+        })
+    });
+}
+
+fn end_to_end(c: &mut Criterion) {
+    let mut group = c.benchmark_group("solve");
+    group.sample_size(10);
+
+    let formula = pigeon_hole(7);
+    group.bench_function("pigeon hole 7", |b| {
+        b.iter_batched(
+            || solver(&formula),
+            |mut sat| assert_eq!(sat.solve(), Some(false)),
+            BatchSize::SmallInput,
+        )
+    });
+
+    let instances: Vec<_> = (1..=5).map(|seed| random_ksat(200, 852, 3, seed)).collect();
+    group.bench_function("random 3-SAT 200 at 4.26", |b| {
+        b.iter(|| {
+            for formula in instances.iter() {
+                black_box(solver(formula).solve());
+            }
+        })
+    });
+
+    let formula = parity(24, 3);
+    group.bench_function("parity chains 24", |b| {
+        b.iter_batched(
+            || solver(&formula),
+            |mut sat| assert_eq!(sat.solve(), Some(false)),
+            BatchSize::SmallInput,
+        )
+    });
+
+    let formula = random_ksat(300, 1050, 3, 7);
+    group.bench_function("incremental assumptions 3-SAT 300", |b| {
+        b.iter_batched(
+            || solver(&formula),
+            |mut sat| {
+                let mut random = Random(11);
+                for _ in 0..100 {
+                    let assumptions: Vec<i32> = (0..10).map(|_| random.lit(300)).collect();
+                    black_box(sat.solve_with(assumptions));
+                }
+            },
+            BatchSize::SmallInput,
+        )
+    });
+    group.finish();
+}
+
+criterion_group!(benches, ffi_overhead, end_to_end);
+criterion_main!(benches);
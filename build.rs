@@ -1,18 +1,65 @@
 fn main() -> std::io::Result<()> {
+    // the pure Rust backend does not need the C++ sources
+    if std::env::var("CARGO_FEATURE_PURE_RUST").is_ok() {
+        return Ok(());
+    }
+
+    let target = Target::new();
     let mut build = cc::Build::new();
     build
         .cpp(true)
         .flag_if_supported("-std=c++11")
         .warnings(true)
-        .define("NBUILD", None)
-        .define("NUNLOCKED", None)
-        .define("NTRACING", None)
-        .define("QUIET", None);
+        .define("NBUILD", None);
 
-    let version = std::fs::read_to_string("cadical/VERSION");
-    let version = version.expect("missing cadical submodule");
-    let version = format!("\"{}\"", version.trim());
-    build.define("VERSION", version.as_ref());
+    // settings of the cross toolchain, next to the `CXX_<target>`,
+    // `CXXFLAGS_<target>` and `CXXSTDLIB_<target>` variables of `cc`
+    println!("cargo:rerun-if-env-changed=CADICAL_SYSROOT");
+    println!("cargo:rerun-if-env-changed=CADICAL_CXXFLAGS");
+    if let Ok(dir) = std::env::var("CADICAL_SYSROOT") {
+        build.flag(format!("--sysroot={}", dir));
+    }
+    if let Ok(flags) = std::env::var("CADICAL_CXXFLAGS") {
+        for flag in flags.split_whitespace() {
+            build.flag(flag);
+        }
+    }
+
+    // the C++ runtime is linked statically into static musl executables
+    if target.env == "musl" && target.crt_static && std::env::var("CXXSTDLIB").is_err() {
+        build.cpp_link_stdlib(None);
+        println!("cargo:rustc-link-lib=static={}", target.cpp_stdlib());
+    }
+
+    // the live statistics are published with 64-bit atomics
+    if !target.has_atomic64 {
+        build.define("NATOMIC64", None);
+    }
+    if !target.has_atomic64 && std::env::var("CARGO_FEATURE_MEMORY_ACCOUNTING").is_ok() {
+        println!("cargo:rustc-link-lib=atomic");
+    }
+
+    // compile-time switches of CaDiCaL
+    if std::env::var("CARGO_FEATURE_CPP_LOCKED_IO").is_err() {
+        build.define("NUNLOCKED", None);
+    }
+    if std::env::var("CARGO_FEATURE_CPP_TRACING").is_err() {
+        build.define("NTRACING", None);
+    }
+    if std::env::var("CARGO_FEATURE_CPP_LOGGING").is_ok() {
+        build.define("LOGGING", None);
+    }
+    if std::env::var("CARGO_FEATURE_CPP_NO_CONTRACTS").is_ok() {
+        build.define("NCONTRACTS", None);
+    }
+    if std::env::var("CARGO_FEATURE_MEMORY_ACCOUNTING").is_ok() {
+        build.define("ACCOUNTING", None);
+    }
+
+    // profiling is only available in non-quiet builds
+    if std::env::var("CARGO_FEATURE_PROFILING").is_err() {
+        build.define("QUIET", None);
+    }
 
     // assertions only for debug builds with debug feature enabled
     if std::env::var("PROFILE").unwrap() == "debug"
@@ -23,6 +70,69 @@ fn main() -> std::io::Result<()> {
         build.debug(false).opt_level(3).define("NDEBUG", None);
     }
 
+    // optimize for size and leave out the lookahead code
+    let minimal = std::env::var("CARGO_FEATURE_MINIMAL").is_ok();
+    if minimal {
+        build
+            .opt_level_str("s")
+            .define("NCONTRACTS", None)
+            .flag_if_supported("-ffunction-sections")
+            .flag_if_supported("-fdata-sections");
+    }
+
+    // tune for the build machine, but not when cross-compiling or when the
+    // compiler does not understand the flags
+    if std::env::var("CARGO_FEATURE_NATIVE_CPU").is_ok()
+        && std::env::var("TARGET").ok() == std::env::var("HOST").ok()
+    {
+        let compiler = build.get_compiler();
+        if compiler.is_like_msvc() {
+            // msvc has no equivalent of -march=native
+        } else if build.is_flag_supported("-march=native").unwrap_or(false) {
+            build
+                .flag("-march=native")
+                .flag_if_supported("-mtune=native");
+        } else {
+            build.flag_if_supported("-mcpu=native");
+        }
+    }
+
+    // profile-guided optimization in two phases: an instrumented build that
+    // writes profiles to the given directory, and a build that uses them
+    println!("cargo:rerun-if-env-changed=CADICAL_PGO_GENERATE");
+    println!("cargo:rerun-if-env-changed=CADICAL_PGO_USE");
+    if let Ok(dir) = std::env::var("CADICAL_PGO_GENERATE") {
+        build.flag(format!("-fprofile-generate={}", dir));
+        if build.get_compiler().is_like_clang() {
+            println!("cargo:rustc-link-arg=-fprofile-generate");
+        } else {
+            println!("cargo:rustc-link-lib=gcov");
+        }
+    } else if let Ok(dir) = std::env::var("CADICAL_PGO_USE") {
+        build
+            .flag(format!("-fprofile-use={}", dir))
+            .flag_if_supported("-fprofile-correction")
+            .flag_if_supported("-Wno-missing-profile");
+    }
+
+    // only the wrapper is compiled when linking against an installed library
+    if std::env::var("CARGO_FEATURE_SYSTEM").is_ok() {
+        let links = link_system(&mut build, &target);
+        println!("cargo:rerun-if-changed=src/ccadical.cpp");
+        build.file("src/ccadical.cpp");
+        build.compile("ccadical");
+        for link in links.iter() {
+            println!("{}", link);
+        }
+        return Ok(());
+    }
+
+    build.include("cadical/src");
+    let version = std::fs::read_to_string("cadical/VERSION");
+    let version = version.expect("missing cadical submodule");
+    let version = format!("\"{}\"", version.trim());
+    build.define("VERSION", version.as_ref());
+
     let mut files = vec![
         "src/ccadical.cpp",
         "cadical/src/version.cpp",
@@ -89,13 +199,26 @@ fn main() -> std::io::Result<()> {
         "cadical/src/format.cpp",
     ];
 
+    // the resource usage of CaDiCaL needs POSIX, so Windows targets (also
+    // with MinGW) use the generic version
     if build.get_compiler().is_like_msvc() {
         build.include(std::path::Path::new("src/msvc"));
         files.push("src/msvc/resources.cpp");
-        files.push("src/msvc/lookahead.cpp");
+        if !minimal {
+            files.push("src/msvc/lookahead.cpp");
+        }
     } else {
-        files.push("cadical/src/resources.cpp");
-        files.push("cadical/src/lookahead.cpp");
+        if target.family == "unix" {
+            files.push("cadical/src/resources.cpp");
+        } else {
+            files.push("src/msvc/resources.cpp");
+        }
+        if !minimal {
+            files.push("cadical/src/lookahead.cpp");
+        }
+    }
+    if minimal {
+        files.push("src/minimal/lookahead.cpp");
     }
 
     build.files(files.iter());
@@ -106,3 +229,117 @@ fn main() -> std::io::Result<()> {
     build.compile("ccadical");
     Ok(())
 }
+
+/// The properties of the target platform, which differ from the platform of
+/// the build script when cross-compiling.
+struct Target {
+    os: String,
+    env: String,
+    family: String,
+    crt_static: bool,
+    has_atomic64: bool,
+}
+
+impl Target {
+    fn new() -> Self {
+        let var = |name: &str| std::env::var(name).unwrap_or_default();
+        Target {
+            os: var("CARGO_CFG_TARGET_OS"),
+            env: var("CARGO_CFG_TARGET_ENV"),
+            family: var("CARGO_CFG_TARGET_FAMILY"),
+            crt_static: var("CARGO_CFG_TARGET_FEATURE")
+                .split(',')
+                .any(|feature| feature == "crt-static"),
+            has_atomic64: var("CARGO_CFG_TARGET_HAS_ATOMIC")
+                .split(',')
+                .any(|width| width == "64"),
+        }
+    }
+
+    /// Returns the name of the C++ standard library of the target, which can
+    /// be overridden with the `CXXSTDLIB` variable.
+    fn cpp_stdlib(&self) -> String {
+        if let Ok(name) = std::env::var("CXXSTDLIB") {
+            return name;
+        }
+        match self.os.as_str() {
+            "android" => "c++_shared",
+            "macos" | "ios" | "tvos" | "watchos" | "freebsd" | "openbsd" => "c++",
+            _ => "stdc++",
+        }
+        .to_string()
+    }
+}
+
+/// Configures the wrapper for an installed CaDiCaL library, and returns the
+/// link directives, which must be printed after the wrapper is compiled. The
+/// library is located with the `CADICAL_LIB_DIR` and `CADICAL_INCLUDE_DIR`
+/// environment variables if they are set, and with `pkg-config` otherwise.
+/// The wrapper includes `ccadical.cpp` and `internal.hpp`, so one of the
+/// include directories must be the `src` directory of the sources of the
+/// installed version, not only the directory of `cadical.hpp`. The wrapper
+/// then defines all functions of the C interface itself, and it is linked
+/// before the library, so a static library that also contains `ccadical.o`
+/// does not lead to duplicate symbols.
+#[cfg(feature = "system")]
+fn link_system(build: &mut cc::Build, target: &Target) -> Vec<String> {
+    println!("cargo:rerun-if-env-changed=CADICAL_LIB_DIR");
+    println!("cargo:rerun-if-env-changed=CADICAL_INCLUDE_DIR");
+    println!("cargo:rerun-if-env-changed=CADICAL_STATIC");
+    let kind = if std::env::var("CADICAL_STATIC").is_ok() {
+        "static"
+    } else {
+        "dylib"
+    };
+
+    let mut includes: Vec<std::path::PathBuf> = Vec::new();
+    let mut links = Vec::new();
+    if let Ok(dir) = std::env::var("CADICAL_LIB_DIR") {
+        links.push(format!("cargo:rustc-link-search=native={}", dir));
+        links.push(format!("cargo:rustc-link-lib={}=cadical", kind));
+    } else {
+        let library = pkg_config::Config::new()
+            .statik(kind == "static")
+            .cargo_metadata(false)
+            .probe("cadical")
+            .expect("cannot find the cadical library with pkg-config");
+        for dir in library.link_paths.iter() {
+            links.push(format!("cargo:rustc-link-search=native={}", dir.display()));
+        }
+        for lib in library.libs.iter() {
+            if lib == "cadical" {
+                links.push(format!("cargo:rustc-link-lib={}=cadical", kind));
+            } else {
+                links.push(format!("cargo:rustc-link-lib={}", lib));
+            }
+        }
+        includes.extend(library.include_paths);
+    }
+    if let Ok(dir) = std::env::var("CADICAL_INCLUDE_DIR") {
+        includes.push(dir.into());
+    }
+
+    let sources = includes
+        .iter()
+        .any(|dir| dir.join("internal.hpp").is_file() && dir.join("ccadical.cpp").is_file());
+    if !sources {
+        panic!(
+            "the system feature needs internal.hpp and ccadical.cpp of the installed \
+             CaDiCaL version, set CADICAL_INCLUDE_DIR to the src directory of its sources"
+        );
+    }
+    for dir in includes.iter() {
+        build.include(dir);
+    }
+
+    // the static library needs the C++ runtime of the wrapper
+    if kind == "static" && !build.get_compiler().is_like_msvc() {
+        links.push(format!("cargo:rustc-link-lib={}", target.cpp_stdlib()));
+    }
+    links
+}
+
+#[cfg(not(feature = "system"))]
+fn link_system(_build: &mut cc::Build, _target: &Target) -> Vec<String> {
+    unreachable!()
+}
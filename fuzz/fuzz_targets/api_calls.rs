@@ -0,0 +1,107 @@
+//! Runs random sequences of API calls on a solver and checks the models.
+
+#![no_main]
+
+use cadical::{Solver, State};
+use libfuzzer_sys::arbitrary::Arbitrary;
+use libfuzzer_sys::fuzz_target;
+
+#[derive(Arbitrary, Debug)]
+enum Call {
+    AddClause(Vec<i8>),
+    AddRemovableClause(Vec<i8>),
+    RemoveClause(u8),
+    Push,
+    Pop,
+    Assume(i8),
+    Constrain(Vec<i8>),
+    ClearConstraint,
+    Prioritize(Vec<i8>),
+    Reserve(u8),
+    SetLimit(u8),
+    Solve,
+    SolveWith(Vec<i8>),
+    Value(i8),
+    Failed(i8),
+    Traverse,
+    Reset,
+}
+
+/// Maps the input to a nonzero literal over the first 32 variables.
+fn lit(val: i8) -> i32 {
+    let var = (val.unsigned_abs() % 32) as i32 + 1;
+    if val < 0 {
+        -var
+    } else {
+        var
+    }
+}
+
+fn lits(vals: &[i8]) -> Vec<i32> {
+    vals.iter().map(|&val| lit(val)).collect()
+}
+
+fuzz_target!(|calls: Vec<Call>| {
+    let mut sat: Solver = Solver::new();
+    let mut handles = Vec::new();
+    let mut assumptions = Vec::new();
+    for call in calls.iter().take(64) {
+        match call {
+            Call::AddClause(vals) => sat.add_clause(lits(vals)),
+            Call::AddRemovableClause(vals) => handles.push(sat.add_removable_clause(lits(vals))),
+            Call::RemoveClause(index) => {
+                if !handles.is_empty() {
+                    let handle = handles.swap_remove(*index as usize % handles.len());
+                    sat.remove_clause(handle).unwrap();
+                }
+            }
+            Call::Push => sat.push(),
+            Call::Pop => {
+                let depth = sat.scope_depth();
+                assert_eq!(sat.pop().is_ok(), depth > 0);
+            }
+            Call::Assume(val) => {
+                sat.assume(lit(*val));
+                assumptions.push(lit(*val));
+            }
+            Call::Constrain(vals) => sat.constrain(lits(vals)),
+            Call::ClearConstraint => sat.clear_constraint(),
+            Call::Prioritize(vals) => sat.prioritize(lits(vals).into_iter().map(i32::abs)),
+            Call::Reserve(max) => sat.reserve((*max % 64) as i32),
+            Call::SetLimit(conflicts) => sat.set_limit("conflicts", *conflicts as i32).unwrap(),
+            Call::Solve | Call::SolveWith(_) => {
+                if let Call::SolveWith(vals) = call {
+                    assumptions.extend(lits(vals));
+                    sat.solve_with(lits(vals))
+                } else {
+                    sat.solve()
+                };
+                if sat.state() == State::Satisfied {
+                    for &lit in assumptions.iter() {
+                        assert_eq!(sat.value(lit), Some(true));
+                    }
+                }
+                assumptions.clear();
+            }
+            Call::Value(val) => {
+                if sat.state() == State::Satisfied {
+                    let lit = lit(*val);
+                    assert_eq!(sat.value(-lit), sat.value(lit).map(|b| !b));
+                }
+            }
+            Call::Failed(val) => {
+                if sat.state() == State::Unsatisfied {
+                    sat.failed(lit(*val));
+                }
+            }
+            Call::Traverse => {
+                assert!(sat.traverse_clauses(|clause| !clause.contains(&0)));
+            }
+            Call::Reset => {
+                sat.reset();
+                handles.clear();
+                assumptions.clear();
+            }
+        }
+    }
+});
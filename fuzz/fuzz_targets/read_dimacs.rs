@@ -0,0 +1,22 @@
+//! Feeds arbitrary bytes to the DIMACS parser and solves the accepted inputs.
+
+#![no_main]
+
+use cadical::Solver;
+use libfuzzer_sys::fuzz_target;
+use std::io::Write;
+
+fuzz_target!(|data: &[u8]| {
+    let path = std::env::temp_dir().join(format!("cadical-fuzz-{}.cnf", std::process::id()));
+    let mut file = std::fs::File::create(&path).unwrap();
+    file.write_all(data).unwrap();
+    drop(file);
+
+    let mut sat: Solver = Solver::new();
+    if let Ok(vars) = sat.read_dimacs(&path) {
+        assert!(sat.max_variable() >= vars);
+        sat.set_limit("conflicts", 1000).unwrap();
+        sat.solve();
+    }
+    std::fs::remove_file(&path).unwrap();
+});
@@ -0,0 +1,290 @@
+//! Minimization of recorded API call sequences for bug reports.
+
+#[cfg(feature = "cpp-tracing")]
+use super::ffi::*;
+#[cfg(feature = "cpp-tracing")]
+use super::{dimacs_path, Error};
+use super::{Callbacks, Solver};
+use std::fmt;
+use std::ops::Range;
+#[cfg(feature = "cpp-tracing")]
+use std::path::Path;
+
+/// A sequence of API calls in the line based trace format of CaDiCaL, as
+/// written by a library built with the `cpp-tracing` feature. The calls can
+/// be replayed on a solver, and `shrink` minimizes them while a failure
+/// persists, which produces small reproducers for bug reports.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ApiTrace {
+    /// The calls of the trace, one per line, such as `add 1` or `solve`.
+    pub calls: Vec<String>,
+}
+
+/// Returns the name of the call and its first integer argument.
+fn split_call(call: &str) -> (&str, Option<i32>) {
+    let mut words = call.split_whitespace();
+    let name = words.next().unwrap_or("");
+    let arg = words.next().and_then(|word| word.parse().ok());
+    (name, arg)
+}
+
+/// Checks if the call must be kept when the trace is shrunk.
+fn is_fixed(call: &str) -> bool {
+    matches!(split_call(call).0, "init" | "release" | "reset")
+}
+
+/// Checks if the call adds a literal to a clause or to the constraint.
+fn is_literal(call: &str) -> bool {
+    match split_call(call) {
+        ("add", Some(lit)) | ("constrain", Some(lit)) => lit != 0,
+        _ => false,
+    }
+}
+
+impl ApiTrace {
+    /// Parses a trace, where every non-empty line is a call.
+    /// # Examples
+    /// ```
+    /// let trace = cadical::ApiTrace::parse("init\nadd 1\nadd 0\nsolve\n");
+    /// assert_eq!(trace.calls.len(), 4);
+    /// assert_eq!(trace.to_string(), "init\nadd 1\nadd 0\nsolve\n");
+    /// ```
+    pub fn parse(text: &str) -> Self {
+        let calls = text
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .map(str::to_string)
+            .collect();
+        ApiTrace { calls }
+    }
+
+    /// Replays the calls on the given solver through the safe interface and
+    /// returns the results of the solve calls. The clauses and the
+    /// constraint are added when their terminating zero is reached, and
+    /// queries and unknown calls are skipped.
+    pub fn replay<C: Callbacks>(&self, sat: &mut Solver<C>) -> Vec<Option<bool>> {
+        let mut clause = Vec::new();
+        let mut constraint = Vec::new();
+        let mut results = Vec::new();
+        for call in self.calls.iter() {
+            let mut words = call.split_whitespace();
+            let name = words.next().unwrap_or("");
+            let args: Vec<&str> = words.collect();
+            let arg = args.first().and_then(|word| word.parse::<i32>().ok());
+            let value = args.get(1).and_then(|word| word.parse::<i32>().ok());
+            match (name, arg) {
+                ("add", Some(0)) => sat.add_clause(clause.drain(..)),
+                ("add", Some(lit)) => clause.push(lit),
+                ("constrain", Some(0)) => sat.constrain(constraint.drain(..)),
+                ("constrain", Some(lit)) => constraint.push(lit),
+                ("assume", Some(lit)) if lit != 0 => sat.assume(lit),
+                ("freeze", Some(lit)) if lit != 0 => sat.freeze(lit),
+                ("melt", Some(lit)) if lit != 0 => {
+                    let _ = sat.melt(lit);
+                }
+                ("reserve", Some(max)) => sat.reserve(max),
+                ("set", _) => {
+                    if let (Some(option), Some(value)) = (args.first(), value) {
+                        let _ = sat.set_option(option, value);
+                    }
+                }
+                ("limit", _) => {
+                    if let (Some(limit), Some(value)) = (args.first(), value) {
+                        let _ = sat.set_limit(limit, value);
+                    }
+                }
+                ("solve", _) => results.push(sat.solve()),
+                _ => {}
+            }
+        }
+        results
+    }
+
+    /// Splits the trace into the ranges of calls that are removed together:
+    /// whole clauses and constraints, and single calls otherwise.
+    fn units(&self) -> Vec<Range<usize>> {
+        let mut units = Vec::new();
+        let mut start = 0;
+        for (index, call) in self.calls.iter().enumerate() {
+            if is_literal(call) {
+                continue;
+            }
+            units.push(start..index + 1);
+            start = index + 1;
+        }
+        if start < self.calls.len() {
+            units.push(start..self.calls.len());
+        }
+        units
+    }
+
+    /// Returns the trace made of the selected calls in their original order.
+    fn select(&self, keep: &[bool]) -> ApiTrace {
+        let calls = self.calls.iter().zip(keep.iter());
+        ApiTrace {
+            calls: calls
+                .filter(|(_, &keep)| keep)
+                .map(|(call, _)| call.clone())
+                .collect(),
+        }
+    }
+
+    /// Returns which calls are kept when the given units are removed.
+    fn mask(&self, units: &[Range<usize>], removed: &[bool]) -> Vec<bool> {
+        let mut keep = vec![true; self.calls.len()];
+        for (unit, &gone) in units.iter().zip(removed.iter()) {
+            if gone {
+                keep[unit.clone()].fill(false);
+            }
+        }
+        keep
+    }
+
+    /// Removes as many whole units as possible with delta debugging, trying
+    /// to drop ever smaller chunks of them.
+    fn shrink_units<F>(&self, failing: &mut F) -> ApiTrace
+    where
+        F: FnMut(&ApiTrace) -> bool,
+    {
+        let units: Vec<Range<usize>> = self
+            .units()
+            .into_iter()
+            .filter(|unit| !self.calls[unit.clone()].iter().any(|call| is_fixed(call)))
+            .collect();
+        let mut removed = vec![false; units.len()];
+        let mut chunks = 2;
+        loop {
+            let alive: Vec<usize> = (0..units.len()).filter(|&i| !removed[i]).collect();
+            if alive.is_empty() {
+                break;
+            }
+            let size = alive.len().div_ceil(chunks);
+            let mut reduced = false;
+            for chunk in alive.chunks(size) {
+                let mut candidate = removed.clone();
+                chunk.iter().for_each(|&unit| candidate[unit] = true);
+                if failing(&self.select(&self.mask(&units, &candidate))) {
+                    removed = candidate;
+                    chunks = (chunks - 1).max(2);
+                    reduced = true;
+                    break;
+                }
+            }
+            if !reduced {
+                if size == 1 {
+                    break;
+                }
+                chunks = (chunks * 2).min(alive.len());
+            }
+        }
+        self.select(&self.mask(&units, &removed))
+    }
+
+    /// Removes single literals from the clauses and the constraint.
+    fn shrink_literals<F>(&self, failing: &mut F) -> ApiTrace
+    where
+        F: FnMut(&ApiTrace) -> bool,
+    {
+        let mut trace = self.clone();
+        let mut index = 0;
+        while index < trace.calls.len() {
+            if is_literal(&trace.calls[index]) {
+                let mut candidate = trace.clone();
+                candidate.calls.remove(index);
+                if failing(&candidate) {
+                    trace = candidate;
+                    continue;
+                }
+            }
+            index += 1;
+        }
+        trace
+    }
+
+    /// Minimizes the trace while the given predicate, which should replay
+    /// the calls and return `true` if the failure still occurs, holds. Whole
+    /// clauses and calls are dropped first, then single literals, until no
+    /// more calls can be removed. The `init`, `release` and `reset` calls
+    /// are always kept. The predicate should hold for the original trace.
+    /// # Examples
+    /// ```
+    /// let text = "init\nadd 1\nadd 0\nadd 2\nadd 3\nadd 0\nadd -1\nadd 2\nadd 0\n\
+    ///             add -2\nadd 0\nsolve\nrelease\n";
+    /// let trace = cadical::ApiTrace::parse(text);
+    /// let small = trace.shrink(|trace| {
+    ///     let mut sat: cadical::Solver = Default::default();
+    ///     trace.replay(&mut sat).last() == Some(&Some(false))
+    /// });
+    /// assert_eq!(small.to_string(), "init\nadd 0\nsolve\nrelease\n");
+    /// ```
+    pub fn shrink<F>(&self, mut failing: F) -> ApiTrace
+    where
+        F: FnMut(&ApiTrace) -> bool,
+    {
+        let mut trace = self.clone();
+        loop {
+            let smaller = trace.shrink_units(&mut failing);
+            let smaller = smaller.shrink_literals(&mut failing);
+            if smaller.calls.len() == trace.calls.len() {
+                return smaller;
+            }
+            trace = smaller;
+        }
+    }
+}
+
+impl fmt::Display for ApiTrace {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for call in self.calls.iter() {
+            writeln!(f, "{}", call)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "cpp-tracing")]
+impl<C: Callbacks> Solver<C> {
+    /// Starts writing the API calls of this solver to the given file in the
+    /// native trace format of CaDiCaL, which can be replayed with `mobical`
+    /// and the other debugging tools of CaDiCaL, or loaded with
+    /// `ApiTrace::parse`. The trace starts with an `init` call, so this
+    /// should be called right after the solver is created to get a
+    /// complete trace. It continues after `reset` and the file is closed
+    /// when the solver is dropped. This requires the `cpp-tracing` feature,
+    /// and fails if the calls are already traced, including through the
+    /// `CADICAL_API_TRACE` environment variable.
+    /// # Examples
+    /// ```
+    /// let path = std::env::temp_dir().join("cadical-doc-trace.txt");
+    /// let mut sat: cadical::Solver = Default::default();
+    /// sat.trace_api_calls(&path).unwrap();
+    /// assert!(sat.trace_api_calls(&path).is_err());
+    /// sat.add_clause([1, 2]);
+    /// assert_eq!(sat.solve(), Some(true));
+    /// drop(sat);
+    /// let trace = cadical::ApiTrace::parse(&std::fs::read_to_string(&path).unwrap());
+    /// assert_eq!(trace.calls[0], "init");
+    /// # std::fs::remove_file(&path).ok();
+    /// ```
+    pub fn trace_api_calls(&mut self, path: &Path) -> Result<(), Error> {
+        if self.is_tracing_api_calls() {
+            return Err(Error::new("already tracing API calls"));
+        }
+        let path = dimacs_path(path)?;
+        let file = unsafe { ccadical_trace_open(path.as_ptr()) };
+        if file.is_null() {
+            return Err(Error::new("cannot open file"));
+        }
+        unsafe { ccadical_trace_api_calls(self.ptr, file) };
+        self.api_trace_file = file;
+        Ok(())
+    }
+
+    /// Returns `true` if the API calls of this solver are traced, either
+    /// with `trace_api_calls` or through the `CADICAL_API_TRACE`
+    /// environment variable, which CaDiCaL reads when the solver is created.
+    pub fn is_tracing_api_calls(&self) -> bool {
+        !self.api_trace_file.is_null() || std::env::var_os("CADICAL_API_TRACE").is_some()
+    }
+}
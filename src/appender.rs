@@ -0,0 +1,145 @@
+//! Incremental logging of the added clauses and the assumptions of the solve
+//! calls to a file in the incremental DIMACS (ICNF) format.
+
+use super::{Callbacks, ClauseStore, Error, Solver};
+use std::fs::{File, OpenOptions};
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+
+/// The clauses and the queries added since the last flush, and the file
+/// they are appended to. Every query remembers the number of pending
+/// clauses before it.
+pub(crate) struct DimacsLog {
+    file: File,
+    pending: ClauseStore,
+    queries: ClauseStore,
+    positions: Vec<usize>,
+    assumptions: Vec<i32>,
+}
+
+impl DimacsLog {
+    /// Records a clause that was added to the solver.
+    #[inline]
+    pub(crate) fn record(&mut self, clause: &[i32]) {
+        self.pending.push(clause);
+    }
+
+    /// Records assumptions for the next solve call.
+    pub(crate) fn assume(&mut self, lits: &[i32]) {
+        self.assumptions.extend_from_slice(lits);
+    }
+
+    /// Forgets the assumptions recorded for the next solve call.
+    pub(crate) fn clear_assumptions(&mut self) {
+        self.assumptions.clear();
+    }
+
+    /// Records a solve call with the recorded and the given assumptions.
+    pub(crate) fn record_query<I>(&mut self, persistent: I)
+    where
+        I: IntoIterator<Item = i32>,
+    {
+        self.assumptions.extend(persistent);
+        self.queries.push(&self.assumptions);
+        self.positions.push(self.pending.len());
+        self.assumptions.clear();
+    }
+
+    /// Appends the pending clauses and queries to the file.
+    fn write_pending(&mut self) -> io::Result<()> {
+        let mut writer = BufWriter::new(&self.file);
+        let mut query = 0;
+        for index in 0..=self.pending.len() {
+            while query < self.queries.len() && self.positions[query] == index {
+                write!(writer, "a ")?;
+                for lit in self.queries.clause(query) {
+                    write!(writer, "{} ", lit)?;
+                }
+                writeln!(writer, "0")?;
+                query += 1;
+            }
+            if index < self.pending.len() {
+                for lit in self.pending.clause(index) {
+                    write!(writer, "{} ", lit)?;
+                }
+                writeln!(writer, "0")?;
+            }
+        }
+        writer.flush()?;
+        self.pending.clear();
+        self.queries.clear();
+        self.positions.clear();
+        Ok(())
+    }
+}
+
+impl Drop for DimacsLog {
+    fn drop(&mut self) {
+        let _ = self.write_pending();
+    }
+}
+
+impl<C: Callbacks> Solver<C> {
+    /// Starts logging the clauses added from now on to the given file, which
+    /// is created with a `p inccnf` header if it does not exist or is empty,
+    /// and appended to otherwise. Every solve call is logged as an `a` line
+    /// with its assumptions, so the file can be replayed as an incremental
+    /// DIMACS trace. The clauses are kept in memory until `flush_dimacs_log`
+    /// is called or logging is stopped, so the file can be kept up to date
+    /// cheaply during long incremental sessions. The clauses and the
+    /// assumptions are logged as seen by CaDiCaL, including the activation
+    /// literals of scopes and removable clauses, but clauses loaded with
+    /// `read_dimacs` and constraints are not logged.
+    /// # Examples
+    /// ```
+    /// let path = std::env::temp_dir().join("cadical-doc-log.icnf");
+    /// let mut sat: cadical::Solver = Default::default();
+    /// sat.set_dimacs_log(&path).unwrap();
+    /// sat.add_clause([1, -2]);
+    /// assert_eq!(sat.solve_with([2]), Some(true));
+    /// sat.flush_dimacs_log().unwrap();
+    /// sat.close_dimacs_log().unwrap();
+    /// let text = std::fs::read_to_string(&path).unwrap();
+    /// assert!(text.ends_with("1 -2 0\na 2 0\n"));
+    /// std::fs::remove_file(&path).unwrap();
+    /// ```
+    pub fn set_dimacs_log(&mut self, path: &Path) -> Result<(), Error> {
+        self.close_dimacs_log()?;
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .map_err(|_| Error::new("cannot open file"))?;
+        let empty = file.metadata().map(|m| m.len() == 0).unwrap_or(false);
+        if empty {
+            writeln!(file, "p inccnf").map_err(|_| Error::new("cannot write file"))?;
+        }
+        self.dimacs_log = Some(DimacsLog {
+            file,
+            pending: ClauseStore::new(),
+            queries: ClauseStore::new(),
+            positions: Vec::new(),
+            assumptions: Vec::new(),
+        });
+        Ok(())
+    }
+
+    /// Appends the clauses added since the last flush to the log file. Does
+    /// nothing if logging is not enabled.
+    pub fn flush_dimacs_log(&mut self) -> Result<(), Error> {
+        match self.dimacs_log.as_mut() {
+            Some(log) => log
+                .write_pending()
+                .map_err(|_| Error::new("cannot write file")),
+            None => Ok(()),
+        }
+    }
+
+    /// Flushes the pending clauses and stops logging. Dropping the solver
+    /// also flushes the log, but ignores the errors.
+    pub fn close_dimacs_log(&mut self) -> Result<(), Error> {
+        let result = self.flush_dimacs_log();
+        self.dimacs_log = None;
+        result
+    }
+}
@@ -0,0 +1,94 @@
+//! Solving on a background thread with an owning handle.
+
+use super::{Callbacks, Solver, Timeout};
+use std::panic;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+
+/// The owning handle of a solver that is solving on a background thread,
+/// returned by `Solver::solve_in_background`. The handle can be moved to and
+/// dropped on any thread. Dropping it terminates the running solve call and
+/// waits until the native call has returned before the solver is released,
+/// so an early exit can never free the solver while CaDiCaL still uses it.
+/// # Examples
+/// ```
+/// let mut sat: cadical::Solver = Default::default();
+/// sat.add_formula(&cadical::generators::pigeon_hole(12));
+/// let handle = sat.solve_in_background(None);
+/// handle.terminate();
+/// let (sat, result) = handle.join();
+/// assert_eq!(result, None);
+/// assert_eq!(sat.status(), None);
+/// ```
+pub struct SolveHandle<C: Callbacks = Timeout> {
+    interrupt: Arc<AtomicBool>,
+    thread: Option<JoinHandle<(Solver<C>, Option<bool>)>>,
+}
+
+impl<C: Callbacks + Send + 'static> SolveHandle<C> {
+    fn spawn(mut solver: Solver<C>, assumptions: Vec<i32>) -> Self {
+        let interrupt = solver.interrupt_flag();
+        let thread = thread::spawn(move || {
+            let result = solver.solve_with(assumptions);
+            (solver, result)
+        });
+        SolveHandle {
+            interrupt,
+            thread: Some(thread),
+        }
+    }
+}
+
+impl<C: Callbacks> SolveHandle<C> {
+    /// Terminates the running solve call. The result of the call is `None`
+    /// unless it has already finished.
+    pub fn terminate(&self) {
+        self.interrupt.store(true, Ordering::Relaxed);
+    }
+
+    /// Checks if the solve call has finished, so `join` would not block.
+    pub fn is_finished(&self) -> bool {
+        match &self.thread {
+            Some(thread) => thread.is_finished(),
+            None => true,
+        }
+    }
+
+    /// Waits for the solve call to finish, and returns the solver with the
+    /// result of the call. A panic of the callbacks on the background thread
+    /// is propagated to the caller.
+    pub fn join(mut self) -> (Solver<C>, Option<bool>) {
+        let thread = self.thread.take().unwrap();
+        let ret = thread
+            .join()
+            .unwrap_or_else(|err| panic::resume_unwind(err));
+        // a late termination must not stop the next solve call
+        self.interrupt.store(false, Ordering::Relaxed);
+        ret
+    }
+}
+
+impl<C: Callbacks> Drop for SolveHandle<C> {
+    fn drop(&mut self) {
+        if let Some(thread) = self.thread.take() {
+            self.terminate();
+            // the solver is released on the background thread after the
+            // native call has returned, a panic there is not propagated
+            let _ = thread.join();
+        }
+    }
+}
+
+impl<C: Callbacks + Send + 'static> Solver<C> {
+    /// Moves the solver to a new thread and starts solving the formula under
+    /// the given assumptions there. The returned handle gives the solver
+    /// back with `join`, and terminates the solve call safely if it is
+    /// dropped before.
+    pub fn solve_in_background<I>(self, assumptions: I) -> SolveHandle<C>
+    where
+        I: IntoIterator<Item = i32>,
+    {
+        SolveHandle::spawn(self, assumptions.into_iter().collect())
+    }
+}
@@ -0,0 +1,14 @@
+//! The worker process of `IsolatedSolver`, which serves the solver commands
+//! read from the standard input.
+
+use std::io;
+use std::process;
+
+fn main() {
+    let stdin = io::stdin();
+    let stdout = io::stdout();
+    if let Err(err) = cadical::isolated::serve(stdin.lock(), stdout.lock()) {
+        eprintln!("cadical-worker: {}", err);
+        process::exit(1);
+    }
+}
@@ -0,0 +1,197 @@
+//! Reading of combinational logic networks in the Berkeley Logic
+//! Interchange Format (BLIF) and their encoding into clauses.
+
+use super::{Callbacks, Error, Solver};
+use std::collections::HashMap;
+use std::path::Path;
+
+/// A logic function of a BLIF network given by a `.names` table, which is a
+/// sum of products cover of either the on-set or the off-set of the output.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BlifTable {
+    /// The input nets of the function.
+    pub inputs: Vec<String>,
+    /// The output net of the function.
+    pub output: String,
+    /// The cubes of the cover, one character `0`, `1` or `-` for each input.
+    pub cubes: Vec<String>,
+    /// Whether the cubes cover the on-set (`true`) or the off-set (`false`)
+    /// of the output.
+    pub on_set: bool,
+}
+
+/// A single model of a BLIF netlist. Latches are read as pairs of nets, but
+/// only the combinational part is encoded, with the latch outputs treated
+/// as free inputs.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct BlifNetwork {
+    /// The name of the model.
+    pub model: String,
+    /// The primary input nets.
+    pub inputs: Vec<String>,
+    /// The primary output nets.
+    pub outputs: Vec<String>,
+    /// The input and output nets of the latches.
+    pub latches: Vec<(String, String)>,
+    /// The logic functions of the network.
+    pub tables: Vec<BlifTable>,
+}
+
+impl BlifNetwork {
+    /// Parses a network in BLIF format. Only the first model is read, and
+    /// hierarchical (`.subckt`) and library gate (`.gate`) constructs are
+    /// not supported.
+    /// # Examples
+    /// ```
+    /// let text = ".model and\n.inputs a b\n.outputs c\n.names a b c\n11 1\n.end\n";
+    /// let network = cadical::BlifNetwork::parse(text).unwrap();
+    /// assert_eq!(network.inputs, vec!["a", "b"]);
+    /// assert_eq!(network.tables[0].cubes, vec!["11"]);
+    /// ```
+    pub fn parse(text: &str) -> Result<Self, Error> {
+        let mut network = BlifNetwork::default();
+        let mut line = String::new();
+        for part in text.lines() {
+            let part = part.split('#').next().unwrap();
+            if let Some(part) = part.trim_end().strip_suffix('\\') {
+                line.push_str(part);
+                line.push(' ');
+                continue;
+            }
+            line.push_str(part);
+            let tokens: Vec<&str> = line.split_whitespace().collect();
+            if !tokens.is_empty() && network.add_line(&tokens)? {
+                return Ok(network);
+            }
+            line.clear();
+        }
+        Ok(network)
+    }
+
+    /// Processes a single logical line, and returns `true` at the end of the
+    /// model.
+    fn add_line(&mut self, tokens: &[&str]) -> Result<bool, Error> {
+        let names = |tokens: &[&str]| tokens.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        match tokens[0] {
+            ".model" => self.model = tokens[1..].join(" "),
+            ".inputs" => self.inputs.extend(names(&tokens[1..])),
+            ".outputs" => self.outputs.extend(names(&tokens[1..])),
+            ".names" => {
+                let mut inputs = names(&tokens[1..]);
+                let output = inputs.pop().ok_or_else(|| Error::new("missing output"))?;
+                self.tables.push(BlifTable {
+                    inputs,
+                    output,
+                    cubes: Vec::new(),
+                    on_set: true,
+                });
+            }
+            ".latch" => {
+                if tokens.len() < 3 {
+                    return Err(Error::new("invalid latch"));
+                }
+                self.latches
+                    .push((tokens[1].to_string(), tokens[2].to_string()));
+            }
+            ".end" | ".exdc" => return Ok(true),
+            token if token.starts_with('.') => {
+                return Err(Error::new("unsupported directive"));
+            }
+            _ => self.add_cube(tokens)?,
+        }
+        Ok(false)
+    }
+
+    /// Adds a row of the truth table of the last `.names` construct.
+    fn add_cube(&mut self, tokens: &[&str]) -> Result<(), Error> {
+        let table = self
+            .tables
+            .last_mut()
+            .ok_or_else(|| Error::new("cube outside of table"))?;
+        let (cube, value) = match tokens {
+            [value] if table.inputs.is_empty() => ("", *value),
+            [cube, value] => (*cube, *value),
+            _ => return Err(Error::new("invalid cube")),
+        };
+        if cube.len() != table.inputs.len() || !cube.chars().all(|c| "01-".contains(c)) {
+            return Err(Error::new("invalid cube"));
+        }
+        let on_set = match value {
+            "1" => true,
+            "0" => false,
+            _ => return Err(Error::new("invalid cube")),
+        };
+        if !table.cubes.is_empty() && table.on_set != on_set {
+            return Err(Error::new("mixed on-set and off-set"));
+        }
+        table.on_set = on_set;
+        table.cubes.push(cube.to_string());
+        Ok(())
+    }
+}
+
+impl<C: Callbacks> Solver<C> {
+    /// Encodes the combinational logic of the network with the gate helpers
+    /// and returns the map from net names to their literals. Each primary
+    /// input, latch output and table output gets a fresh variable in this
+    /// order, and each table is encoded as the disjunction of conjunctions.
+    /// Returns an error if a net is used but not defined, or defined twice.
+    /// # Examples
+    /// ```
+    /// let text = ".model xor\n.inputs a b\n.outputs c\n.names a b c\n01 1\n10 1\n.end\n";
+    /// let network = cadical::BlifNetwork::parse(text).unwrap();
+    /// let mut sat: cadical::Solver = Default::default();
+    /// let nets = sat.add_blif(&network).unwrap();
+    /// assert_eq!(sat.solve_with([nets["a"], nets["b"], nets["c"]]), Some(false));
+    /// assert_eq!(sat.solve_with([nets["a"], -nets["b"], nets["c"]]), Some(true));
+    /// ```
+    pub fn add_blif(&mut self, network: &BlifNetwork) -> Result<HashMap<String, i32>, Error> {
+        let defined = network.inputs.iter();
+        let defined = defined.chain(network.latches.iter().map(|(_, output)| output));
+        let defined = defined.chain(network.tables.iter().map(|table| &table.output));
+        let mut nets: HashMap<String, i32> = HashMap::new();
+        for net in defined {
+            if nets.contains_key(net) {
+                return Err(Error::new("net defined twice"));
+            }
+            nets.insert(net.clone(), self.try_new_variable()?);
+        }
+        let used = network.outputs.iter();
+        let used = used.chain(network.latches.iter().map(|(input, _)| input));
+        let mut used = used.chain(network.tables.iter().flat_map(|table| table.inputs.iter()));
+        if !used.all(|net| nets.contains_key(net)) {
+            return Err(Error::new("undefined net"));
+        }
+
+        for table in network.tables.iter() {
+            let inputs: Vec<i32> = table.inputs.iter().map(|net| nets[net]).collect();
+            let mut cubes = Vec::new();
+            for cube in table.cubes.iter() {
+                let lits: Vec<i32> = cube
+                    .chars()
+                    .zip(inputs.iter())
+                    .filter(|&(c, _)| c != '-')
+                    .map(|(c, &lit)| if c == '1' { lit } else { -lit })
+                    .collect();
+                cubes.push(if lits.len() == 1 {
+                    lits[0]
+                } else {
+                    self.and_gate(lits)
+                });
+            }
+            // the output is the disjunction of the cubes, or its negation
+            let out = nets[&table.output];
+            let out = if table.on_set { out } else { -out };
+            let negated: Vec<i32> = cubes.iter().map(|&lit| -lit).collect();
+            self.define_and(-out, &negated);
+        }
+        Ok(nets)
+    }
+
+    /// Reads a network in BLIF format from the given file and encodes it
+    /// with `add_blif`, returning the map from net names to literals.
+    pub fn read_blif(&mut self, path: &Path) -> Result<HashMap<String, i32>, Error> {
+        let text = std::fs::read_to_string(path).map_err(|_| Error::new("cannot read file"))?;
+        self.add_blif(&BlifNetwork::parse(&text)?)
+    }
+}
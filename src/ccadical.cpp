@@ -1,11 +1,300 @@
-#include "../cadical/src/ccadical.cpp"
+// Proof files can grow past 2 GB, so off_t must be 64 bits wide on 32-bit
+// POSIX targets as well.
+#ifndef _FILE_OFFSET_BITS
+#define _FILE_OFFSET_BITS 64
+#endif
+
+// The sources of CaDiCaL are on the include path, either the vendored ones
+// or those of an installed library when the system feature is enabled. The
+// C interface of CaDiCaL is compiled into this wrapper, so it does not need
+// the ccadical.o object of an installed library.
+#include <ccadical.cpp>
+#include <internal.hpp>
+#include <climits>
+#include <cstdio>
+#include <new>
+#ifndef _WIN32
+#include <sys/types.h>
+#endif
 
 // This files converts some of the C++ interface of cadical to C.
 // These functions are not available in the C interface of cadical.
 // The C interface that cadical provides is in: cadical/src/ccadical.h
 
+namespace CaDiCaL
+{
+  // The Solver class declares Testing as a friend, which gives us access to
+  // the internal solver for features that are not part of the public API.
+  class Testing
+  {
+  public:
+    static Internal *internal(Solver *solver) { return solver->internal; }
+    static External *external(Solver *solver) { return solver->external; }
+    static State state(Solver *solver) { return solver->_state; }
+  };
+}
+
+#ifdef ACCOUNTING
+#include <atomic>
+#include <cstdlib>
+#include <new>
+
+// The native memory of a solver, counted by the replaced global allocation
+// functions while the account is active on the allocating thread. The
+// account is referenced by its solver and by every allocation charged to
+// it, so it is only freed after the last of them is gone.
+struct Account
+{
+  std::atomic<int64_t> current;
+  std::atomic<int64_t> peak;
+  std::atomic<int64_t> references;
+};
+
+static void account_unref(Account *account)
+{
+  if (--account->references == 0)
+  {
+    account->~Account();
+    free(account);
+  }
+}
+
+static thread_local Account *active_account = nullptr;
+
+// Every allocation is prefixed with the account it is charged to, so it
+// can be freed on any thread.
+union AllocationHeader
+{
+  struct
+  {
+    Account *account;
+    size_t size;
+  } info;
+  std::max_align_t align;
+};
+
+static void *accounted_alloc(size_t size)
+{
+  AllocationHeader *header =
+      (AllocationHeader *)malloc(sizeof(AllocationHeader) + size);
+  if (!header)
+    return nullptr;
+  Account *account = active_account;
+  header->info.account = account;
+  header->info.size = size;
+  if (account)
+  {
+    account->references++;
+    int64_t current = account->current += size;
+    int64_t peak = account->peak.load();
+    while (current > peak && !account->peak.compare_exchange_weak(peak, current))
+      ;
+  }
+  return header + 1;
+}
+
+static void accounted_free(void *ptr)
+{
+  if (!ptr)
+    return;
+  AllocationHeader *header = (AllocationHeader *)ptr - 1;
+  Account *account = header->info.account;
+  if (account)
+  {
+    account->current -= header->info.size;
+    account_unref(account);
+  }
+  free(header);
+}
+
+void *operator new(size_t size)
+{
+  void *ptr = accounted_alloc(size);
+  if (!ptr)
+    throw std::bad_alloc();
+  return ptr;
+}
+
+void *operator new[](size_t size)
+{
+  void *ptr = accounted_alloc(size);
+  if (!ptr)
+    throw std::bad_alloc();
+  return ptr;
+}
+
+void *operator new(size_t size, const std::nothrow_t &) noexcept
+{
+  return accounted_alloc(size);
+}
+
+void *operator new[](size_t size, const std::nothrow_t &) noexcept
+{
+  return accounted_alloc(size);
+}
+
+void operator delete(void *ptr) noexcept { accounted_free(ptr); }
+
+void operator delete[](void *ptr) noexcept { accounted_free(ptr); }
+
+void operator delete(void *ptr, const std::nothrow_t &) noexcept
+{
+  accounted_free(ptr);
+}
+
+void operator delete[](void *ptr, const std::nothrow_t &) noexcept
+{
+  accounted_free(ptr);
+}
+#endif
+
+// Forwards the clauses of a traversal to a C callback.
+struct ClauseCallback : CaDiCaL::ClauseIterator
+{
+  void *data;
+  int (*callback)(void *, const int *, size_t);
+
+  bool clause(const std::vector<int> &clause)
+  {
+    return callback(data, clause.data(), clause.size()) != 0;
+  }
+};
+
+// Publishes the counters of the solver into a block of five words guarded
+// by a sequence number, which is odd while the block is being written, so
+// that other threads can read consistent snapshots without locking. The
+// words are the sequence number, conflicts, decisions, restarts and memory.
+static void publish_stats(CaDiCaL::Solver *solver, int64_t *stats)
+{
+#ifdef NATOMIC64
+  // the Rust side has no live statistics on these targets
+  (void)solver;
+  (void)stats;
+#else
+  CaDiCaL::Internal *internal = CaDiCaL::Testing::internal(solver);
+  int64_t sequence = __atomic_load_n(stats, __ATOMIC_RELAXED);
+  __atomic_store_n(stats, sequence + 1, __ATOMIC_RELAXED);
+  __atomic_thread_fence(__ATOMIC_RELEASE);
+  __atomic_store_n(stats + 1, internal->stats.conflicts, __ATOMIC_RELAXED);
+  __atomic_store_n(stats + 2, internal->stats.decisions, __ATOMIC_RELAXED);
+  __atomic_store_n(stats + 3, internal->stats.restarts, __ATOMIC_RELAXED);
+#ifdef ACCOUNTING
+  int64_t memory = active_account ? active_account->current.load() : 0;
+#else
+  int64_t memory = 0;
+#endif
+  __atomic_store_n(stats + 4, memory, __ATOMIC_RELAXED);
+  __atomic_store_n(stats, sequence + 2, __ATOMIC_RELEASE);
+#endif
+}
+
+// Terminates the search once a flag set by another thread becomes nonzero,
+// and publishes the statistics of the solver each time it is polled. Both
+// the flag and the statistics are optional.
+struct FlagTerminator : CaDiCaL::Terminator
+{
+  CaDiCaL::Solver *solver;
+  const unsigned char *flag;
+  int64_t *stats;
+
+  bool terminate()
+  {
+    if (stats)
+      publish_stats(solver, stats);
+    return flag && __atomic_load_n(flag, __ATOMIC_RELAXED) != 0;
+  }
+};
+
+// Returns the number of different decision levels of the literals of a
+// clause, and stores the second highest level in jump.
+static int clause_glue(CaDiCaL::Solver *solver, const int *clause, size_t len,
+                       int *jump)
+{
+  CaDiCaL::Internal *internal = CaDiCaL::Testing::internal(solver);
+  CaDiCaL::External *external = CaDiCaL::Testing::external(solver);
+  std::vector<int> levels;
+  for (size_t i = 0; i < len; i++)
+  {
+    int ilit = external->e2i[abs(clause[i])];
+    levels.push_back(ilit ? internal->var(ilit).level : 0);
+  }
+  std::sort(levels.begin(), levels.end());
+  *jump = len > 1 ? levels[len - 2] : 0;
+  return std::unique(levels.begin(), levels.end()) - levels.begin();
+}
+
+// Collects the literals of learned clauses in a reused buffer and forwards
+// them together with their length to a C callback, dropping the clauses
+// with too high glue before crossing the language boundary.
+struct LearnCallback : CaDiCaL::Learner
+{
+  CaDiCaL::Solver *solver;
+  void *data;
+  int max_length;
+  int max_glue;
+  void (*callback)(void *, const int *, size_t);
+  std::vector<int> clause;
+
+  bool learning(int size)
+  {
+    if (size > max_length)
+      return false;
+    clause.reserve(size);
+    return true;
+  }
+
+  void learn(int lit)
+  {
+    if (lit)
+      clause.push_back(lit);
+    else
+    {
+      int jump;
+      if (max_glue == INT_MAX ||
+          clause_glue(solver, clause.data(), clause.size(), &jump) <= max_glue)
+        callback(data, clause.data(), clause.size());
+      clause.clear();
+    }
+  }
+};
+
+// Seeking in proof files with 64-bit offsets, since long is only 32 bits
+// wide on Windows.
+static int proof_seek(FILE *file, int64_t offset, int whence)
+{
+#ifdef _WIN32
+  return _fseeki64(file, offset, whence);
+#else
+  return fseeko(file, (off_t)offset, whence);
+#endif
+}
+
+static int64_t proof_tell(FILE *file)
+{
+#ifdef _WIN32
+  return _ftelli64(file);
+#else
+  return (int64_t)ftello(file);
+#endif
+}
+
+// The functions below that allocate memory catch std::bad_alloc instead of
+// letting it unwind into Rust, and return -1 or an error message instead.
+// The solver might be inconsistent after such a failure.
 extern "C"
 {
+  int ccadical_solve_checked(CCaDiCaL *wrapper)
+  {
+    try
+    {
+      return ((Wrapper *)wrapper)->solver->solve();
+    }
+    catch (const std::bad_alloc &)
+    {
+      return -1;
+    }
+  }
+
   int ccadical_status(CCaDiCaL *wrapper)
   {
     return ((Wrapper *)wrapper)->solver->status();
@@ -19,7 +308,14 @@ extern "C"
   const char *ccadical_read_dimacs(CCaDiCaL *wrapper, const char *path,
                                    int &vars, int strict)
   {
-    return ((Wrapper *)wrapper)->solver->read_dimacs(path, vars, strict);
+    try
+    {
+      return ((Wrapper *)wrapper)->solver->read_dimacs(path, vars, strict);
+    }
+    catch (const std::bad_alloc &)
+    {
+      return "out of memory";
+    }
   }
 
   const char *ccadical_write_dimacs(CCaDiCaL *wrapper, const char *path,
@@ -39,8 +335,396 @@ extern "C"
     return ((Wrapper *)wrapper)->solver->limit(name, val);
   }
 
-  void ccadical_reserve(CCaDiCaL *wrapper, int min_max_var = 0)
+  void ccadical_reset_assumptions(CCaDiCaL *wrapper)
+  {
+    ((Wrapper *)wrapper)->solver->reset_assumptions();
+  }
+
+  void ccadical_reset_constraint(CCaDiCaL *wrapper)
+  {
+    ((Wrapper *)wrapper)->solver->reset_constraint();
+  }
+
+  int ccadical_set_option2(CCaDiCaL *wrapper,
+                           const char *name, int val)
+  {
+    return ((Wrapper *)wrapper)->solver->set(name, val);
+  }
+
+  int ccadical_option_range(CCaDiCaL *, const char *name, int *min, int *max)
+  {
+    const CaDiCaL::Option *option = CaDiCaL::Options::has(name);
+    if (!option)
+      return 0;
+    *min = option->lo;
+    *max = option->hi;
+    return 1;
+  }
+
+  int ccadical_option_default(CCaDiCaL *, const char *name)
+  {
+    const CaDiCaL::Option *option = CaDiCaL::Options::has(name);
+    return option ? option->def : 0;
+  }
+
+  const char *ccadical_option_name(CCaDiCaL *, int index)
+  {
+    if (index < 0 || index >= CaDiCaL::Options::end() - CaDiCaL::Options::begin())
+      return nullptr;
+    return CaDiCaL::Options::begin()[index].name;
+  }
+
+  // The public set function only accepts the output options after the
+  // configuring state and aborts on the others, so those are rejected here.
+  int ccadical_set_option_live(CCaDiCaL *wrapper, const char *name, int val)
+  {
+    CaDiCaL::Solver *solver = ((Wrapper *)wrapper)->solver;
+    if (CaDiCaL::Testing::state(solver) != CaDiCaL::CONFIGURING &&
+        strcmp(name, "log") && strcmp(name, "quiet") &&
+        strcmp(name, "report") && strcmp(name, "verbose"))
+      return 0;
+    return solver->set(name, val);
+  }
+
+  void ccadical_set_terminate_flag(CCaDiCaL *wrapper, const unsigned char *flag,
+                                   int64_t *stats)
+  {
+    CaDiCaL::Solver *solver = ((Wrapper *)wrapper)->solver;
+    CaDiCaL::Terminator *terminator =
+        CaDiCaL::Testing::external(solver)->terminator;
+    solver->disconnect_terminator();
+    delete dynamic_cast<FlagTerminator *>(terminator);
+    if (flag || stats)
+    {
+      FlagTerminator *poll = new FlagTerminator();
+      poll->solver = solver;
+      poll->flag = flag;
+      poll->stats = stats;
+      solver->connect_terminator(poll);
+    }
+  }
+
+  int ccadical_copy(CCaDiCaL *wrapper, CCaDiCaL *other)
+  {
+    try
+    {
+      ((Wrapper *)wrapper)->solver->copy(*((Wrapper *)other)->solver);
+    }
+    catch (const std::bad_alloc &)
+    {
+      return -1;
+    }
+    return 0;
+  }
+
+  void ccadical_publish_stats(CCaDiCaL *wrapper, int64_t *stats)
+  {
+    publish_stats(((Wrapper *)wrapper)->solver, stats);
+  }
+
+  void ccadical_set_learn_length(CCaDiCaL *wrapper, void *data, int max_length,
+                                 int max_glue,
+                                 void (*callback)(void *, const int *, size_t))
+  {
+    CaDiCaL::Solver *solver = ((Wrapper *)wrapper)->solver;
+    CaDiCaL::Learner *learner = CaDiCaL::Testing::external(solver)->learner;
+    solver->disconnect_learner();
+    delete dynamic_cast<LearnCallback *>(learner);
+    if (callback)
+    {
+      LearnCallback *forward = new LearnCallback();
+      forward->solver = solver;
+      forward->data = data;
+      forward->max_length = max_length;
+      forward->max_glue = max_glue;
+      forward->callback = callback;
+      solver->connect_learner(forward);
+    }
+  }
+
+  int ccadical_assume_all(CCaDiCaL *wrapper, const int *lits, size_t len)
+  {
+    CaDiCaL::Solver *solver = ((Wrapper *)wrapper)->solver;
+    try
+    {
+      for (size_t i = 0; i < len; i++)
+        solver->assume(lits[i]);
+    }
+    catch (const std::bad_alloc &)
+    {
+      return -1;
+    }
+    return 0;
+  }
+
+  int ccadical_add_clause(CCaDiCaL *wrapper, const int *lits, size_t len)
+  {
+    CaDiCaL::Solver *solver = ((Wrapper *)wrapper)->solver;
+    try
+    {
+      for (size_t i = 0; i < len; i++)
+        solver->add(lits[i]);
+      solver->add(0);
+    }
+    catch (const std::bad_alloc &)
+    {
+      return -1;
+    }
+    return 0;
+  }
+
+  int ccadical_reserve(CCaDiCaL *wrapper, int min_max_var = 0)
+  {
+    try
+    {
+      ((Wrapper *)wrapper)->solver->reserve(min_max_var);
+    }
+    catch (const std::bad_alloc &)
+    {
+      return -1;
+    }
+    return 0;
+  }
+
+  int ccadical_reserve_clauses(CCaDiCaL *wrapper, size_t count)
+  {
+    CaDiCaL::Internal *internal =
+        CaDiCaL::Testing::internal(((Wrapper *)wrapper)->solver);
+    try
+    {
+      internal->clauses.reserve(internal->clauses.size() + count);
+    }
+    catch (const std::bad_alloc &)
+    {
+      return -1;
+    }
+    return 0;
+  }
+
+  int ccadical_traverse_clauses(CCaDiCaL *wrapper, void *data,
+                                int (*callback)(void *, const int *, size_t))
+  {
+    ClauseCallback iterator;
+    iterator.data = data;
+    iterator.callback = callback;
+    return ((Wrapper *)wrapper)->solver->traverse_clauses(iterator);
+  }
+
+  int ccadical_simplify(CCaDiCaL *wrapper, int rounds)
+  {
+    try
+    {
+      return ((Wrapper *)wrapper)->solver->simplify(rounds);
+    }
+    catch (const std::bad_alloc &)
+    {
+      return -1;
+    }
+  }
+
+  // Only active variables are bumped, the fixed, eliminated and substituted
+  // ones are not on the decision queue anymore.
+  void ccadical_prioritize(CCaDiCaL *wrapper, int var)
+  {
+    CaDiCaL::Solver *solver = ((Wrapper *)wrapper)->solver;
+    CaDiCaL::External *external = CaDiCaL::Testing::external(solver);
+    if (var <= 0 || var > external->max_var)
+      return;
+    int ilit = external->e2i[var];
+    if (ilit <= 0)
+      return;
+    CaDiCaL::Internal *internal = CaDiCaL::Testing::internal(solver);
+    if (!internal->flags(ilit).active())
+      return;
+    internal->bump_queue(ilit);
+    internal->bump_variable_score(ilit);
+  }
+
+  int64_t ccadical_conflicts(CCaDiCaL *wrapper)
+  {
+    return CaDiCaL::Testing::internal(((Wrapper *)wrapper)->solver)->stats.conflicts;
+  }
+
+  int64_t ccadical_decisions(CCaDiCaL *wrapper)
+  {
+    return CaDiCaL::Testing::internal(((Wrapper *)wrapper)->solver)->stats.decisions;
+  }
+
+  int ccadical_state(CCaDiCaL *wrapper)
+  {
+    return CaDiCaL::Testing::state(((Wrapper *)wrapper)->solver);
+  }
+
+  int ccadical_is_inconsistent(CCaDiCaL *wrapper)
+  {
+    return CaDiCaL::Testing::internal(((Wrapper *)wrapper)->solver)->unsat;
+  }
+
+#ifndef QUIET
+  double ccadical_profile(CCaDiCaL *wrapper, const char *name)
+  {
+    CaDiCaL::Internal *internal =
+        CaDiCaL::Testing::internal(((Wrapper *)wrapper)->solver);
+#define PROFILE(NAME, LEVEL)    \
+  if (!strcmp(name, #NAME))     \
+    return internal->profiles.NAME.value;
+    PROFILES
+#undef PROFILE
+    return 0;
+  }
+#endif
+
+  int64_t ccadical_restarts(CCaDiCaL *wrapper)
+  {
+    return CaDiCaL::Testing::internal(((Wrapper *)wrapper)->solver)->stats.restarts;
+  }
+
+  int ccadical_level(CCaDiCaL *wrapper)
+  {
+    return CaDiCaL::Testing::internal(((Wrapper *)wrapper)->solver)->level;
+  }
+
+  int ccadical_decision(CCaDiCaL *wrapper)
+  {
+    CaDiCaL::Internal *internal =
+        CaDiCaL::Testing::internal(((Wrapper *)wrapper)->solver);
+    if (!internal->level)
+      return 0;
+    int ilit = internal->control[internal->level].decision;
+    return ilit ? internal->externalize(ilit) : 0;
+  }
+
+  // Returns the number of different decision levels of the literals of a
+  // clause that is being learned, and the level the solver backjumps to.
+  int ccadical_learned_levels(CCaDiCaL *wrapper, const int *clause,
+                              size_t len, int *jump)
+  {
+    return clause_glue(((Wrapper *)wrapper)->solver, clause, len, jump);
+  }
+
+  int64_t ccadical_propagations(CCaDiCaL *wrapper)
+  {
+    return CaDiCaL::Testing::internal(((Wrapper *)wrapper)->solver)->stats.propagations.search;
+  }
+
+  // The columns of the progress report lines, which are printed only in
+  // verbose builds, for structured report events.
+  void ccadical_report(CCaDiCaL *wrapper, int64_t *report)
+  {
+    CaDiCaL::Internal *internal =
+        CaDiCaL::Testing::internal(((Wrapper *)wrapper)->solver);
+    report[0] = internal->stats.conflicts;
+    report[1] = internal->stats.restarts;
+    report[2] = internal->stats.reductions;
+    report[3] = internal->level;
+    report[4] = internal->trail.size();
+    report[5] = internal->stats.current.irredundant;
+    report[6] = internal->stats.current.redundant;
+    report[7] = internal->active();
+    report[8] = internal->max_var;
+  }
+
+  int64_t ccadical_learned(CCaDiCaL *wrapper)
+  {
+    return CaDiCaL::Testing::internal(((Wrapper *)wrapper)->solver)->stats.learned.clauses;
+  }
+
+  void *ccadical_proof_open()
+  {
+    return tmpfile();
+  }
+
+  // The solver does not own the proof file either, and the binary format
+  // is forced, which is the only one the Rust side parses.
+  int ccadical_trace_proof(CCaDiCaL *wrapper, void *file)
+  {
+    CaDiCaL::Solver *solver = ((Wrapper *)wrapper)->solver;
+    if (CaDiCaL::Testing::state(solver) != CaDiCaL::CONFIGURING)
+      return 0;
+    solver->set("binary", 1);
+    return solver->trace_proof((FILE *)file, "<proof>");
+  }
+
+  uint64_t ccadical_proof_size(CCaDiCaL *wrapper, void *file)
+  {
+    ((Wrapper *)wrapper)->solver->flush_proof_trace();
+    FILE *proof = (FILE *)file;
+    fflush(proof);
+    int64_t size = proof_tell(proof);
+    return size < 0 ? 0 : (uint64_t)size;
+  }
+
+  size_t ccadical_proof_read(CCaDiCaL *wrapper, void *file, uint64_t offset,
+                             uint8_t *buffer, size_t size)
+  {
+    ((Wrapper *)wrapper)->solver->flush_proof_trace();
+    FILE *proof = (FILE *)file;
+    fflush(proof);
+    if (offset > (uint64_t)INT64_MAX || proof_seek(proof, (int64_t)offset, SEEK_SET))
+      return 0;
+    size_t count = fread(buffer, 1, size, proof);
+    // the solver continues writing at the end
+    proof_seek(proof, 0, SEEK_END);
+    return count;
+  }
+
+  void ccadical_proof_close(void *file)
+  {
+    fclose((FILE *)file);
+  }
+
+#ifndef NTRACING
+  void *ccadical_trace_open(const char *path)
+  {
+    return fopen(path, "w");
+  }
+
+  // The solver does not own the file, which is closed by the caller after
+  // the solver has written its final calls in its destructor.
+  void ccadical_trace_api_calls(CCaDiCaL *wrapper, void *file)
+  {
+    ((Wrapper *)wrapper)->solver->trace_api_calls((FILE *)file);
+  }
+
+  void ccadical_trace_close(void *file)
+  {
+    fclose((FILE *)file);
+  }
+#endif
+
+#ifdef ACCOUNTING
+  void *ccadical_account_new()
+  {
+    // the account itself is not charged to any account
+    Account *account = new (malloc(sizeof(Account))) Account();
+    account->references = 1;
+    return account;
+  }
+
+  // Drops the reference of the solver, the account stays alive until the
+  // allocations charged to it are freed.
+  void ccadical_account_release(void *account)
+  {
+    account_unref((Account *)account);
+  }
+
+  // Charges the native allocations of the calling thread to the given
+  // account (or to none if null), and returns the previous one.
+  void *ccadical_account_enter(void *account)
+  {
+    Account *previous = active_account;
+    active_account = (Account *)account;
+    return previous;
+  }
+
+  int64_t ccadical_account_current(void *account)
+  {
+    return ((Account *)account)->current.load();
+  }
+
+  int64_t ccadical_account_peak(void *account)
   {
-    ((Wrapper *)wrapper)->solver->reserve(min_max_var);
+    return ((Account *)account)->peak.load();
   }
+#endif
 }
@@ -0,0 +1,1546 @@
+//! A small conflict-driven clause learning solver written in Rust, which
+//! replaces CaDiCaL when the `pure-rust` feature is enabled. It implements
+//! the same C interface as the wrapper of CaDiCaL, so the `Solver` API is
+//! unchanged, but it does no preprocessing or inprocessing at all: it uses
+//! two watched literals, first UIP learning with clause minimization, VSIDS
+//! scores, phase saving, Luby restarts and glue based clause deletion.
+//! Assumptions are decided first as in MiniSat, and the constraint clause
+//! is satisfied by deciding one of its literals after the assumptions, as
+//! in CaDiCaL, so the learned clauses do not depend on it. The learned and
+//! deleted clauses can be written as a binary DRAT proof.
+
+#![allow(unused_variables, clippy::missing_safety_doc)]
+
+use std::convert::TryFrom;
+use std::ffi::{CStr, CString, OsStr};
+use std::os::raw::{c_char, c_int, c_void};
+use std::path::PathBuf;
+use std::ptr::{null, null_mut};
+#[cfg(target_has_atomic = "64")]
+use std::sync::atomic::AtomicI64;
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::time::Instant;
+
+/// The options known to the solver with their default, minimum and maximum
+/// values. Only `phase` affects the search, the others are accepted for
+/// compatibility with CaDiCaL.
+const OPTIONS: [(&str, i32, i32, i32); 17] = [
+    ("chrono", 1, 0, 2),
+    ("elim", 1, 0, 1),
+    ("lucky", 1, 0, 1),
+    ("phase", 1, 0, 1),
+    ("probe", 1, 0, 1),
+    ("quiet", 0, 0, 1),
+    ("restart", 1, 0, 1),
+    ("seed", 0, 0, 2_000_000_000),
+    ("shuffle", 0, 0, 1),
+    ("shufflequeue", 1, 0, 1),
+    ("shufflerandom", 0, 0, 1),
+    ("shufflescores", 1, 0, 1),
+    ("stabilize", 1, 0, 1),
+    ("stabilizeonly", 0, 0, 1),
+    ("subsume", 1, 0, 1),
+    ("verbose", 0, 0, 3),
+    ("walk", 1, 0, 1),
+];
+
+/// The names of the options in the same order, terminated by a zero byte.
+const OPTION_NAMES: [&str; 17] = [
+    "chrono\0",
+    "elim\0",
+    "lucky\0",
+    "phase\0",
+    "probe\0",
+    "quiet\0",
+    "restart\0",
+    "seed\0",
+    "shuffle\0",
+    "shufflequeue\0",
+    "shufflerandom\0",
+    "shufflescores\0",
+    "stabilize\0",
+    "stabilizeonly\0",
+    "subsume\0",
+    "verbose\0",
+    "walk\0",
+];
+
+/// The reason of decisions and of the literals fixed at the root level.
+const NO_REASON: usize = usize::MAX;
+
+/// The number of conflicts in the unit of the Luby restart sequence.
+const RESTART_INTERVAL: u64 = 100;
+
+/// The answer of a search between two restarts.
+enum Answer {
+    Sat,
+    Unsat,
+    Unknown,
+    Restart,
+}
+
+/// The next decision of the search.
+enum Decision {
+    Literal(i32),
+    Failed(i32),
+    FailedConstraint,
+    Satisfied,
+}
+
+struct Clause {
+    lits: Vec<i32>,
+    learnt: bool,
+    glue: u32,
+    deleted: bool,
+}
+
+/// A binary max-heap of the variables ordered by their scores.
+#[derive(Default)]
+struct Heap {
+    heap: Vec<usize>,
+    positions: Vec<usize>,
+}
+
+impl Heap {
+    const ABSENT: usize = usize::MAX;
+
+    fn contains(&self, var: usize) -> bool {
+        self.positions.get(var).copied().unwrap_or(Heap::ABSENT) != Heap::ABSENT
+    }
+
+    fn insert(&mut self, var: usize, scores: &[f64]) {
+        if self.positions.len() <= var {
+            self.positions.resize(var + 1, Heap::ABSENT);
+        }
+        if !self.contains(var) {
+            self.positions[var] = self.heap.len();
+            self.heap.push(var);
+            self.up(self.heap.len() - 1, scores);
+        }
+    }
+
+    fn pop(&mut self, scores: &[f64]) -> Option<usize> {
+        let top = *self.heap.first()?;
+        let last = self.heap.pop().unwrap();
+        self.positions[top] = Heap::ABSENT;
+        if !self.heap.is_empty() {
+            self.heap[0] = last;
+            self.positions[last] = 0;
+            self.down(0, scores);
+        }
+        Some(top)
+    }
+
+    /// Restores the heap property after the score of the variable grew.
+    fn bumped(&mut self, var: usize, scores: &[f64]) {
+        if self.contains(var) {
+            self.up(self.positions[var], scores);
+        }
+    }
+
+    fn up(&mut self, mut pos: usize, scores: &[f64]) {
+        let var = self.heap[pos];
+        while pos > 0 {
+            let parent = (pos - 1) / 2;
+            if scores[self.heap[parent]] >= scores[var] {
+                break;
+            }
+            self.heap[pos] = self.heap[parent];
+            self.positions[self.heap[pos]] = pos;
+            pos = parent;
+        }
+        self.heap[pos] = var;
+        self.positions[var] = pos;
+    }
+
+    fn down(&mut self, mut pos: usize, scores: &[f64]) {
+        let var = self.heap[pos];
+        loop {
+            let mut child = 2 * pos + 1;
+            if child >= self.heap.len() {
+                break;
+            }
+            if child + 1 < self.heap.len()
+                && scores[self.heap[child + 1]] > scores[self.heap[child]]
+            {
+                child += 1;
+            }
+            if scores[self.heap[child]] <= scores[var] {
+                break;
+            }
+            self.heap[pos] = self.heap[child];
+            self.positions[self.heap[pos]] = pos;
+            pos = child;
+        }
+        self.heap[pos] = var;
+        self.positions[var] = pos;
+    }
+}
+
+/// Returns the value of the literal: 1 if true, -1 if false, and 0 if it is
+/// unassigned.
+#[inline]
+fn value_of(values: &[i8], lit: i32) -> i8 {
+    let val = values[lit.unsigned_abs() as usize];
+    if lit < 0 {
+        -val
+    } else {
+        val
+    }
+}
+
+/// Returns the index of the watch list of the literal.
+#[inline]
+fn watch_index(lit: i32) -> usize {
+    2 * lit.unsigned_abs() as usize + (lit < 0) as usize
+}
+
+/// Returns the element of the Luby sequence 1, 1, 2, 1, 1, 2, 4, ... at the
+/// given index.
+fn luby(mut index: u64) -> u64 {
+    let (mut size, mut seq) = (1, 0);
+    while size < index + 1 {
+        seq += 1;
+        size = 2 * size + 1;
+    }
+    while size - 1 != index {
+        size = (size - 1) >> 1;
+        seq -= 1;
+        index %= size;
+    }
+    1 << seq
+}
+
+pub struct Cdcl {
+    // the interface state with external literals: the internal variable of
+    // each external variable, the clauses as added, and the literals of the
+    // clause, the assumptions and the constraint under construction
+    e2i: Vec<usize>,
+    i2e: Vec<i32>,
+    formula: Vec<Vec<i32>>,
+    clause: Vec<i32>,
+    assumptions: Vec<i32>,
+    constraint: Vec<i32>,
+    constraint_clause: Option<Vec<i32>>,
+    frozen: Vec<u32>,
+    options: Vec<(String, i32)>,
+    configuring: bool,
+    inconsistent: bool,
+    status: i32,
+    error: CString,
+    model: Vec<i8>,
+    failed: Vec<i32>,
+    constraint_failed: bool,
+
+    // the search state indexed by internal variables and literals
+    values: Vec<i8>,
+    levels: Vec<u32>,
+    reasons: Vec<usize>,
+    phases: Vec<bool>,
+    seen: Vec<bool>,
+    scores: Vec<f64>,
+    score_increment: f64,
+    heap: Heap,
+    watches: Vec<Vec<usize>>,
+    clauses: Vec<Clause>,
+    trail: Vec<i32>,
+    control: Vec<usize>,
+    propagated: usize,
+    num_learnt: usize,
+    reduce_limit: usize,
+
+    conflict_limit: i32,
+    decision_limit: i32,
+    conflicts: i64,
+    decisions: i64,
+    propagations: i64,
+    restarts: i64,
+    reductions: i64,
+    learned: i64,
+    solve_time: f64,
+    search_time: f64,
+
+    terminate_data: *const c_void,
+    terminate_cbs: Option<extern "C" fn(*const c_void) -> c_int>,
+    terminate_flag: *const u8,
+    terminate_stats: *mut i64,
+    learn_data: *const c_void,
+    learn_max_length: c_int,
+    learn_max_glue: c_int,
+    learn_cbs: Option<extern "C" fn(*const c_void, *const c_int)>,
+    learn_length_cbs: Option<extern "C" fn(*const c_void, *const c_int, usize)>,
+    proof: *mut Vec<u8>,
+}
+
+impl Cdcl {
+    fn new() -> Self {
+        Cdcl {
+            e2i: vec![0],
+            i2e: vec![0],
+            formula: Vec::new(),
+            clause: Vec::new(),
+            assumptions: Vec::new(),
+            constraint: Vec::new(),
+            constraint_clause: None,
+            frozen: Vec::new(),
+            options: Vec::new(),
+            configuring: true,
+            inconsistent: false,
+            status: 0,
+            error: CString::default(),
+            model: Vec::new(),
+            failed: Vec::new(),
+            constraint_failed: false,
+            values: vec![0],
+            levels: vec![0],
+            reasons: vec![NO_REASON],
+            phases: vec![true],
+            seen: vec![false],
+            scores: vec![0.0],
+            score_increment: 1.0,
+            heap: Heap::default(),
+            watches: vec![Vec::new(), Vec::new()],
+            clauses: Vec::new(),
+            trail: Vec::new(),
+            control: Vec::new(),
+            propagated: 0,
+            num_learnt: 0,
+            reduce_limit: 2000,
+            conflict_limit: -1,
+            decision_limit: -1,
+            conflicts: 0,
+            decisions: 0,
+            propagations: 0,
+            restarts: 0,
+            reductions: 0,
+            learned: 0,
+            solve_time: 0.0,
+            search_time: 0.0,
+            terminate_data: null(),
+            terminate_cbs: None,
+            terminate_flag: null(),
+            terminate_stats: null_mut(),
+            learn_data: null(),
+            learn_max_length: 0,
+            learn_max_glue: 0,
+            learn_cbs: None,
+            learn_length_cbs: None,
+            proof: null_mut(),
+        }
+    }
+
+    /// Leaves the configuring state and invalidates the last answer.
+    fn touch(&mut self) {
+        self.configuring = false;
+        self.status = 0;
+    }
+
+    fn option(&self, name: &str) -> i32 {
+        match self.options.iter().find(|(key, _)| key == name) {
+            Some(&(_, val)) => val,
+            None => OPTIONS
+                .iter()
+                .find(|option| option.0 == name)
+                .map_or(0, |option| option.1),
+        }
+    }
+
+    fn max_var(&self) -> i32 {
+        self.e2i.len() as i32 - 1
+    }
+
+    /// Creates a new internal variable for the given external variable.
+    fn new_var(&mut self, external: i32) -> usize {
+        let var = self.values.len();
+        self.i2e.push(external);
+        self.values.push(0);
+        self.levels.push(0);
+        self.reasons.push(NO_REASON);
+        self.phases.push(self.option("phase") != 0);
+        self.seen.push(false);
+        self.scores.push(0.0);
+        self.watches.push(Vec::new());
+        self.watches.push(Vec::new());
+        self.heap.insert(var, &self.scores);
+        var
+    }
+
+    /// Returns the internal literal of an external literal, importing all
+    /// external variables up to it.
+    fn internal(&mut self, lit: i32) -> i32 {
+        let evar = lit.unsigned_abs() as usize;
+        while self.e2i.len() <= evar {
+            let ivar = self.new_var(self.e2i.len() as i32);
+            self.e2i.push(ivar);
+        }
+        let ivar = self.e2i[evar] as i32;
+        if lit < 0 {
+            -ivar
+        } else {
+            ivar
+        }
+    }
+
+    /// Returns the external literal of an internal one.
+    fn external(&self, lit: i32) -> i32 {
+        let evar = self.i2e[lit.unsigned_abs() as usize];
+        if lit < 0 {
+            -evar
+        } else {
+            evar
+        }
+    }
+
+    fn value(&self, lit: i32) -> i8 {
+        value_of(&self.values, lit)
+    }
+
+    /// Writes an added (`a`) or deleted (`d`) clause of internal literals
+    /// to the binary DRAT proof, if it is traced.
+    fn trace(&self, step: u8, lits: &[i32]) {
+        if self.proof.is_null() {
+            return;
+        }
+        let proof = unsafe { &mut *self.proof };
+        proof.push(step);
+        for &lit in lits.iter() {
+            let lit = self.external(lit);
+            let mut code = 2 * lit.unsigned_abs() + (lit < 0) as u32;
+            while code > 0x7f {
+                proof.push((code & 0x7f) as u8 | 0x80);
+                code >>= 7;
+            }
+            proof.push(code as u8);
+        }
+        proof.push(0);
+    }
+
+    fn enqueue(&mut self, lit: i32, reason: usize) {
+        let var = lit.unsigned_abs() as usize;
+        self.values[var] = if lit > 0 { 1 } else { -1 };
+        self.levels[var] = self.control.len() as u32;
+        self.reasons[var] = reason;
+        self.trail.push(lit);
+    }
+
+    fn attach(&mut self, lits: Vec<i32>, learnt: bool, glue: u32) -> usize {
+        let index = self.clauses.len();
+        self.watches[watch_index(lits[0])].push(index);
+        self.watches[watch_index(lits[1])].push(index);
+        self.clauses.push(Clause {
+            lits,
+            learnt,
+            glue,
+            deleted: false,
+        });
+        index
+    }
+
+    /// Adds an irredundant clause of internal literals at the root level,
+    /// after removing duplicate and fixed literals.
+    fn add_internal(&mut self, mut lits: Vec<i32>) {
+        if self.inconsistent {
+            return;
+        }
+        lits.sort_unstable_by_key(|&lit| (lit.unsigned_abs(), lit > 0));
+        lits.dedup();
+        if lits.windows(2).any(|pair| pair[0] == -pair[1]) {
+            return;
+        }
+        if lits.iter().any(|&lit| self.value(lit) > 0) {
+            return;
+        }
+        lits.retain(|&lit| value_of(&self.values, lit) == 0);
+        match lits.len() {
+            0 => self.inconsistent = true,
+            1 => self.enqueue(lits[0], NO_REASON),
+            _ => {
+                self.attach(lits, false, 0);
+            }
+        }
+    }
+
+    fn add_clause(&mut self, clause: Vec<i32>) {
+        let lits = clause.iter().map(|&lit| self.internal(lit)).collect();
+        self.formula.push(clause);
+        self.add_internal(lits);
+    }
+
+    fn bump(&mut self, var: usize) {
+        self.scores[var] += self.score_increment;
+        if self.scores[var] > 1e100 {
+            for score in self.scores.iter_mut() {
+                *score *= 1e-100;
+            }
+            self.score_increment *= 1e-100;
+        }
+        self.heap.bumped(var, &self.scores);
+    }
+
+    fn backtrack(&mut self, level: usize) {
+        if self.control.len() <= level {
+            return;
+        }
+        let start = self.control[level];
+        for lit in self.trail.split_off(start) {
+            let var = lit.unsigned_abs() as usize;
+            self.values[var] = 0;
+            self.reasons[var] = NO_REASON;
+            self.phases[var] = lit > 0;
+            self.heap.insert(var, &self.scores);
+        }
+        self.control.truncate(level);
+        self.propagated = start;
+    }
+
+    /// Propagates the assigned literals with the watch lists, and returns
+    /// the falsified clause if there is a conflict.
+    fn propagate(&mut self) -> Option<usize> {
+        while self.propagated < self.trail.len() {
+            let lit = self.trail[self.propagated];
+            self.propagated += 1;
+            self.propagations += 1;
+            let falsified = -lit;
+            let mut watches = std::mem::take(&mut self.watches[watch_index(falsified)]);
+            let mut conflict = None;
+            let (mut index, mut kept) = (0, 0);
+            while index < watches.len() {
+                let clause = watches[index];
+                index += 1;
+                if self.clauses[clause].deleted {
+                    continue;
+                }
+                let lits = &mut self.clauses[clause].lits;
+                if lits[0] == falsified {
+                    lits.swap(0, 1);
+                }
+                let first = lits[0];
+                if value_of(&self.values, first) > 0 {
+                    watches[kept] = clause;
+                    kept += 1;
+                    continue;
+                }
+                let values = &self.values;
+                if let Some(pos) = (2..lits.len()).find(|&pos| value_of(values, lits[pos]) >= 0) {
+                    lits.swap(1, pos);
+                    let other = lits[1];
+                    self.watches[watch_index(other)].push(clause);
+                    continue;
+                }
+                watches[kept] = clause;
+                kept += 1;
+                if value_of(&self.values, first) < 0 {
+                    conflict = Some(clause);
+                    while index < watches.len() {
+                        watches[kept] = watches[index];
+                        kept += 1;
+                        index += 1;
+                    }
+                } else {
+                    self.enqueue(first, clause);
+                }
+            }
+            watches.truncate(kept);
+            self.watches[watch_index(falsified)] = watches;
+            if conflict.is_some() {
+                return conflict;
+            }
+        }
+        None
+    }
+
+    /// Derives the first UIP clause of the conflict, minimizes it, and
+    /// returns it with the asserting literal first and a literal of the
+    /// backjump level second.
+    fn analyze(&mut self, conflict: usize) -> Vec<i32> {
+        let level = self.control.len() as u32;
+        let mut learnt = vec![0];
+        let (mut open, mut lit, mut reason) = (0, 0, conflict);
+        let mut index = self.trail.len();
+        loop {
+            for pos in 0..self.clauses[reason].lits.len() {
+                let other = self.clauses[reason].lits[pos];
+                let var = other.unsigned_abs() as usize;
+                if other == lit || self.seen[var] || self.levels[var] == 0 {
+                    continue;
+                }
+                self.seen[var] = true;
+                self.bump(var);
+                if self.levels[var] >= level {
+                    open += 1;
+                } else {
+                    learnt.push(other);
+                }
+            }
+            loop {
+                index -= 1;
+                if self.seen[self.trail[index].unsigned_abs() as usize] {
+                    break;
+                }
+            }
+            lit = self.trail[index];
+            let var = lit.unsigned_abs() as usize;
+            self.seen[var] = false;
+            open -= 1;
+            if open == 0 {
+                break;
+            }
+            reason = self.reasons[var];
+        }
+        learnt[0] = -lit;
+
+        // remove the literals implied by the other literals of the clause
+        let mut minimized = vec![learnt[0]];
+        for &other in learnt[1..].iter() {
+            let reason = self.reasons[other.unsigned_abs() as usize];
+            let redundant = reason != NO_REASON
+                && self.clauses[reason].lits[1..].iter().all(|&lit| {
+                    let var = lit.unsigned_abs() as usize;
+                    self.seen[var] || self.levels[var] == 0
+                });
+            if !redundant {
+                minimized.push(other);
+            }
+        }
+        for &other in learnt[1..].iter() {
+            self.seen[other.unsigned_abs() as usize] = false;
+        }
+
+        if minimized.len() > 2 {
+            let level = |lit: &i32| self.levels[lit.unsigned_abs() as usize];
+            let pos = (1..minimized.len()).max_by_key(|&pos| level(&minimized[pos]));
+            minimized.swap(1, pos.unwrap());
+        }
+        minimized
+    }
+
+    /// Returns the number of different decision levels of the literals.
+    fn glue(&self, lits: &[i32]) -> u32 {
+        let mut levels: Vec<u32> = lits
+            .iter()
+            .map(|&lit| self.levels[lit.unsigned_abs() as usize])
+            .collect();
+        levels.sort_unstable();
+        levels.dedup();
+        levels.len() as u32
+    }
+
+    /// Backjumps and adds the learned clause, which asserts its first
+    /// literal.
+    fn add_learnt(&mut self, learnt: Vec<i32>, glue: u32) {
+        let jump = learnt
+            .get(1)
+            .map_or(0, |&lit| self.levels[lit.unsigned_abs() as usize]);
+        self.backtrack(jump as usize);
+        let first = learnt[0];
+        if learnt.len() == 1 {
+            self.enqueue(first, NO_REASON);
+        } else {
+            let clause = self.attach(learnt, true, glue);
+            self.num_learnt += 1;
+            self.enqueue(first, clause);
+        }
+        self.score_increment /= 0.95;
+    }
+
+    /// Collects the assumptions responsible for falsifying the given
+    /// assumption.
+    fn analyze_final(&mut self, lit: i32) -> Vec<i32> {
+        let mut failed = vec![lit];
+        if self.control.is_empty() {
+            return failed;
+        }
+        let var = lit.unsigned_abs() as usize;
+        self.seen[var] = true;
+        self.collect_failed(&mut failed);
+        self.seen[var] = false;
+        failed
+    }
+
+    /// Collects the assumptions responsible for falsifying all literals of
+    /// the constraint.
+    fn analyze_constraint(&mut self, constraint: &[i32]) -> Vec<i32> {
+        let mut failed = Vec::new();
+        if self.control.is_empty() {
+            return failed;
+        }
+        for &lit in constraint.iter() {
+            let var = lit.unsigned_abs() as usize;
+            if self.levels[var] > 0 {
+                self.seen[var] = true;
+            }
+        }
+        self.collect_failed(&mut failed);
+        failed
+    }
+
+    /// Adds the decisions implying the seen literals above the root level
+    /// to the failed assumptions, and clears their seen flags.
+    fn collect_failed(&mut self, failed: &mut Vec<i32>) {
+        for index in (self.control[0]..self.trail.len()).rev() {
+            let other = self.trail[index];
+            let var = other.unsigned_abs() as usize;
+            if !self.seen[var] {
+                continue;
+            }
+            let reason = self.reasons[var];
+            if reason == NO_REASON {
+                failed.push(other);
+            } else {
+                for &lit in self.clauses[reason].lits[1..].iter() {
+                    let var = lit.unsigned_abs() as usize;
+                    if self.levels[var] > 0 {
+                        self.seen[var] = true;
+                    }
+                }
+            }
+            self.seen[var] = false;
+        }
+    }
+
+    /// Deletes half of the learned clauses with the highest glue that are
+    /// not the reason of an assigned literal.
+    fn reduce(&mut self) {
+        self.reductions += 1;
+        let mut candidates: Vec<usize> = (0..self.clauses.len())
+            .filter(|&index| {
+                let clause = &self.clauses[index];
+                let var = clause
+                    .lits
+                    .first()
+                    .map_or(0, |lit| lit.unsigned_abs() as usize);
+                clause.learnt && !clause.deleted && clause.glue > 2 && self.reasons[var] != index
+            })
+            .collect();
+        candidates.sort_unstable_by_key(|&index| {
+            let clause = &self.clauses[index];
+            std::cmp::Reverse((clause.glue, clause.lits.len()))
+        });
+        for &index in candidates[..candidates.len() / 2].iter() {
+            self.trace(b'd', &self.clauses[index].lits);
+            let clause = &mut self.clauses[index];
+            clause.deleted = true;
+            clause.lits = Vec::new();
+            self.num_learnt -= 1;
+        }
+        self.reduce_limit += self.reduce_limit / 10 + 500;
+    }
+
+    /// Returns the next assumption that is not satisfied yet, a literal of
+    /// the constraint if it is not satisfied yet, or the unassigned variable
+    /// with the highest score in its saved phase.
+    fn next_decision(&mut self, assumptions: &[i32], constraint: Option<&[i32]>) -> Decision {
+        while self.control.len() < assumptions.len() {
+            let lit = assumptions[self.control.len()];
+            match self.value(lit) {
+                0 => return Decision::Literal(lit),
+                1 => self.control.push(self.trail.len()),
+                _ => return Decision::Failed(lit),
+            }
+        }
+        if let Some(constraint) = constraint {
+            if self.control.len() == assumptions.len() {
+                if constraint.iter().any(|&lit| self.value(lit) > 0) {
+                    self.control.push(self.trail.len());
+                } else if let Some(&lit) = constraint.iter().find(|&&lit| self.value(lit) == 0) {
+                    return Decision::Literal(lit);
+                } else {
+                    return Decision::FailedConstraint;
+                }
+            }
+        }
+        while let Some(var) = self.heap.pop(&self.scores) {
+            if self.values[var] == 0 {
+                let var = var as i32;
+                return Decision::Literal(if self.phases[var as usize] { var } else { -var });
+            }
+        }
+        Decision::Satisfied
+    }
+
+    /// Checks the terminate callback and flag.
+    unsafe fn terminated(this: *mut Cdcl) -> bool {
+        let (data, cbs) = ((*this).terminate_data, (*this).terminate_cbs);
+        if let Some(cbs) = cbs {
+            if cbs(data) != 0 {
+                return true;
+            }
+        }
+        if !(*this).terminate_stats.is_null() {
+            ccadical_publish_stats(this as *mut c_void, (*this).terminate_stats);
+        }
+        let flag = (*this).terminate_flag;
+        !flag.is_null() && (*(flag as *const AtomicU8)).load(Ordering::Relaxed) != 0
+    }
+
+    /// Reports a learned clause to the learn callbacks.
+    unsafe fn learn(this: *mut Cdcl, learnt: &[i32], glue: u32) {
+        (*this).learned += 1;
+        let clause: Vec<i32> = learnt.iter().map(|&lit| (*this).external(lit)).collect();
+        let (data, length) = ((*this).learn_data, clause.len() as c_int);
+        if let Some(cbs) = (*this).learn_cbs {
+            if length <= (*this).learn_max_length {
+                let mut clause = clause.clone();
+                clause.push(0);
+                cbs(data, clause.as_ptr());
+            }
+        }
+        if let Some(cbs) = (*this).learn_length_cbs {
+            if length <= (*this).learn_max_length && glue as c_int <= (*this).learn_max_glue {
+                cbs(data, clause.as_ptr(), clause.len());
+            }
+        }
+    }
+
+    /// Searches until the given number of conflicts, or until the limits
+    /// are reached. The solver is only accessed through the raw pointer,
+    /// because the callbacks may query it.
+    unsafe fn search(
+        this: *mut Cdcl,
+        assumptions: &[i32],
+        constraint: Option<&[i32]>,
+        budget: u64,
+        conflicts: &mut i64,
+        decisions: &mut i64,
+    ) -> Answer {
+        let mut local = 0;
+        loop {
+            if let Some(conflict) = (*this).propagate() {
+                (*this).conflicts += 1;
+                *conflicts += 1;
+                local += 1;
+                if (*this).control.is_empty() {
+                    (*this).trace(b'a', &[]);
+                    (*this).inconsistent = true;
+                    return Answer::Unsat;
+                }
+                let learnt = (*this).analyze(conflict);
+                let glue = (*this).glue(&learnt);
+                (*this).trace(b'a', &learnt);
+                Cdcl::learn(this, &learnt, glue);
+                (*this).add_learnt(learnt, glue);
+                let limit = (*this).conflict_limit;
+                if limit >= 0 && *conflicts > limit as i64 {
+                    return Answer::Unknown;
+                }
+                if local >= budget {
+                    return Answer::Restart;
+                }
+                continue;
+            }
+            if Cdcl::terminated(this) {
+                return Answer::Unknown;
+            }
+            if (*this).num_learnt >= (*this).reduce_limit {
+                (*this).reduce();
+            }
+            let lit = match (*this).next_decision(assumptions, constraint) {
+                Decision::Literal(lit) => lit,
+                Decision::Failed(lit) => {
+                    let failed = (*this).analyze_final(lit);
+                    (*this).failed = failed;
+                    return Answer::Unsat;
+                }
+                Decision::FailedConstraint => {
+                    let failed = (*this).analyze_constraint(constraint.unwrap());
+                    (*this).failed = failed;
+                    (*this).constraint_failed = true;
+                    return Answer::Unsat;
+                }
+                Decision::Satisfied => return Answer::Sat,
+            };
+            (*this).decisions += 1;
+            *decisions += 1;
+            let limit = (*this).decision_limit;
+            if limit >= 0 && *decisions > limit as i64 {
+                return Answer::Unknown;
+            }
+            let solver = &mut *this;
+            solver.control.push(solver.trail.len());
+            solver.enqueue(lit, NO_REASON);
+        }
+    }
+
+    unsafe fn solve(this: *mut Cdcl) -> c_int {
+        let start = Instant::now();
+        let (assumptions, constraint) = {
+            let solver = &mut *this;
+            solver.configuring = false;
+            solver.failed.clear();
+            solver.constraint_failed = false;
+            let lits = std::mem::take(&mut solver.assumptions);
+            let assumptions: Vec<i32> = lits.iter().map(|&lit| solver.internal(lit)).collect();
+            let constraint: Option<Vec<i32>> = solver
+                .constraint_clause
+                .take()
+                .map(|clause| clause.iter().map(|&lit| solver.internal(lit)).collect());
+            (assumptions, constraint)
+        };
+
+        let search_start = Instant::now();
+        let (mut conflicts, mut decisions, mut restarts) = (0, 0, 0);
+        let answer = loop {
+            if (*this).inconsistent {
+                break Answer::Unsat;
+            }
+            let budget = luby(restarts) * RESTART_INTERVAL;
+            let constraint = constraint.as_deref();
+            match Cdcl::search(
+                this,
+                &assumptions,
+                constraint,
+                budget,
+                &mut conflicts,
+                &mut decisions,
+            ) {
+                Answer::Restart => {
+                    (*this).restarts += 1;
+                    (*this).backtrack(0);
+                    restarts += 1;
+                }
+                answer => break answer,
+            }
+        };
+
+        let solver = &mut *this;
+        solver.search_time += search_start.elapsed().as_secs_f64();
+        let status = match answer {
+            Answer::Sat => {
+                let model =
+                    (0..=solver.max_var() as usize).map(|var| solver.values[solver.e2i[var]]);
+                solver.model = model.collect();
+                10
+            }
+            Answer::Unsat => {
+                let failed = std::mem::take(&mut solver.failed);
+                solver.failed = failed.iter().map(|&lit| solver.external(lit)).collect();
+                20
+            }
+            _ => 0,
+        };
+        solver.backtrack(0);
+        solver.constraint.clear();
+        solver.conflict_limit = -1;
+        solver.decision_limit = -1;
+        solver.status = status;
+        solver.solve_time += start.elapsed().as_secs_f64();
+        status
+    }
+}
+
+unsafe fn solver<'a>(ptr: *mut c_void) -> &'a mut Cdcl {
+    &mut *(ptr as *mut Cdcl)
+}
+
+pub unsafe fn ccadical_signature() -> *const c_char {
+    "cadical-rust-cdcl\0".as_ptr() as *const c_char
+}
+
+pub unsafe fn ccadical_init() -> *mut c_void {
+    Box::into_raw(Box::new(Cdcl::new())) as *mut c_void
+}
+
+pub unsafe fn ccadical_release(ptr: *mut c_void) {
+    drop(Box::from_raw(ptr as *mut Cdcl));
+}
+
+pub unsafe fn ccadical_add(ptr: *mut c_void, lit: c_int) {
+    let solver = solver(ptr);
+    solver.touch();
+    if lit == 0 {
+        let clause = std::mem::take(&mut solver.clause);
+        solver.add_clause(clause);
+    } else {
+        solver.internal(lit);
+        solver.clause.push(lit);
+    }
+}
+
+pub unsafe fn ccadical_assume(ptr: *mut c_void, lit: c_int) {
+    let solver = solver(ptr);
+    solver.touch();
+    solver.internal(lit);
+    solver.assumptions.push(lit);
+}
+
+pub unsafe fn ccadical_solve_checked(ptr: *mut c_void) -> c_int {
+    ccadical_solve(ptr)
+}
+
+pub unsafe fn ccadical_solve(ptr: *mut c_void) -> c_int {
+    Cdcl::solve(ptr as *mut Cdcl)
+}
+
+pub unsafe fn ccadical_val(ptr: *mut c_void, lit: c_int) -> c_int {
+    let solver = solver(ptr);
+    let val = solver.model.get(lit.unsigned_abs() as usize).copied();
+    let val = if lit < 0 {
+        -val.unwrap_or(0)
+    } else {
+        val.unwrap_or(0)
+    };
+    if val > 0 {
+        lit.abs()
+    } else {
+        -lit.abs()
+    }
+}
+
+pub unsafe fn ccadical_failed(ptr: *mut c_void, lit: c_int) -> c_int {
+    solver(ptr).failed.contains(&lit) as c_int
+}
+
+pub unsafe fn ccadical_set_terminate(
+    ptr: *mut c_void,
+    data: *const c_void,
+    cbs: Option<extern "C" fn(*const c_void) -> c_int>,
+) {
+    let solver = solver(ptr);
+    solver.terminate_data = data;
+    solver.terminate_cbs = cbs;
+    solver.terminate_flag = null();
+    solver.terminate_stats = null_mut();
+}
+
+pub unsafe fn ccadical_set_terminate_flag(ptr: *mut c_void, flag: *const u8, stats: *mut i64) {
+    let solver = solver(ptr);
+    solver.terminate_flag = flag;
+    solver.terminate_stats = stats;
+    solver.terminate_cbs = None;
+}
+
+pub unsafe fn ccadical_copy(ptr: *mut c_void, other: *mut c_void) -> c_int {
+    let (source, target) = (solver(ptr), solver(other));
+    target.touch();
+    target.options = source.options.clone();
+    target.internal(source.max_var());
+    for clause in source.formula.iter() {
+        target.add_clause(clause.clone());
+    }
+    target.frozen = source.frozen.clone();
+    target.inconsistent |= source.inconsistent;
+    0
+}
+
+#[cfg(not(target_has_atomic = "64"))]
+pub unsafe fn ccadical_publish_stats(ptr: *mut c_void, stats: *mut i64) {}
+
+#[cfg(target_has_atomic = "64")]
+pub unsafe fn ccadical_publish_stats(ptr: *mut c_void, stats: *mut i64) {
+    let solver = solver(ptr);
+    let stats = stats as *const AtomicI64;
+    let sequence = (*stats).load(Ordering::Relaxed);
+    (*stats).store(sequence + 1, Ordering::Relaxed);
+    (*stats.add(1)).store(solver.conflicts, Ordering::Relaxed);
+    (*stats.add(2)).store(solver.decisions, Ordering::Relaxed);
+    (*stats.add(3)).store(solver.restarts, Ordering::Relaxed);
+    (*stats.add(4)).store(0, Ordering::Relaxed);
+    (*stats).store(sequence + 2, Ordering::Release);
+}
+
+pub unsafe fn ccadical_set_learn(
+    ptr: *mut c_void,
+    data: *const c_void,
+    max_len: c_int,
+    cbs: Option<extern "C" fn(*const c_void, *const c_int)>,
+) {
+    let solver = solver(ptr);
+    solver.learn_data = data;
+    solver.learn_max_length = max_len;
+    solver.learn_cbs = cbs;
+    solver.learn_length_cbs = None;
+}
+
+pub unsafe fn ccadical_set_learn_length(
+    ptr: *mut c_void,
+    data: *const c_void,
+    max_len: c_int,
+    max_glue: c_int,
+    cbs: Option<extern "C" fn(*const c_void, *const c_int, usize)>,
+) {
+    let solver = solver(ptr);
+    solver.learn_data = data;
+    solver.learn_max_length = max_len;
+    solver.learn_max_glue = max_glue;
+    solver.learn_length_cbs = cbs;
+    solver.learn_cbs = None;
+}
+
+pub unsafe fn ccadical_status(ptr: *mut c_void) -> c_int {
+    solver(ptr).status
+}
+
+pub unsafe fn ccadical_vars(ptr: *mut c_void) -> c_int {
+    solver(ptr).max_var()
+}
+
+pub unsafe fn ccadical_active(ptr: *mut c_void) -> i64 {
+    let solver = solver(ptr);
+    let mut active = vec![false; solver.e2i.len()];
+    for &lit in solver.formula.iter().flatten() {
+        let var = lit.unsigned_abs() as usize;
+        active[var] = solver.values[solver.e2i[var]] == 0;
+    }
+    active.iter().filter(|&&active| active).count() as i64
+}
+
+pub unsafe fn ccadical_irredundant(ptr: *mut c_void) -> i64 {
+    solver(ptr).formula.len() as i64
+}
+
+/// Returns the path passed as a C string, which may be arbitrary bytes on
+/// Unix.
+unsafe fn file_path(path: *const c_char) -> PathBuf {
+    let path = CStr::from_ptr(path);
+    #[cfg(unix)]
+    let path = <OsStr as std::os::unix::ffi::OsStrExt>::from_bytes(path.to_bytes());
+    #[cfg(not(unix))]
+    let path = OsStr::new(path.to_str().unwrap_or_default());
+    PathBuf::from(path)
+}
+
+pub unsafe fn ccadical_read_dimacs(
+    ptr: *mut c_void,
+    path: *const c_char,
+    vars: *mut c_int,
+    strict: c_int,
+) -> *const c_char {
+    let path = file_path(path);
+    let text = match std::fs::read_to_string(&path) {
+        Ok(text) => text,
+        Err(_) => {
+            let solver = solver(ptr);
+            solver.error = CString::new(format!("could not read '{}'", path.display())).unwrap();
+            return solver.error.as_ptr();
+        }
+    };
+    for (index, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.starts_with('c') || line.is_empty() {
+            continue;
+        } else if line.starts_with('%') {
+            break;
+        }
+        let mut tokens = line.split_whitespace();
+        if line.starts_with('p') {
+            let count = tokens.nth(2).and_then(|count| count.parse().ok());
+            *vars = count.unwrap_or(0);
+            ccadical_reserve(ptr, *vars);
+            continue;
+        }
+        for token in tokens {
+            match token.parse::<i32>() {
+                Ok(lit) if lit != i32::MIN => ccadical_add(ptr, lit),
+                _ => {
+                    let solver = solver(ptr);
+                    let msg = format!(
+                        "{}:{}: parse error: invalid literal",
+                        path.display(),
+                        index + 1
+                    );
+                    solver.error = CString::new(msg).unwrap();
+                    return solver.error.as_ptr();
+                }
+            }
+        }
+    }
+    null::<c_char>()
+}
+
+pub unsafe fn ccadical_write_dimacs(
+    ptr: *mut c_void,
+    path: *const c_char,
+    min_max_var: c_int,
+) -> *const c_char {
+    let solver = solver(ptr);
+    let path = file_path(path);
+    let vars = solver.max_var().max(min_max_var);
+    let mut text = format!("p cnf {} {}\n", vars, solver.formula.len());
+    for clause in solver.formula.iter() {
+        for lit in clause {
+            text += &format!("{} ", lit);
+        }
+        text += "0\n";
+    }
+    if std::fs::write(&path, text).is_err() {
+        solver.error = CString::new(format!("could not write '{}'", path.display())).unwrap();
+        return solver.error.as_ptr();
+    }
+    null::<c_char>()
+}
+
+pub unsafe fn ccadical_configure(ptr: *mut c_void, name: *const c_char) -> c_int {
+    let name = CStr::from_ptr(name).to_bytes();
+    [&b"default"[..], b"plain", b"sat", b"unsat"].contains(&name) as c_int
+}
+
+pub unsafe fn ccadical_limit2(ptr: *mut c_void, name: *const c_char, limit: c_int) -> c_int {
+    let solver = solver(ptr);
+    match CStr::from_ptr(name).to_bytes() {
+        b"conflicts" => solver.conflict_limit = limit,
+        b"decisions" => solver.decision_limit = limit,
+        b"preprocessing" | b"localsearch" | b"terminate" => {}
+        _ => return 0,
+    }
+    1
+}
+
+pub unsafe fn ccadical_add_clause(ptr: *mut c_void, lits: *const c_int, len: usize) -> c_int {
+    for i in 0..len {
+        ccadical_add(ptr, *lits.add(i));
+    }
+    ccadical_add(ptr, 0);
+    0
+}
+
+pub unsafe fn ccadical_assume_all(ptr: *mut c_void, lits: *const c_int, len: usize) -> c_int {
+    for i in 0..len {
+        ccadical_assume(ptr, *lits.add(i));
+    }
+    0
+}
+
+pub unsafe fn ccadical_reserve(ptr: *mut c_void, min_max_var: c_int) -> c_int {
+    let solver = solver(ptr);
+    solver.touch();
+    if min_max_var > 0 {
+        solver.internal(min_max_var);
+    }
+    0
+}
+
+pub unsafe fn ccadical_reserve_clauses(ptr: *mut c_void, count: usize) -> c_int {
+    solver(ptr).formula.reserve(count);
+    0
+}
+
+pub unsafe fn ccadical_traverse_clauses(
+    ptr: *mut c_void,
+    data: *mut c_void,
+    cbs: extern "C" fn(*mut c_void, *const c_int, usize) -> c_int,
+) -> c_int {
+    let formula = solver(ptr).formula.clone();
+    for clause in formula.iter() {
+        if cbs(data, clause.as_ptr(), clause.len()) == 0 {
+            return 0;
+        }
+    }
+    1
+}
+
+pub unsafe fn ccadical_simplify(ptr: *mut c_void, rounds: c_int) -> c_int {
+    let solver = solver(ptr);
+    solver.touch();
+    // the assumptions and the constraint only last for one call
+    solver.assumptions.clear();
+    solver.constraint_clause = None;
+    if !solver.inconsistent && solver.propagate().is_some() {
+        solver.inconsistent = true;
+    }
+    if solver.inconsistent {
+        20
+    } else {
+        0
+    }
+}
+
+pub unsafe fn ccadical_prioritize(ptr: *mut c_void, lit: c_int) {
+    let solver = solver(ptr);
+    if lit <= 0 || lit > solver.max_var() {
+        return;
+    }
+    let var = solver.internal(lit).unsigned_abs() as usize;
+    // the variables fixed at the root level are not bumped
+    if solver.values[var] != 0 && solver.levels[var] == 0 {
+        return;
+    }
+    let top = solver.scores.iter().copied().fold(0.0, f64::max);
+    solver.scores[var] = top + solver.score_increment;
+    solver.heap.bumped(var, &solver.scores);
+}
+
+pub unsafe fn ccadical_freeze(ptr: *mut c_void, lit: c_int) {
+    let solver = solver(ptr);
+    solver.internal(lit);
+    let var = lit.unsigned_abs() as usize;
+    if solver.frozen.len() <= var {
+        solver.frozen.resize(var + 1, 0);
+    }
+    solver.frozen[var] += 1;
+}
+
+pub unsafe fn ccadical_melt(ptr: *mut c_void, lit: c_int) {
+    let solver = solver(ptr);
+    let var = lit.unsigned_abs() as usize;
+    assert!(solver.frozen.get(var).copied().unwrap_or(0) > 0);
+    solver.frozen[var] -= 1;
+}
+
+pub unsafe fn ccadical_frozen(ptr: *mut c_void, lit: c_int) -> c_int {
+    let solver = solver(ptr);
+    let var = lit.unsigned_abs() as usize;
+    (solver.frozen.get(var).copied().unwrap_or(0) > 0) as c_int
+}
+
+pub unsafe fn ccadical_set_option2(ptr: *mut c_void, name: *const c_char, val: c_int) -> c_int {
+    let solver = solver(ptr);
+    let name = CStr::from_ptr(name).to_string_lossy();
+    let option = OPTIONS.iter().find(|option| option.0 == name);
+    let &(_, _, min, max) = match option {
+        Some(option) => option,
+        None => return 0,
+    };
+    solver.options.retain(|(key, _)| key != &name);
+    solver
+        .options
+        .push((name.into_owned(), val.max(min).min(max)));
+    1
+}
+
+pub unsafe fn ccadical_option_range(
+    _ptr: *mut c_void,
+    name: *const c_char,
+    min: *mut c_int,
+    max: *mut c_int,
+) -> c_int {
+    let name = CStr::from_ptr(name).to_string_lossy();
+    match OPTIONS.iter().find(|option| option.0 == name) {
+        Some(option) => {
+            *min = option.2;
+            *max = option.3;
+            1
+        }
+        None => 0,
+    }
+}
+
+pub unsafe fn ccadical_option_default(_ptr: *mut c_void, name: *const c_char) -> c_int {
+    let name = CStr::from_ptr(name).to_string_lossy();
+    OPTIONS
+        .iter()
+        .find(|option| option.0 == name)
+        .map_or(0, |option| option.1)
+}
+
+pub unsafe fn ccadical_option_name(_ptr: *mut c_void, index: c_int) -> *const c_char {
+    match OPTION_NAMES.get(index as usize) {
+        Some(name) if index >= 0 => name.as_ptr() as *const c_char,
+        _ => std::ptr::null(),
+    }
+}
+
+pub unsafe fn ccadical_set_option_live(ptr: *mut c_void, name: *const c_char, val: c_int) -> c_int {
+    let live = ["log", "quiet", "report", "verbose"];
+    let key = CStr::from_ptr(name).to_string_lossy();
+    if ccadical_state(ptr) != 2 && !live.contains(&key.as_ref()) {
+        return 0;
+    }
+    ccadical_set_option2(ptr, name, val)
+}
+
+pub unsafe fn ccadical_get_option(ptr: *mut c_void, name: *const c_char) -> c_int {
+    let name = CStr::from_ptr(name).to_string_lossy();
+    solver(ptr).option(&name)
+}
+
+pub unsafe fn ccadical_constrain(ptr: *mut c_void, lit: c_int) {
+    let solver = solver(ptr);
+    solver.touch();
+    if lit == 0 {
+        solver.constraint_clause = Some(std::mem::take(&mut solver.constraint));
+    } else {
+        solver.internal(lit);
+        solver.constraint.push(lit);
+    }
+}
+
+pub unsafe fn ccadical_constraint_failed(ptr: *mut c_void) -> c_int {
+    solver(ptr).constraint_failed as c_int
+}
+
+pub unsafe fn ccadical_reset_assumptions(ptr: *mut c_void) {
+    solver(ptr).assumptions.clear();
+}
+
+pub unsafe fn ccadical_reset_constraint(ptr: *mut c_void) {
+    let solver = solver(ptr);
+    solver.constraint.clear();
+    solver.constraint_clause = None;
+}
+
+pub unsafe fn ccadical_conflicts(ptr: *mut c_void) -> i64 {
+    solver(ptr).conflicts
+}
+
+pub unsafe fn ccadical_decisions(ptr: *mut c_void) -> i64 {
+    solver(ptr).decisions
+}
+
+pub unsafe fn ccadical_state(ptr: *mut c_void) -> c_int {
+    let solver = solver(ptr);
+    if solver.status == 10 {
+        32
+    } else if solver.status == 20 {
+        64
+    } else if !solver.clause.is_empty() {
+        8
+    } else if solver.configuring {
+        2
+    } else {
+        4
+    }
+}
+
+pub unsafe fn ccadical_is_inconsistent(ptr: *mut c_void) -> c_int {
+    solver(ptr).inconsistent as c_int
+}
+
+#[cfg(feature = "profiling")]
+pub unsafe fn ccadical_profile(ptr: *mut c_void, name: *const c_char) -> f64 {
+    let solver = solver(ptr);
+    match CStr::from_ptr(name).to_bytes() {
+        b"solve" => solver.solve_time,
+        b"search" => solver.search_time,
+        _ => 0.0,
+    }
+}
+
+pub unsafe fn ccadical_restarts(ptr: *mut c_void) -> i64 {
+    solver(ptr).restarts
+}
+
+pub unsafe fn ccadical_level(ptr: *mut c_void) -> c_int {
+    solver(ptr).control.len() as c_int
+}
+
+pub unsafe fn ccadical_decision(ptr: *mut c_void) -> c_int {
+    let solver = solver(ptr);
+    match solver.control.last() {
+        Some(&start) if start < solver.trail.len() => solver.external(solver.trail[start]),
+        _ => 0,
+    }
+}
+
+pub unsafe fn ccadical_learned_levels(
+    ptr: *mut c_void,
+    clause: *const c_int,
+    len: usize,
+    jump: *mut c_int,
+) -> c_int {
+    let solver = solver(ptr);
+    let mut levels: Vec<c_int> = (0..len)
+        .map(|i| *clause.add(i))
+        .map(|lit| solver.e2i.get(lit.unsigned_abs() as usize).copied())
+        .map(|var| var.map_or(0, |var| solver.levels[var] as c_int))
+        .collect();
+    levels.sort_unstable();
+    *jump = if len > 1 { levels[len - 2] } else { 0 };
+    levels.dedup();
+    levels.len() as c_int
+}
+
+pub unsafe fn ccadical_propagations(ptr: *mut c_void) -> i64 {
+    solver(ptr).propagations
+}
+
+pub unsafe fn ccadical_learned(ptr: *mut c_void) -> i64 {
+    solver(ptr).learned
+}
+
+pub unsafe fn ccadical_report(ptr: *mut c_void, report: *mut i64) {
+    let active = ccadical_active(ptr);
+    let irredundant = ccadical_irredundant(ptr);
+    let solver = solver(ptr);
+    let columns = [
+        solver.conflicts,
+        solver.restarts,
+        solver.reductions,
+        solver.control.len() as i64,
+        solver.trail.len() as i64,
+        irredundant,
+        solver.num_learnt as i64,
+        active,
+        solver.i2e.len() as i64 - 1,
+    ];
+    std::ptr::copy_nonoverlapping(columns.as_ptr(), report, columns.len());
+}
+
+pub unsafe fn ccadical_proof_open() -> *mut c_void {
+    Box::into_raw(Box::<Vec<u8>>::default()) as *mut c_void
+}
+
+pub unsafe fn ccadical_trace_proof(ptr: *mut c_void, file: *mut c_void) -> c_int {
+    let solver = solver(ptr);
+    if !solver.configuring {
+        return 0;
+    }
+    solver.proof = file as *mut Vec<u8>;
+    1
+}
+
+pub unsafe fn ccadical_proof_size(ptr: *mut c_void, file: *mut c_void) -> u64 {
+    (*(file as *const Vec<u8>)).len() as u64
+}
+
+pub unsafe fn ccadical_proof_read(
+    ptr: *mut c_void,
+    file: *mut c_void,
+    offset: u64,
+    buffer: *mut u8,
+    size: usize,
+) -> usize {
+    let proof = &*(file as *const Vec<u8>);
+    let offset = usize::try_from(offset).unwrap_or(usize::MAX);
+    let count = proof.len().saturating_sub(offset).min(size);
+    if count > 0 {
+        std::ptr::copy_nonoverlapping(proof.as_ptr().add(offset), buffer, count);
+    }
+    count
+}
+
+pub unsafe fn ccadical_proof_close(file: *mut c_void) {
+    drop(Box::from_raw(file as *mut Vec<u8>));
+}
+
+#[cfg(feature = "cpp-tracing")]
+pub unsafe fn ccadical_trace_open(path: *const c_char) -> *mut c_void {
+    match std::fs::File::create(file_path(path)) {
+        Ok(file) => Box::into_raw(Box::new(file)) as *mut c_void,
+        Err(_) => null_mut(),
+    }
+}
+
+#[cfg(feature = "cpp-tracing")]
+pub unsafe fn ccadical_trace_api_calls(ptr: *mut c_void, file: *mut c_void) {
+    use std::io::Write;
+    let file = &mut *(file as *mut std::fs::File);
+    let _ = writeln!(file, "init");
+}
+
+#[cfg(feature = "cpp-tracing")]
+pub unsafe fn ccadical_trace_close(file: *mut c_void) {
+    drop(Box::from_raw(file as *mut std::fs::File));
+}
+
+#[cfg(feature = "memory-accounting")]
+pub unsafe fn ccadical_account_new() -> *mut c_void {
+    null_mut()
+}
+
+#[cfg(feature = "memory-accounting")]
+pub unsafe fn ccadical_account_release(account: *mut c_void) {}
+
+#[cfg(feature = "memory-accounting")]
+pub unsafe fn ccadical_account_enter(account: *mut c_void) -> *mut c_void {
+    null_mut()
+}
+
+#[cfg(feature = "memory-accounting")]
+pub unsafe fn ccadical_account_current(account: *mut c_void) -> i64 {
+    0
+}
+
+#[cfg(feature = "memory-accounting")]
+pub unsafe fn ccadical_account_peak(account: *mut c_void) -> i64 {
+    0
+}
@@ -0,0 +1,117 @@
+//! A reusable buffer for building clauses literal by literal, and the types
+//! that can be added to the solver as contiguous clauses.
+
+use super::{Callbacks, Solver};
+
+/// A buffer for building clauses one literal at a time and adding them to a
+/// solver. The buffer is cleared but not deallocated after each clause, so
+/// encoders that emit many short clauses do not allocate per clause.
+/// # Examples
+/// ```
+/// let mut sat: cadical::Solver = Default::default();
+/// let mut clause = cadical::ClauseBuilder::new();
+/// for var in 1..=3 {
+///     clause.push(var);
+/// }
+/// clause.commit(&mut sat);
+/// clause.push(-1);
+/// clause.commit(&mut sat);
+/// assert_eq!(sat.solve_with([-2, -3]), Some(false));
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct ClauseBuilder {
+    lits: Vec<i32>,
+}
+
+impl ClauseBuilder {
+    /// Creates a new empty clause builder.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Creates a new empty clause builder with room for clauses of the
+    /// given length.
+    pub fn with_capacity(capacity: usize) -> Self {
+        ClauseBuilder {
+            lits: Vec::with_capacity(capacity),
+        }
+    }
+
+    /// Appends a literal to the current clause. The literal must be nonzero
+    /// and different from `i32::MIN`.
+    #[inline]
+    pub fn push(&mut self, lit: i32) {
+        debug_assert!(lit != 0 && lit != i32::MIN);
+        self.lits.push(lit);
+    }
+
+    /// Returns the literals of the current clause.
+    #[inline]
+    pub fn literals(&self) -> &[i32] {
+        &self.lits
+    }
+
+    /// Discards the current clause.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.lits.clear();
+    }
+
+    /// Adds the current clause to the solver and starts a new empty one.
+    #[inline]
+    pub fn commit<C: Callbacks>(&mut self, solver: &mut Solver<C>) {
+        solver.add_clause_slice(&self.lits);
+        self.lits.clear();
+    }
+}
+
+/// Clauses stored as contiguous literals, which can be added to the solver
+/// with `Solver::add_clause_slice` without going through an iterator.
+pub trait AsClause {
+    /// Returns the literals of the clause.
+    fn as_clause(&self) -> &[i32];
+}
+
+impl AsClause for [i32] {
+    #[inline]
+    fn as_clause(&self) -> &[i32] {
+        self
+    }
+}
+
+impl<const N: usize> AsClause for [i32; N] {
+    #[inline]
+    fn as_clause(&self) -> &[i32] {
+        self
+    }
+}
+
+impl AsClause for Vec<i32> {
+    #[inline]
+    fn as_clause(&self) -> &[i32] {
+        self
+    }
+}
+
+impl AsClause for ClauseBuilder {
+    #[inline]
+    fn as_clause(&self) -> &[i32] {
+        &self.lits
+    }
+}
+
+#[cfg(feature = "smallvec")]
+impl<A: smallvec::Array<Item = i32>> AsClause for smallvec::SmallVec<A> {
+    #[inline]
+    fn as_clause(&self) -> &[i32] {
+        self
+    }
+}
+
+impl Extend<i32> for ClauseBuilder {
+    fn extend<T: IntoIterator<Item = i32>>(&mut self, lits: T) {
+        for lit in lits {
+            self.push(lit);
+        }
+    }
+}
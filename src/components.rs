@@ -0,0 +1,155 @@
+//! Decomposition of formulas into variable-disjoint components, which can be
+//! solved independently.
+
+use super::{CnfFormula, Solver};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::thread;
+
+/// The result of `CnfFormula::solve_components`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ComponentResult {
+    /// The formula is satisfiable, and the model is the list of true
+    /// literals, one for each variable up to the maximum variable.
+    Satisfiable(Vec<i32>),
+    /// The formula is unsatisfiable because the given component is.
+    Unsatisfiable(CnfFormula),
+}
+
+/// Returns the representative of the set of the given variable, with path
+/// halving.
+fn find(parent: &mut [usize], mut var: usize) -> usize {
+    while parent[var] != var {
+        parent[var] = parent[parent[var]];
+        var = parent[var];
+    }
+    var
+}
+
+impl CnfFormula {
+    /// Splits the formula into components that do not share variables. The
+    /// variables keep their numbering, and the clauses keep their order
+    /// within each component. The components are ordered by their first
+    /// clause, and each empty clause forms a component on its own.
+    /// # Examples
+    /// ```
+    /// let mut formula = cadical::CnfFormula::new();
+    /// formula.add_clause([1, 2]);
+    /// formula.add_clause([3, -4]);
+    /// formula.add_clause([-2, 5]);
+    /// let components = formula.components();
+    /// assert_eq!(components.len(), 2);
+    /// assert_eq!(components[0].len(), 2);
+    /// assert_eq!(components[1].clause(0), &[3, -4]);
+    /// ```
+    pub fn components(&self) -> Vec<CnfFormula> {
+        let vars = self.max_variable() as usize;
+        let mut parent: Vec<usize> = (0..=vars).collect();
+        for clause in self.iter() {
+            if let Some(first) = clause.first() {
+                let root = find(&mut parent, first.unsigned_abs() as usize);
+                for lit in clause[1..].iter() {
+                    let other = find(&mut parent, lit.unsigned_abs() as usize);
+                    parent[other] = root;
+                }
+            }
+        }
+
+        // number the components in the order of their first clauses
+        let mut index: Vec<usize> = vec![usize::MAX; vars + 1];
+        let mut components: Vec<CnfFormula> = Vec::new();
+        for clause in self.iter() {
+            let target = match clause.first() {
+                Some(lit) => {
+                    let root = find(&mut parent, lit.unsigned_abs() as usize);
+                    if index[root] == usize::MAX {
+                        index[root] = components.len();
+                        components.push(CnfFormula::new());
+                    }
+                    index[root]
+                }
+                None => {
+                    components.push(CnfFormula::new());
+                    components.len() - 1
+                }
+            };
+            components[target].add_clause(clause.iter().copied());
+        }
+        components
+    }
+
+    /// Solves the formula by splitting it into components and solving each
+    /// of them with a separate solver on up to `threads` worker threads (all
+    /// available cores if zero). The models of the components are combined,
+    /// and variables that do not occur in any clause are set to true. Once a
+    /// component is found unsatisfiable no new components are started.
+    /// Formulas with a single component gain nothing from this, but some
+    /// industrial instances fall apart into many small independent parts.
+    /// # Examples
+    /// ```
+    /// use cadical::ComponentResult;
+    /// let mut formula = cadical::CnfFormula::new();
+    /// formula.add_clause([1, 2]);
+    /// formula.add_clause([-1]);
+    /// formula.add_clause([3]);
+    /// formula.add_clause([-3, -4]);
+    /// assert_eq!(
+    ///     formula.solve_components(2),
+    ///     ComponentResult::Satisfiable(vec![-1, 2, 3, -4])
+    /// );
+    /// ```
+    pub fn solve_components(&self, threads: usize) -> ComponentResult {
+        let components = self.components();
+        let threads = if threads == 0 {
+            thread::available_parallelism().map_or(1, |n| n.get())
+        } else {
+            threads
+        };
+        let threads = threads.min(components.len());
+
+        let next = AtomicUsize::new(0);
+        let failed = AtomicBool::new(false);
+        let results: Mutex<Vec<Option<Vec<i32>>>> = Mutex::new(vec![None; components.len()]);
+        let unsat: Mutex<Option<usize>> = Mutex::new(None);
+        thread::scope(|scope| {
+            for _ in 0..threads {
+                scope.spawn(|| loop {
+                    let index = next.fetch_add(1, Ordering::Relaxed);
+                    if index >= components.len() || failed.load(Ordering::Relaxed) {
+                        break;
+                    }
+                    let component = &components[index];
+                    let mut sat: Solver = Solver::new();
+                    sat.add_formula(component);
+                    if sat.solve() == Some(true) {
+                        let vars = component.iter().flatten().map(|lit| lit.abs());
+                        let model = vars.map(|var| {
+                            if sat.value(var) == Some(false) {
+                                -var
+                            } else {
+                                var
+                            }
+                        });
+                        results.lock().unwrap()[index] = Some(model.collect());
+                    } else {
+                        failed.store(true, Ordering::Relaxed);
+                        let mut unsat = unsat.lock().unwrap();
+                        *unsat = Some(unsat.map_or(index, |other| other.min(index)));
+                    }
+                });
+            }
+        });
+
+        if let Some(index) = unsat.into_inner().unwrap() {
+            let mut components = components;
+            return ComponentResult::Unsatisfiable(components.swap_remove(index));
+        }
+        let mut model: Vec<i32> = (1..=self.max_variable()).collect();
+        for lits in results.into_inner().unwrap().into_iter().flatten() {
+            for lit in lits {
+                model[lit.unsigned_abs() as usize - 1] = lit;
+            }
+        }
+        ComponentResult::Satisfiable(model)
+    }
+}
@@ -0,0 +1,76 @@
+//! Diversified solver configurations for running portfolios of solvers on
+//! the same formula.
+
+use super::{Callbacks, Error, Solver};
+
+/// A configuration preset together with a list of options, which can be used
+/// to construct identically configured solvers.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SolverConfig {
+    /// The configuration preset passed to `Solver::with_config`.
+    pub preset: String,
+    /// The options passed to `Solver::set_option` in this order.
+    pub options: Vec<(String, i32)>,
+}
+
+impl SolverConfig {
+    /// Constructs a new solver with this configuration.
+    pub fn build<C: Callbacks>(&self) -> Result<Solver<C>, Error> {
+        let mut sat = Solver::with_config(&self.preset)?;
+        for (name, value) in self.options.iter() {
+            sat.set_option(name, *value)?;
+        }
+        Ok(sat)
+    }
+}
+
+/// The base configurations of the portfolio: the presets of CaDiCaL, the
+/// two phase policies, only stable (SAT-oriented) or only focused
+/// (UNSAT-oriented) search modes, and search without variable elimination.
+const VARIANTS: [(&str, &[(&str, i32)]); 8] = [
+    ("default", &[]),
+    ("sat", &[]),
+    ("unsat", &[]),
+    ("default", &[("phase", 0)]),
+    ("default", &[("stabilizeonly", 1)]),
+    ("default", &[("stabilize", 0)]),
+    ("default", &[("elim", 0)]),
+    ("plain", &[]),
+];
+
+/// Returns `n` different configurations for a portfolio. The first ones
+/// cycle through different restart and phase policies, presets and
+/// preprocessing settings, each with its own random seed derived from
+/// `seed`. Once these are exhausted, the variants are repeated with random
+/// shuffling of the decision queue and scores enabled.
+/// # Examples
+/// ```
+/// let configs = cadical::configs::diversified(4, 1);
+/// assert_eq!(configs[1].preset, "sat");
+/// let mut sat: cadical::Solver = configs[3].build().unwrap();
+/// sat.add_clause([1, 2]);
+/// assert_eq!(sat.solve(), Some(true));
+/// ```
+pub fn diversified(n: usize, seed: u64) -> Vec<SolverConfig> {
+    (0..n)
+        .map(|index| {
+            let (preset, base) = VARIANTS[index % VARIANTS.len()];
+            let mut options: Vec<(String, i32)> = base
+                .iter()
+                .map(|&(name, value)| (name.to_string(), value))
+                .collect();
+            let seed = seed.wrapping_add(index as u64) % 2_000_000_001;
+            options.push(("seed".to_string(), seed as i32));
+            if index >= VARIANTS.len() {
+                for &name in ["shuffle", "shufflequeue", "shufflescores", "shufflerandom"].iter() {
+                    options.push((name.to_string(), 1));
+                }
+            }
+            SolverConfig {
+                preset: preset.to_string(),
+                options,
+            }
+        })
+        .collect()
+}
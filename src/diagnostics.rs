@@ -0,0 +1,163 @@
+//! A global registry of the live solver instances, which helps long-running
+//! services to find leaked or forgotten solvers. Every `Solver` registers
+//! itself when it is constructed, with the backtrace of its construction,
+//! and removes itself when it is dropped. This module is only available with
+//! the `diagnostics` feature.
+//!
+//! The backtraces are captured with `Backtrace::capture`, so they are only
+//! recorded when the `RUST_BACKTRACE` or `RUST_LIB_BACKTRACE` environment
+//! variable enables them. The sizes of the solvers are updated after every
+//! solve call and reset, because a solver cannot be queried from another
+//! thread while it is in use.
+
+use super::{narrow, Callbacks, Solver};
+use std::backtrace::Backtrace;
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, SystemTime};
+
+/// The estimated number of bytes used by CaDiCaL for each variable.
+#[cfg(not(feature = "memory-accounting"))]
+const BYTES_PER_VARIABLE: usize = 96;
+
+/// The estimated number of bytes used by CaDiCaL for each clause.
+#[cfg(not(feature = "memory-accounting"))]
+const BYTES_PER_CLAUSE: usize = 64;
+
+#[derive(Default)]
+struct Usage {
+    variables: AtomicUsize,
+    clauses: AtomicUsize,
+    memory: AtomicUsize,
+}
+
+struct Entry {
+    created: SystemTime,
+    backtrace: Arc<Backtrace>,
+    usage: Arc<Usage>,
+}
+
+struct Registry {
+    next: u64,
+    entries: BTreeMap<u64, Entry>,
+}
+
+static REGISTRY: Mutex<Registry> = Mutex::new(Registry {
+    next: 0,
+    entries: BTreeMap::new(),
+});
+
+fn registry() -> MutexGuard<'static, Registry> {
+    // the registry is consistent even if a thread panicked while holding it
+    REGISTRY.lock().unwrap_or_else(|err| err.into_inner())
+}
+
+/// The entry of a solver in the registry, which is removed when the solver
+/// is dropped.
+pub(crate) struct Registration {
+    id: u64,
+    usage: Arc<Usage>,
+}
+
+impl Registration {
+    pub(crate) fn new() -> Self {
+        let usage: Arc<Usage> = Default::default();
+        let entry = Entry {
+            created: SystemTime::now(),
+            backtrace: Arc::new(Backtrace::capture()),
+            usage: usage.clone(),
+        };
+        let mut registry = registry();
+        let id = registry.next;
+        registry.next += 1;
+        registry.entries.insert(id, entry);
+        Registration { id, usage }
+    }
+}
+
+impl Drop for Registration {
+    fn drop(&mut self) {
+        registry().entries.remove(&self.id);
+    }
+}
+
+/// The description of a live solver instance.
+#[derive(Clone, Debug)]
+pub struct SolverInfo {
+    /// The identifier of the solver, as returned by `Solver::diagnostics_id`.
+    pub id: u64,
+    /// The time when the solver was constructed.
+    pub created: SystemTime,
+    /// The backtrace of the construction of the solver, which is disabled
+    /// unless enabled by the environment.
+    pub backtrace: Arc<Backtrace>,
+    /// The maximal variable of the solver after its last solve call.
+    pub variables: usize,
+    /// The number of irredundant clauses after its last solve call.
+    pub clauses: usize,
+    /// The number of bytes of native memory used by the solver after its
+    /// last solve call. This is measured with the `memory-accounting`
+    /// feature, and estimated from the number of variables and clauses
+    /// otherwise.
+    pub memory: usize,
+}
+
+impl SolverInfo {
+    /// Returns the time elapsed since the solver was constructed.
+    pub fn age(&self) -> Duration {
+        self.created.elapsed().unwrap_or_default()
+    }
+}
+
+/// Returns the descriptions of all live solver instances in the order of
+/// their construction.
+/// # Examples
+/// ```
+/// let sat: cadical::Solver = Default::default();
+/// let solvers = cadical::diagnostics::live_solvers();
+/// assert!(solvers.iter().any(|info| info.id == sat.diagnostics_id()));
+/// ```
+pub fn live_solvers() -> Vec<SolverInfo> {
+    let registry = registry();
+    let entries = registry.entries.iter();
+    entries
+        .map(|(&id, entry)| SolverInfo {
+            id,
+            created: entry.created,
+            backtrace: entry.backtrace.clone(),
+            variables: entry.usage.variables.load(Ordering::Relaxed),
+            clauses: entry.usage.clauses.load(Ordering::Relaxed),
+            memory: entry.usage.memory.load(Ordering::Relaxed),
+        })
+        .collect()
+}
+
+/// Returns the number of live solver instances.
+pub fn live_solver_count() -> usize {
+    registry().entries.len()
+}
+
+impl<C: Callbacks> Solver<C> {
+    /// Returns the identifier of this solver in the registry of the
+    /// `diagnostics` module.
+    pub fn diagnostics_id(&self) -> u64 {
+        self.registration.id
+    }
+
+    /// Updates the size of this solver in the registry.
+    pub(crate) fn update_diagnostics(&self) {
+        let usage = &self.registration.usage;
+        let variables: usize = narrow(self.max_variable()).unwrap_or(0);
+        let clauses: usize = narrow(self.num_clauses_u64()).unwrap_or(usize::MAX);
+        #[cfg(feature = "memory-accounting")]
+        let memory = narrow(self.memory_usage()).unwrap_or(usize::MAX);
+        #[cfg(not(feature = "memory-accounting"))]
+        let memory = variables
+            .saturating_mul(BYTES_PER_VARIABLE)
+            .saturating_add(clauses.saturating_mul(BYTES_PER_CLAUSE));
+        usage.variables.store(variables, Ordering::Relaxed);
+        usage.clauses.store(clauses, Ordering::Relaxed);
+        usage.memory.store(memory, Ordering::Relaxed);
+    }
+}
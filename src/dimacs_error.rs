@@ -0,0 +1,185 @@
+//! Detailed diagnostics of DIMACS read and parse errors.
+
+use super::{Callbacks, CnfFormula, Error, Solver};
+use std::fs::File;
+use std::path::Path;
+use std::{error, fmt, io};
+
+/// The kind of a DIMACS parse error.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DimacsErrorKind {
+    /// The `p cnf <variables> <clauses>` header is malformed.
+    InvalidHeader,
+    /// A token is not an integer.
+    InvalidLiteral,
+    /// A literal does not fit into the range of variables.
+    LiteralOutOfRange,
+    /// The last clause is not terminated by `0`.
+    UnterminatedClause,
+    /// An error reported by the parser of CaDiCaL, which is not classified
+    /// further.
+    Native(String),
+}
+
+/// A DIMACS parse error with its position, the offending token, and a
+/// suggestion how to fix the input if one is known.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DimacsError {
+    /// The kind of the error.
+    pub kind: DimacsErrorKind,
+    /// The line of the error starting from 1, or 0 if it is not known.
+    pub line: usize,
+    /// The column of the offending token starting from 1, or 0 if it is not
+    /// known.
+    pub column: usize,
+    /// The offending token.
+    pub token: Option<String>,
+    /// A suggestion how to fix the input.
+    pub suggestion: Option<String>,
+}
+
+impl DimacsError {
+    pub(crate) fn new(
+        kind: DimacsErrorKind,
+        line: usize,
+        column: usize,
+        token: Option<&str>,
+    ) -> Self {
+        DimacsError {
+            kind,
+            line,
+            column,
+            token: token.map(str::to_string),
+            suggestion: None,
+        }
+    }
+
+    pub(crate) fn suggest(mut self, suggestion: &str) -> Self {
+        self.suggestion = Some(suggestion.to_string());
+        self
+    }
+
+    /// Parses an error message of CaDiCaL of the form
+    /// `<path>:<line>: parse error: <message>`.
+    fn from_native(msg: &str) -> Self {
+        if let Some(pos) = msg.find(": parse error: ") {
+            let line = msg[..pos].rsplit(':').next().and_then(|n| n.parse().ok());
+            let text = &msg[pos + ": parse error: ".len()..];
+            DimacsError::new(
+                DimacsErrorKind::Native(text.to_string()),
+                line.unwrap_or(0),
+                0,
+                None,
+            )
+        } else {
+            DimacsError::new(DimacsErrorKind::Native(msg.to_string()), 0, 0, None)
+        }
+    }
+}
+
+impl fmt::Display for DimacsError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.line != 0 {
+            write!(f, "line {}", self.line)?;
+            if self.column != 0 {
+                write!(f, ", column {}", self.column)?;
+            }
+            write!(f, ": ")?;
+        }
+        match &self.kind {
+            DimacsErrorKind::InvalidHeader => write!(f, "invalid header")?,
+            DimacsErrorKind::InvalidLiteral => write!(f, "invalid literal")?,
+            DimacsErrorKind::LiteralOutOfRange => write!(f, "literal out of range")?,
+            DimacsErrorKind::UnterminatedClause => write!(f, "unterminated clause")?,
+            DimacsErrorKind::Native(msg) => write!(f, "{}", msg)?,
+        }
+        if let Some(token) = &self.token {
+            write!(f, " `{}`", token)?;
+        }
+        if let Some(suggestion) = &self.suggestion {
+            write!(f, " ({})", suggestion)?;
+        }
+        Ok(())
+    }
+}
+
+impl error::Error for DimacsError {}
+
+impl From<DimacsError> for Error {
+    fn from(err: DimacsError) -> Self {
+        Error::new(&err.to_string())
+    }
+}
+
+/// An error of reading a DIMACS file, which separates failures of accessing
+/// the file (missing files, denied permissions) from malformed contents.
+#[derive(Debug)]
+pub enum ReadDimacsError {
+    /// The file could not be opened or read.
+    Io(io::Error),
+    /// The contents of the file are not valid DIMACS.
+    Parse(DimacsError),
+}
+
+impl fmt::Display for ReadDimacsError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ReadDimacsError::Io(err) => write!(f, "cannot read file: {}", err),
+            ReadDimacsError::Parse(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl error::Error for ReadDimacsError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            ReadDimacsError::Io(err) => Some(err),
+            ReadDimacsError::Parse(err) => Some(err),
+        }
+    }
+}
+
+impl From<io::Error> for ReadDimacsError {
+    fn from(err: io::Error) -> Self {
+        ReadDimacsError::Io(err)
+    }
+}
+
+impl From<DimacsError> for ReadDimacsError {
+    fn from(err: DimacsError) -> Self {
+        ReadDimacsError::Parse(err)
+    }
+}
+
+impl From<ReadDimacsError> for Error {
+    fn from(err: ReadDimacsError) -> Self {
+        Error::new(&err.to_string())
+    }
+}
+
+impl<C: Callbacks> Solver<C> {
+    /// Reads a problem in DIMACS format from the given file like
+    /// `read_dimacs`, but returns a detailed error. The file is opened first
+    /// in Rust, so that failures of accessing it are returned as
+    /// `ReadDimacsError::Io` with the original `io::Error`. The errors
+    /// reported by CaDiCaL only carry the line number, so for uncompressed
+    /// files the position, the offending token and a suggestion are
+    /// recovered by parsing the file again in Rust.
+    pub fn read_dimacs_detailed(&mut self, path: &Path) -> Result<i32, ReadDimacsError> {
+        File::open(path)?;
+        let msg = match self.read_dimacs(path) {
+            Ok(vars) => return Ok(vars),
+            Err(err) => err.msg,
+        };
+        let native = DimacsError::from_native(&msg);
+        if native.line == 0 {
+            // not a parse error, for example a failing decompression
+            return Err(io::Error::other(msg).into());
+        }
+        let text = std::fs::read_to_string(path);
+        if let Ok(text) = text {
+            CnfFormula::parse_dimacs_detailed(&text)?;
+        }
+        Err(native.into())
+    }
+}
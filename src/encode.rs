@@ -0,0 +1,209 @@
+//! Encodings of higher level constraints into clauses. The auxiliary
+//! variables are allocated with `Solver::new_variable`.
+
+use super::{Callbacks, Solver};
+use std::collections::BTreeMap;
+
+pub mod algebra;
+pub mod graph;
+pub mod latin;
+
+/// The relation of a pseudo-Boolean constraint.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Relation {
+    /// The weighted sum is at most the bound.
+    AtMost,
+    /// The weighted sum is at least the bound.
+    AtLeast,
+    /// The weighted sum is equal to the bound.
+    Equal,
+}
+
+/// A linear pseudo-Boolean constraint, where each term is a coefficient
+/// together with a literal, which counts as one if it is true and zero
+/// otherwise.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PbConstraint {
+    /// The coefficients and literals of the weighted sum.
+    pub terms: Vec<(i64, i32)>,
+    /// The relation between the weighted sum and the bound.
+    pub relation: Relation,
+    /// The bound of the weighted sum.
+    pub bound: i64,
+}
+
+impl PbConstraint {
+    /// Returns whether the constraint holds when exactly the given literals
+    /// are true.
+    pub fn holds<F>(&self, mut value: F) -> bool
+    where
+        F: FnMut(i32) -> bool,
+    {
+        let sum: i64 = self
+            .terms
+            .iter()
+            .filter(|&&(_, lit)| value(lit))
+            .map(|&(coef, _)| coef)
+            .sum();
+        match self.relation {
+            Relation::AtMost => sum <= self.bound,
+            Relation::AtLeast => sum >= self.bound,
+            Relation::Equal => sum == self.bound,
+        }
+    }
+}
+
+impl<C: Callbacks> Solver<C> {
+    /// Adds the given pseudo-Boolean constraint to the solver. Equalities
+    /// are encoded as two inequalities, and each inequality is normalized to
+    /// positive coefficients and encoded with a sequential weight counter,
+    /// which uses one auxiliary variable for each reachable partial sum up
+    /// to the bound. This is efficient for small bounds, but the size grows
+    /// with the magnitude of the coefficients. The variables of the terms are
+    /// initialized first, so the auxiliary variables do not overlap them.
+    /// # Examples
+    /// ```
+    /// use cadical::encode::{PbConstraint, Relation};
+    /// let mut sat: cadical::Solver = Default::default();
+    /// sat.add_pb_constraint(&PbConstraint {
+    ///     terms: vec![(2, 1), (3, 2), (4, 3)],
+    ///     relation: Relation::AtMost,
+    ///     bound: 5,
+    /// });
+    /// assert_eq!(sat.solve_with([1, 2]), Some(true));
+    /// assert_eq!(sat.solve_with([1, 3]), Some(false));
+    /// ```
+    pub fn add_pb_constraint(&mut self, constraint: &PbConstraint) {
+        let terms = &constraint.terms;
+        let bound = constraint.bound;
+        // the auxiliary variables must come after the variables of the terms
+        if let Some(max_var) = terms.iter().map(|&(_, lit)| lit.abs()).max() {
+            self.reserve(max_var);
+        }
+        if constraint.relation != Relation::AtLeast {
+            self.add_pb_at_most(terms.iter().copied(), bound);
+        }
+        if constraint.relation != Relation::AtMost {
+            let negated = terms.iter().map(|&(coef, lit)| (-coef, lit));
+            self.add_pb_at_most(negated, -bound);
+        }
+    }
+
+    /// Returns a fresh variable that is equivalent to the conjunction of the
+    /// given literals. The conjunction of no literals is true.
+    /// # Examples
+    /// ```
+    /// let mut sat: cadical::Solver = Default::default();
+    /// let out = sat.and_gate([1, -2]);
+    /// assert_eq!(sat.solve_with([out, 2]), Some(false));
+    /// assert_eq!(sat.solve_with([-out, 1, -2]), Some(false));
+    /// ```
+    pub fn and_gate<I>(&mut self, lits: I) -> i32
+    where
+        I: IntoIterator<Item = i32>,
+    {
+        let lits: Vec<i32> = lits.into_iter().collect();
+        if let Some(max_var) = lits.iter().map(|lit| lit.abs()).max() {
+            self.reserve(max_var);
+        }
+        let out = self.new_variable();
+        self.define_and(out, &lits);
+        out
+    }
+
+    /// Returns a fresh variable that is equivalent to the disjunction of the
+    /// given literals. The disjunction of no literals is false.
+    pub fn or_gate<I>(&mut self, lits: I) -> i32
+    where
+        I: IntoIterator<Item = i32>,
+    {
+        let lits: Vec<i32> = lits.into_iter().map(|lit| -lit).collect();
+        if let Some(max_var) = lits.iter().map(|lit| lit.abs()).max() {
+            self.reserve(max_var);
+        }
+        let out = self.new_variable();
+        self.define_and(-out, &lits);
+        out
+    }
+
+    /// Returns a fresh variable that is equivalent to the exclusive or of
+    /// the two literals.
+    pub fn xor_gate(&mut self, lit1: i32, lit2: i32) -> i32 {
+        self.reserve(lit1.abs().max(lit2.abs()));
+        let out = self.new_variable();
+        self.add_clause([-out, lit1, lit2]);
+        self.add_clause([-out, -lit1, -lit2]);
+        self.add_clause([out, -lit1, lit2]);
+        self.add_clause([out, lit1, -lit2]);
+        out
+    }
+
+    /// Adds the clauses stating that the output literal is equivalent to the
+    /// conjunction of the given literals.
+    pub(crate) fn define_and(&mut self, out: i32, lits: &[i32]) {
+        for &lit in lits {
+            self.add_clause([-out, lit]);
+        }
+        self.add_clause(lits.iter().map(|&lit| -lit).chain(Some(out)));
+    }
+
+    /// Encodes that the weighted sum of the terms is at most the bound.
+    fn add_pb_at_most<I>(&mut self, terms: I, mut bound: i64)
+    where
+        I: Iterator<Item = (i64, i32)>,
+    {
+        // rewrite negative terms with the negated literal
+        let mut normal: Vec<(u64, i32)> = Vec::new();
+        for (coef, lit) in terms {
+            if coef > 0 {
+                normal.push((coef as u64, lit));
+            } else if coef < 0 {
+                bound -= coef;
+                normal.push((coef.unsigned_abs(), -lit));
+            }
+        }
+        if bound < 0 {
+            self.add_clause([]);
+            return;
+        }
+        let bound = bound as u64;
+
+        // terms larger than the bound are false, the others are counted
+        let mut counted = Vec::new();
+        for (coef, lit) in normal {
+            if coef > bound {
+                self.add_clause([-lit]);
+            } else {
+                counted.push((coef, lit));
+            }
+        }
+        if counted.iter().map(|&(coef, _)| coef).sum::<u64>() <= bound {
+            return;
+        }
+
+        // sums[j] is implied by the partial sum being at least j, where
+        // every sum above the bound is represented by the overflow sum
+        let overflow = bound + 1;
+        let mut sums: BTreeMap<u64, i32> = BTreeMap::new();
+        for (coef, lit) in counted {
+            let mut next: BTreeMap<u64, i32> = BTreeMap::new();
+            let mut get = |solver: &mut Self, sum: u64| {
+                *next
+                    .entry(sum.min(overflow))
+                    .or_insert_with(|| solver.new_variable())
+            };
+            let var = get(self, coef);
+            self.add_clause([-lit, var]);
+            for (&sum, &prev) in sums.iter() {
+                let var = get(self, sum);
+                self.add_clause([-prev, var]);
+                let var = get(self, sum + coef);
+                self.add_clause([-lit, -prev, var]);
+            }
+            sums = next;
+        }
+        if let Some(&var) = sums.get(&overflow) {
+            self.add_clause([-var]);
+        }
+    }
+}
@@ -0,0 +1,257 @@
+//! Encodings of finite operations and relations over the domain of the
+//! numbers from zero to `size - 1`, for the search of finite algebraic
+//! structures. The operations are encoded in one-hot form, with a variable
+//! for every tuple of arguments and possible value.
+
+use crate::{Callbacks, Solver};
+use std::collections::BTreeSet;
+
+/// Returns the index of the tuple of arguments in the tables, where the
+/// first argument is the most significant.
+fn tuple_index(size: usize, args: &[usize]) -> usize {
+    args.iter().fold(0, |index, &arg| {
+        assert!(arg < size);
+        index * size + arg
+    })
+}
+
+/// Returns the tuple of arguments with the given index.
+fn tuple_args(size: usize, arity: usize, mut index: usize) -> Vec<usize> {
+    let mut args = vec![0; arity];
+    for arg in args.iter_mut().rev() {
+        *arg = index % size;
+        index /= size;
+    }
+    args
+}
+
+/// Advances the permutation to the next one in lexicographic order, and
+/// returns `false` after the last one.
+fn next_permutation(perm: &mut [usize]) -> bool {
+    let pivot = match (1..perm.len()).rev().find(|&i| perm[i - 1] < perm[i]) {
+        Some(i) => i - 1,
+        None => return false,
+    };
+    let swap = (pivot + 1..perm.len())
+        .rev()
+        .find(|&i| perm[pivot] < perm[i])
+        .unwrap();
+    perm.swap(pivot, swap);
+    perm[pivot + 1..].reverse();
+    true
+}
+
+/// An operation of the given arity in one-hot encoding, with a variable for
+/// every tuple of arguments and value. The operation tables list the values
+/// of the tuples in lexicographic order.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Operation {
+    /// The size of the domain.
+    pub size: usize,
+    /// The number of arguments.
+    pub arity: usize,
+    vars: Vec<i32>,
+}
+
+impl Operation {
+    /// Returns the number of tuples of arguments.
+    pub fn tuples(&self) -> usize {
+        self.size.pow(self.arity as u32)
+    }
+
+    /// Returns the variable that is true if the operation maps the given
+    /// arguments to the value.
+    pub fn var(&self, args: &[usize], value: usize) -> i32 {
+        assert!(args.len() == self.arity && value < self.size);
+        self.vars[tuple_index(self.size, args) * self.size + value]
+    }
+
+    /// Returns the variables of the possible values at the given arguments.
+    pub fn values(&self, args: &[usize]) -> &[i32] {
+        assert!(args.len() == self.arity);
+        let start = tuple_index(self.size, args) * self.size;
+        &self.vars[start..start + self.size]
+    }
+
+    /// Returns the table of the operation in the last model of the solver,
+    /// where undefined values are reported as zero.
+    pub fn decode<C: Callbacks>(&self, sat: &Solver<C>) -> Vec<usize> {
+        self.vars
+            .chunks(self.size.max(1))
+            .map(|values| {
+                let value = values.iter().position(|&var| sat.value(var) == Some(true));
+                value.unwrap_or(0)
+            })
+            .collect()
+    }
+
+    /// Returns the table of the operation isomorphic to the given one under
+    /// the permutation of the domain.
+    pub fn permute_table(&self, table: &[usize], perm: &[usize]) -> Vec<usize> {
+        assert!(table.len() == self.tuples() && perm.len() == self.size);
+        let mut image = vec![0; table.len()];
+        for (index, &value) in table.iter().enumerate() {
+            let args = tuple_args(self.size, self.arity, index);
+            let args: Vec<usize> = args.iter().map(|&arg| perm[arg]).collect();
+            image[tuple_index(self.size, &args)] = perm[value];
+        }
+        image
+    }
+}
+
+/// A relation of the given arity with a variable for every tuple, which is
+/// true if the tuple belongs to the relation.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FiniteRelation {
+    /// The size of the domain.
+    pub size: usize,
+    /// The number of arguments.
+    pub arity: usize,
+    vars: Vec<i32>,
+}
+
+impl FiniteRelation {
+    /// Returns the variable that is true if the tuple belongs to the
+    /// relation.
+    pub fn var(&self, args: &[usize]) -> i32 {
+        assert!(args.len() == self.arity);
+        self.vars[tuple_index(self.size, args)]
+    }
+
+    /// Returns the membership of the tuples in lexicographic order in the
+    /// last model of the solver.
+    pub fn decode<C: Callbacks>(&self, sat: &Solver<C>) -> Vec<bool> {
+        self.vars
+            .iter()
+            .map(|&var| sat.value(var) == Some(true))
+            .collect()
+    }
+}
+
+impl<C: Callbacks> Solver<C> {
+    /// Allocates the variables of an operation of the given arity over the
+    /// domain of the given size. No constraints are added, use
+    /// `add_functional` and `add_total` to make it a function.
+    pub fn new_operation(&mut self, size: usize, arity: usize) -> Operation {
+        let count = size.pow(arity as u32) * size;
+        let vars = (0..count).map(|_| self.new_variable()).collect();
+        Operation { size, arity, vars }
+    }
+
+    /// Allocates the variables of a relation of the given arity over the
+    /// domain of the given size.
+    pub fn new_relation(&mut self, size: usize, arity: usize) -> FiniteRelation {
+        let count = size.pow(arity as u32);
+        let vars = (0..count).map(|_| self.new_variable()).collect();
+        FiniteRelation { size, arity, vars }
+    }
+
+    /// Encodes that the operation has at most one value at every tuple of
+    /// arguments.
+    pub fn add_functional(&mut self, op: &Operation) {
+        for values in op.vars.chunks(op.size.max(1)) {
+            for (i, &var1) in values.iter().enumerate() {
+                for &var2 in values[(i + 1)..].iter() {
+                    self.add_clause([-var1, -var2]);
+                }
+            }
+        }
+    }
+
+    /// Encodes that the operation has at least one value at every tuple of
+    /// arguments.
+    pub fn add_total(&mut self, op: &Operation) {
+        for values in op.vars.chunks(op.size.max(1)) {
+            self.add_clause(values.iter().copied());
+        }
+    }
+
+    /// Encodes that the binary operation is commutative.
+    pub fn add_commutative(&mut self, op: &Operation) {
+        assert!(op.arity == 2);
+        for a in 0..op.size {
+            for b in 0..op.size {
+                for c in 0..op.size {
+                    if a != b {
+                        self.add_clause([-op.var(&[a, b], c), op.var(&[b, a], c)]);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Encodes that the binary operation is associative with the flattened
+    /// clauses `ab = d, dc = e, bc = f -> af = e`, whose number is the sixth
+    /// power of the size of the domain.
+    /// # Examples
+    /// ```
+    /// let mut sat: cadical::Solver = Default::default();
+    /// let op = sat.new_operation(2, 2);
+    /// sat.add_functional(&op);
+    /// sat.add_total(&op);
+    /// sat.add_associative(&op);
+    /// let mut count = 0;
+    /// while sat.solve() == Some(true) {
+    ///     let table = op.decode(&sat);
+    ///     sat.block_isomorphic(&op, &table, |_| true);
+    ///     count += 1;
+    /// }
+    /// assert_eq!(count, 5);
+    /// ```
+    pub fn add_associative(&mut self, op: &Operation) {
+        assert!(op.arity == 2);
+        let n = op.size;
+        for a in 0..n {
+            for b in 0..n {
+                for c in 0..n {
+                    for d in 0..n {
+                        for e in 0..n {
+                            for f in 0..n {
+                                self.add_clause([
+                                    -op.var(&[a, b], d),
+                                    -op.var(&[d, c], e),
+                                    -op.var(&[b, c], f),
+                                    op.var(&[a, f], e),
+                                ]);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Adds a clause excluding the given table of the operation.
+    pub fn block_table(&mut self, op: &Operation, table: &[usize]) {
+        assert!(table.len() == op.tuples());
+        let clause = table
+            .iter()
+            .enumerate()
+            .map(|(index, &value)| -op.vars[index * op.size + value]);
+        self.add_clause(clause);
+    }
+
+    /// Excludes the tables isomorphic to the given one under the
+    /// permutations of the domain accepted by the hook, which can restrict
+    /// the isomorphisms, for example to those fixing some constants. This
+    /// adds a clause for every distinct image, so it is intended for small
+    /// domains where the number of permutations is manageable.
+    pub fn block_isomorphic<F>(&mut self, op: &Operation, table: &[usize], mut accept: F)
+    where
+        F: FnMut(&[usize]) -> bool,
+    {
+        let mut perm: Vec<usize> = (0..op.size).collect();
+        let mut images = BTreeSet::new();
+        loop {
+            if accept(&perm) {
+                images.insert(op.permute_table(table, &perm));
+            }
+            if !next_permutation(&mut perm) {
+                break;
+            }
+        }
+        for image in images.iter() {
+            self.block_table(op, image);
+        }
+    }
+}
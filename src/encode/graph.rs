@@ -0,0 +1,465 @@
+//! Encodings of graph problems over simple undirected graphs. The vertices
+//! are numbered from zero, and the problems return the variables they
+//! allocate, so the solution can be decoded from a model.
+
+use super::{PbConstraint, Relation};
+use crate::{Callbacks, Solver};
+
+/// A simple undirected graph given by the number of its vertices and the
+/// list of its edges. Loops and repeated edges are allowed but ignored.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Graph {
+    /// The number of vertices, which are numbered from zero.
+    pub vertices: usize,
+    /// The edges as pairs of vertices.
+    pub edges: Vec<(usize, usize)>,
+}
+
+impl Graph {
+    /// Creates a graph with the given number of vertices and no edges.
+    pub fn new(vertices: usize) -> Self {
+        Graph {
+            vertices,
+            edges: Vec::new(),
+        }
+    }
+
+    /// Adds an edge between the two vertices.
+    pub fn add_edge(&mut self, u: usize, v: usize) {
+        assert!(u < self.vertices && v < self.vertices);
+        self.edges.push((u, v));
+    }
+
+    /// Returns the adjacency matrix of the graph without loops.
+    pub fn adjacency(&self) -> Vec<Vec<bool>> {
+        let mut matrix = vec![vec![false; self.vertices]; self.vertices];
+        for &(u, v) in self.edges.iter() {
+            if u != v {
+                matrix[u][v] = true;
+                matrix[v][u] = true;
+            }
+        }
+        matrix
+    }
+
+    /// Returns the complement of the graph, where two different vertices
+    /// are adjacent if and only if they are not adjacent in this graph.
+    pub fn complement(&self) -> Graph {
+        let matrix = self.adjacency();
+        let mut graph = Graph::new(self.vertices);
+        for (u, row) in matrix.iter().enumerate() {
+            for (v, &adjacent) in row.iter().enumerate().skip(u + 1) {
+                if !adjacent {
+                    graph.edges.push((u, v));
+                }
+            }
+        }
+        graph
+    }
+
+    /// Returns the edges without loops and repetitions, with the smaller
+    /// vertex first.
+    fn simple_edges(&self) -> Vec<(usize, usize)> {
+        let mut edges: Vec<(usize, usize)> = self
+            .edges
+            .iter()
+            .filter(|&&(u, v)| u != v)
+            .map(|&(u, v)| (u.min(v), u.max(v)))
+            .collect();
+        edges.sort_unstable();
+        edges.dedup();
+        edges
+    }
+}
+
+/// The variables of a coloring, where `vars[v][c]` is true if vertex `v`
+/// has color `c`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Coloring {
+    /// The variables of the vertices and colors.
+    pub vars: Vec<Vec<i32>>,
+}
+
+impl Coloring {
+    /// Returns the color of each vertex in the last model of the solver.
+    pub fn decode<C: Callbacks>(&self, sat: &Solver<C>) -> Vec<usize> {
+        self.vars
+            .iter()
+            .map(|colors| {
+                let color = colors.iter().position(|&var| sat.value(var) == Some(true));
+                color.unwrap_or(0)
+            })
+            .collect()
+    }
+}
+
+/// The variables of a set of vertices, where `vars[v]` is true if vertex
+/// `v` belongs to the set.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VertexSet {
+    /// The variables of the vertices.
+    pub vars: Vec<i32>,
+}
+
+impl VertexSet {
+    /// Returns the vertices of the set in the last model of the solver.
+    pub fn decode<C: Callbacks>(&self, sat: &Solver<C>) -> Vec<usize> {
+        (0..self.vars.len())
+            .filter(|&v| sat.value(self.vars[v]) == Some(true))
+            .collect()
+    }
+}
+
+/// The encoding used by the Hamiltonian path and cycle encoders.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum HamiltonianEncoding {
+    /// Every vertex is assigned a position in the path or cycle, and
+    /// consecutive positions must be occupied by adjacent vertices. This
+    /// uses a quadratic number of variables.
+    #[default]
+    Position,
+    /// Every vertex chooses a successor along an edge, and a transitive
+    /// ordering of the vertices excludes subcycles. This uses a quadratic
+    /// number of variables and a cubic number of clauses, but propagates
+    /// well on sparse graphs.
+    Ordering,
+}
+
+/// The variables of a Hamiltonian path or cycle. Only the variables of the
+/// chosen encoding are present, the other table is empty.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Hamiltonian {
+    /// The position variables, where `positions[v][p]` is true if vertex
+    /// `v` is at position `p`.
+    pub positions: Vec<Vec<i32>>,
+    /// The successor variables, where `successors[u][v]` is true if vertex
+    /// `v` directly follows vertex `u`, and zero if they are not adjacent.
+    pub successors: Vec<Vec<i32>>,
+}
+
+impl Hamiltonian {
+    /// Returns the vertices in the order of the path or cycle in the last
+    /// model of the solver. Cycles are returned starting at vertex zero
+    /// by the ordering encoding.
+    pub fn decode<C: Callbacks>(&self, sat: &Solver<C>) -> Vec<usize> {
+        let holds = |var: i32| var != 0 && sat.value(var) == Some(true);
+        if !self.positions.is_empty() {
+            let n = self.positions.len();
+            return (0..n)
+                .filter_map(|p| (0..n).find(|&v| holds(self.positions[v][p])))
+                .collect();
+        }
+        let n = self.successors.len();
+        let first = (0..n)
+            .find(|&v| !(0..n).any(|u| holds(self.successors[u][v])))
+            .unwrap_or(0);
+        let mut order = Vec::with_capacity(n);
+        let mut next = Some(first);
+        while let Some(u) = next {
+            if n <= order.len() {
+                break;
+            }
+            order.push(u);
+            next = (0..n).find(|&v| holds(self.successors[u][v]));
+        }
+        order
+    }
+}
+
+impl<C: Callbacks> Solver<C> {
+    /// Allocates one variable for each vertex of the graph.
+    fn vertex_vars(&mut self, graph: &Graph) -> Vec<i32> {
+        (0..graph.vertices).map(|_| self.new_variable()).collect()
+    }
+
+    /// Adds the pairwise encoding of the constraint that exactly one of the
+    /// literals is true.
+    fn add_exactly_one(&mut self, lits: &[i32]) {
+        self.add_clause(lits.iter().copied());
+        for (i, &lit1) in lits.iter().enumerate() {
+            for &lit2 in lits[(i + 1)..].iter() {
+                self.add_clause([-lit1, -lit2]);
+            }
+        }
+    }
+
+    /// Adds a cardinality constraint on the selected vertices.
+    fn add_vertex_count(&mut self, vars: &[i32], relation: Relation, size: usize) {
+        self.add_pb_constraint(&PbConstraint {
+            terms: vars.iter().map(|&var| (1, var)).collect(),
+            relation,
+            bound: size as i64,
+        });
+    }
+
+    /// Encodes that the graph has a proper coloring with the given number of
+    /// colors, where every vertex has exactly one color and adjacent
+    /// vertices have different colors.
+    /// # Examples
+    /// ```
+    /// use cadical::encode::graph::Graph;
+    /// let mut triangle = Graph::new(3);
+    /// triangle.add_edge(0, 1);
+    /// triangle.add_edge(1, 2);
+    /// triangle.add_edge(2, 0);
+    /// let mut sat: cadical::Solver = Default::default();
+    /// let coloring = sat.add_coloring(&triangle, 3);
+    /// assert_eq!(sat.solve(), Some(true));
+    /// let colors = coloring.decode(&sat);
+    /// assert!(colors[0] != colors[1] && colors[1] != colors[2] && colors[2] != colors[0]);
+    /// ```
+    pub fn add_coloring(&mut self, graph: &Graph, colors: usize) -> Coloring {
+        let vars: Vec<Vec<i32>> = (0..graph.vertices)
+            .map(|_| (0..colors).map(|_| self.new_variable()).collect())
+            .collect();
+        for row in vars.iter() {
+            self.add_exactly_one(row);
+        }
+        for (u, v) in graph.simple_edges() {
+            for (&var1, &var2) in vars[u].iter().zip(vars[v].iter()) {
+                self.add_clause([-var1, -var2]);
+            }
+        }
+        Coloring { vars }
+    }
+
+    /// Encodes that the graph has a clique with at least the given number of
+    /// vertices.
+    pub fn add_clique(&mut self, graph: &Graph, size: usize) -> VertexSet {
+        self.add_independent_set(&graph.complement(), size)
+    }
+
+    /// Encodes that the graph has an independent set, whose vertices are
+    /// pairwise non-adjacent, with at least the given number of vertices.
+    pub fn add_independent_set(&mut self, graph: &Graph, size: usize) -> VertexSet {
+        let vars = self.vertex_vars(graph);
+        for (u, v) in graph.simple_edges() {
+            self.add_clause([-vars[u], -vars[v]]);
+        }
+        self.add_vertex_count(&vars, Relation::AtLeast, size);
+        VertexSet { vars }
+    }
+
+    /// Encodes that the graph has a vertex cover, which contains at least
+    /// one endpoint of every edge, with at most the given number of
+    /// vertices.
+    pub fn add_vertex_cover(&mut self, graph: &Graph, size: usize) -> VertexSet {
+        let vars = self.vertex_vars(graph);
+        for (u, v) in graph.simple_edges() {
+            self.add_clause([vars[u], vars[v]]);
+        }
+        for &(u, v) in graph.edges.iter() {
+            if u == v {
+                // a loop can only be covered by its vertex
+                self.add_clause([vars[u]]);
+            }
+        }
+        self.add_vertex_count(&vars, Relation::AtMost, size);
+        VertexSet { vars }
+    }
+
+    /// Encodes that the graph has a Hamiltonian path, which visits every
+    /// vertex exactly once. With symmetry breaking, the reversal of the path
+    /// is excluded by requiring the first vertex to be smaller than the
+    /// last one.
+    pub fn add_hamiltonian_path(
+        &mut self,
+        graph: &Graph,
+        encoding: HamiltonianEncoding,
+        symmetry_breaking: bool,
+    ) -> Hamiltonian {
+        self.add_hamiltonian(graph, false, encoding, symmetry_breaking)
+    }
+
+    /// Encodes that the graph has a Hamiltonian cycle, which visits every
+    /// vertex exactly once and returns to the first one. A cycle needs at
+    /// least three vertices, so smaller graphs are unsatisfiable unless
+    /// they have no vertices at all. With symmetry breaking, the rotations
+    /// of the cycle are excluded by starting it at vertex zero, and its
+    /// reversal by requiring the second vertex to be smaller than the last
+    /// one.
+    /// # Examples
+    /// ```
+    /// use cadical::encode::graph::{Graph, HamiltonianEncoding};
+    /// let mut cube = Graph::new(8);
+    /// for u in 0..8 {
+    ///     for bit in [1, 2, 4] {
+    ///         if u & bit == 0 {
+    ///             cube.add_edge(u, u | bit);
+    ///         }
+    ///     }
+    /// }
+    /// let mut sat: cadical::Solver = Default::default();
+    /// let cycle = sat.add_hamiltonian_cycle(&cube, HamiltonianEncoding::Ordering, true);
+    /// assert_eq!(sat.solve(), Some(true));
+    /// let order = cycle.decode(&sat);
+    /// assert_eq!(order.len(), 8);
+    /// assert_eq!(order[0], 0);
+    /// assert!(order[1] < order[7]);
+    /// ```
+    pub fn add_hamiltonian_cycle(
+        &mut self,
+        graph: &Graph,
+        encoding: HamiltonianEncoding,
+        symmetry_breaking: bool,
+    ) -> Hamiltonian {
+        self.add_hamiltonian(graph, true, encoding, symmetry_breaking)
+    }
+
+    /// Encodes a Hamiltonian path or cycle with the given encoding.
+    fn add_hamiltonian(
+        &mut self,
+        graph: &Graph,
+        cycle: bool,
+        encoding: HamiltonianEncoding,
+        symmetry_breaking: bool,
+    ) -> Hamiltonian {
+        let n = graph.vertices;
+        if cycle && (1..3).contains(&n) {
+            self.add_clause([]);
+        }
+        let mut matrix = graph.adjacency();
+        match encoding {
+            HamiltonianEncoding::Position => Hamiltonian {
+                positions: self.add_hamiltonian_positions(&matrix, cycle, symmetry_breaking),
+                successors: Vec::new(),
+            },
+            HamiltonianEncoding::Ordering => {
+                if !cycle && n > 0 {
+                    // a path is a cycle through an extra vertex adjacent to all
+                    matrix.iter_mut().for_each(|row| row.push(true));
+                    matrix.push(vec![true; n + 1]);
+                    matrix[n][n] = false;
+                }
+                let root = if cycle { 0 } else { n };
+                let mut successors =
+                    self.add_hamiltonian_ordering(&matrix, root, symmetry_breaking);
+                successors.truncate(n);
+                successors.iter_mut().for_each(|row| row.truncate(n));
+                Hamiltonian {
+                    positions: Vec::new(),
+                    successors,
+                }
+            }
+        }
+    }
+
+    /// Adds the position encoding of a Hamiltonian path or cycle and
+    /// returns the position variables.
+    fn add_hamiltonian_positions(
+        &mut self,
+        matrix: &[Vec<bool>],
+        cycle: bool,
+        symmetry_breaking: bool,
+    ) -> Vec<Vec<i32>> {
+        let n = matrix.len();
+        let vars: Vec<Vec<i32>> = (0..n)
+            .map(|_| (0..n).map(|_| self.new_variable()).collect())
+            .collect();
+        for row in vars.iter() {
+            self.add_exactly_one(row);
+        }
+        for p in 0..n {
+            let column: Vec<i32> = vars.iter().map(|row| row[p]).collect();
+            self.add_exactly_one(&column);
+        }
+        let steps = if cycle { n } else { n.saturating_sub(1) };
+        for p in 0..steps {
+            let q = (p + 1) % n;
+            for (u, row) in matrix.iter().enumerate() {
+                let mut clause = vec![-vars[u][p]];
+                for (v, &adjacent) in row.iter().enumerate() {
+                    if adjacent {
+                        clause.push(vars[v][q]);
+                    }
+                }
+                self.add_clause(clause);
+            }
+        }
+        if symmetry_breaking && n >= 2 {
+            let first = if cycle {
+                self.add_clause([vars[0][0]]);
+                1
+            } else {
+                0
+            };
+            for a in 0..n {
+                for b in 0..a {
+                    self.add_clause([-vars[a][first], -vars[b][n - 1]]);
+                }
+            }
+        }
+        vars
+    }
+
+    /// Adds the ordering encoding of a Hamiltonian cycle, where the
+    /// vertices other than the root are ordered along the cycle, and
+    /// returns the successor variables.
+    fn add_hamiltonian_ordering(
+        &mut self,
+        matrix: &[Vec<bool>],
+        root: usize,
+        symmetry_breaking: bool,
+    ) -> Vec<Vec<i32>> {
+        let n = matrix.len();
+        let successors: Vec<Vec<i32>> = matrix
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .map(|&adjacent| if adjacent { self.new_variable() } else { 0 })
+                    .collect()
+            })
+            .collect();
+        for u in 0..n {
+            let outgoing: Vec<i32> = successors[u]
+                .iter()
+                .copied()
+                .filter(|&var| var != 0)
+                .collect();
+            self.add_exactly_one(&outgoing);
+            let incoming: Vec<i32> = successors
+                .iter()
+                .map(|row| row[u])
+                .filter(|&var| var != 0)
+                .collect();
+            self.add_exactly_one(&incoming);
+        }
+        // before[u * n + v] is true if u precedes v when starting at the root
+        let mut before = vec![0; n * n];
+        for u in 0..n {
+            for v in (u + 1)..n {
+                if u != root && v != root {
+                    let var = self.new_variable();
+                    before[u * n + v] = var;
+                    before[v * n + u] = -var;
+                }
+            }
+        }
+        for a in 0..n {
+            for b in 0..n {
+                for c in 0..n {
+                    if a != b && b != c && a != c && root != a && root != b && root != c {
+                        self.add_clause([
+                            -before[a * n + b],
+                            -before[b * n + c],
+                            before[a * n + c],
+                        ]);
+                    }
+                }
+                if successors[a][b] != 0 && a != root && b != root {
+                    self.add_clause([-successors[a][b], before[a * n + b]]);
+                }
+            }
+        }
+        if symmetry_breaking {
+            for a in 0..n {
+                for b in 0..a {
+                    if successors[root][a] != 0 && successors[b][root] != 0 {
+                        self.add_clause([-successors[root][a], -successors[b][root]]);
+                    }
+                }
+            }
+        }
+        successors
+    }
+}
@@ -0,0 +1,165 @@
+//! Encodings of Latin squares and quasigroups, whose multiplication tables
+//! contain every element exactly once in every row and column, as binary
+//! operations of the `algebra` module.
+
+use super::algebra::Operation;
+use crate::{Callbacks, Error, Solver};
+
+/// The encoding of the all-different constraints of Latin squares.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum LatinEncoding {
+    /// Every cell has at least one value and no value occurs twice in a
+    /// row or column, with pairwise clauses. This is complete on its own.
+    Minimal,
+    /// The minimal encoding together with the redundant constraints that
+    /// every cell has at most one value and every value occurs in every row
+    /// and column, which usually propagate better.
+    #[default]
+    Extended,
+    /// The extended encoding with sequential counters instead of pairwise
+    /// clauses, which has linear instead of quadratic size in the order.
+    Sequential,
+}
+
+/// A partially filled Latin square, where the empty cells are `None`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PartialSquare {
+    /// The order of the square.
+    pub size: usize,
+    /// The cells in row-major order.
+    pub cells: Vec<Option<usize>>,
+}
+
+impl PartialSquare {
+    /// Parses a partial square, where every non-empty line is a row of
+    /// whitespace separated cells, which are either values starting from
+    /// zero or `.` for the empty cells.
+    /// # Examples
+    /// ```
+    /// use cadical::encode::latin::{LatinEncoding, PartialSquare};
+    /// let square = PartialSquare::parse("0 1 .\n. . 0\n. 0 .\n").unwrap();
+    /// let mut sat: cadical::Solver = Default::default();
+    /// let op = sat.new_quasigroup(3, LatinEncoding::Extended);
+    /// sat.add_partial_square(&op, &square);
+    /// assert_eq!(sat.solve(), Some(true));
+    /// assert_eq!(op.decode(&sat), vec![0, 1, 2, 1, 2, 0, 2, 0, 1]);
+    /// ```
+    pub fn parse(text: &str) -> Result<Self, Error> {
+        let rows: Vec<&str> = text
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .collect();
+        let size = rows.len();
+        let mut cells = Vec::with_capacity(size * size);
+        for row in rows.iter() {
+            let start = cells.len();
+            for word in row.split_whitespace() {
+                let cell = match word {
+                    "." => None,
+                    _ => Some(word.parse().map_err(|_| Error::new("invalid cell"))?),
+                };
+                cells.push(cell);
+            }
+            if cells.len() - start != size {
+                return Err(Error::new("not a square"));
+            }
+        }
+        if cells.iter().flatten().any(|&value| value >= size) {
+            return Err(Error::new("value out of range"));
+        }
+        Ok(PartialSquare { size, cells })
+    }
+}
+
+impl<C: Callbacks> Solver<C> {
+    /// Adds the sequential counter encoding of the constraint that at most
+    /// one of the literals is true.
+    fn add_at_most_one_sequential(&mut self, lits: &[i32]) {
+        if lits.len() <= 1 {
+            return;
+        }
+        let mut prev = self.new_variable();
+        self.add_clause([-lits[0], prev]);
+        for &lit in lits[1..(lits.len() - 1)].iter() {
+            let next = self.new_variable();
+            self.add_clause([-lit, next]);
+            self.add_clause([-prev, next]);
+            self.add_clause([-lit, -prev]);
+            prev = next;
+        }
+        self.add_clause([-lits[lits.len() - 1], -prev]);
+    }
+
+    /// Adds the constraint that at most one of the literals is true.
+    fn add_at_most_one_with(&mut self, lits: &[i32], encoding: LatinEncoding) {
+        if encoding == LatinEncoding::Sequential {
+            self.add_at_most_one_sequential(lits);
+        } else {
+            for (i, &lit1) in lits.iter().enumerate() {
+                for &lit2 in lits[(i + 1)..].iter() {
+                    self.add_clause([-lit1, -lit2]);
+                }
+            }
+        }
+    }
+
+    /// Encodes that the operation takes different values at the given
+    /// tuples of arguments. With the extended and sequential encodings,
+    /// every value must also occur if there are as many tuples as elements.
+    pub fn add_all_different(
+        &mut self,
+        op: &Operation,
+        cells: &[Vec<usize>],
+        encoding: LatinEncoding,
+    ) {
+        for value in 0..op.size {
+            let lits: Vec<i32> = cells.iter().map(|args| op.var(args, value)).collect();
+            self.add_at_most_one_with(&lits, encoding);
+            if encoding != LatinEncoding::Minimal && cells.len() == op.size {
+                self.add_clause(lits);
+            }
+        }
+    }
+
+    /// Encodes that the table of the binary operation is a Latin square,
+    /// so the operation is a quasigroup.
+    pub fn add_latin_square(&mut self, op: &Operation, encoding: LatinEncoding) {
+        assert!(op.arity == 2);
+        let n = op.size;
+        for a in 0..n {
+            for b in 0..n {
+                let values = op.values(&[a, b]).to_vec();
+                self.add_clause(values.iter().copied());
+                if encoding != LatinEncoding::Minimal {
+                    self.add_at_most_one_with(&values, encoding);
+                }
+            }
+        }
+        for a in 0..n {
+            let row: Vec<Vec<usize>> = (0..n).map(|b| vec![a, b]).collect();
+            self.add_all_different(op, &row, encoding);
+            let column: Vec<Vec<usize>> = (0..n).map(|b| vec![b, a]).collect();
+            self.add_all_different(op, &column, encoding);
+        }
+    }
+
+    /// Allocates a binary operation of the given order and encodes that it
+    /// is a quasigroup.
+    pub fn new_quasigroup(&mut self, size: usize, encoding: LatinEncoding) -> Operation {
+        let op = self.new_operation(size, 2);
+        self.add_latin_square(&op, encoding);
+        op
+    }
+
+    /// Fixes the values of the filled cells of the partial square in the
+    /// table of the binary operation, so the solver completes the square.
+    pub fn add_partial_square(&mut self, op: &Operation, square: &PartialSquare) {
+        assert!(op.arity == 2 && op.size == square.size);
+        let n = square.size;
+        for (index, cell) in square.cells.iter().enumerate() {
+            if let Some(value) = *cell {
+                self.add_clause([op.var(&[index / n, index % n], value)]);
+            }
+        }
+    }
+}
@@ -0,0 +1,160 @@
+//! Extraction of the binary implication graph and the classes of equivalent
+//! literals from the irredundant clauses of the solver.
+
+use super::{Callbacks, Solver};
+
+impl<C: Callbacks> Solver<C> {
+    /// Returns the binary clauses of the current formula as obtained by
+    /// `traverse_clauses`. Each binary clause `[a, b]` represents the two
+    /// implications `-a => b` and `-b => a`.
+    pub fn binary_clauses(&self) -> Vec<[i32; 2]> {
+        let mut binaries = Vec::new();
+        self.traverse_clauses(|clause| {
+            if clause.len() == 2 {
+                binaries.push([clause[0], clause[1]]);
+            }
+            true
+        });
+        binaries
+    }
+
+    /// Returns the classes of equivalent literals, which are the strongly
+    /// connected components of the binary implication graph with at least
+    /// two literals. Each class is sorted by variable index, and only one
+    /// of a class and its negation is returned, namely the one where the
+    /// first literal is positive. A class containing a literal together
+    /// with its negation proves that the formula is unsatisfiable.
+    /// # Examples
+    /// ```
+    /// let mut sat: cadical::Solver = Default::default();
+    /// sat.add_clause([-1, 2]);
+    /// sat.add_clause([-2, -3]);
+    /// sat.add_clause([3, 1]);
+    /// assert_eq!(sat.equivalences(), vec![vec![1, 2, -3]]);
+    /// ```
+    pub fn equivalences(&self) -> Vec<Vec<i32>> {
+        let graph = ImplicationGraph::new(self.max_variable(), &self.binary_clauses());
+        let mut classes: Vec<Vec<i32>> = graph
+            .components()
+            .into_iter()
+            .filter_map(|mut class| {
+                class.sort_unstable_by_key(|lit| (lit.abs(), *lit < 0));
+                if class[0] > 0 {
+                    Some(class)
+                } else {
+                    None
+                }
+            })
+            .collect();
+        classes.sort_unstable_by_key(|class| class[0]);
+        classes
+    }
+}
+
+/// The binary implication graph in compressed adjacency form, where the
+/// literal `lit` is represented by the node `2 * (lit.abs() - 1)` if it is
+/// positive, and by the next node if it is negative.
+struct ImplicationGraph {
+    starts: Vec<usize>,
+    targets: Vec<usize>,
+}
+
+impl ImplicationGraph {
+    fn new(max_variable: i32, binaries: &[[i32; 2]]) -> Self {
+        let size = 2 * max_variable.max(0) as usize;
+        let mut starts = vec![0; size + 1];
+        for &[a, b] in binaries {
+            starts[node(-a)] += 1;
+            starts[node(-b)] += 1;
+        }
+        for i in 0..size {
+            starts[i + 1] += starts[i];
+        }
+        let mut targets = vec![0; starts[size]];
+        for &[a, b] in binaries {
+            starts[node(-a)] -= 1;
+            targets[starts[node(-a)]] = node(b);
+            starts[node(-b)] -= 1;
+            targets[starts[node(-b)]] = node(a);
+        }
+        ImplicationGraph { starts, targets }
+    }
+
+    /// Returns the strongly connected components with at least two nodes
+    /// using an iterative version of Tarjan's algorithm.
+    fn components(&self) -> Vec<Vec<i32>> {
+        const UNVISITED: usize = usize::MAX;
+        let size = self.starts.len() - 1;
+        let mut index = vec![UNVISITED; size];
+        let mut lowlink = vec![0; size];
+        let mut on_stack = vec![false; size];
+        let mut stack = Vec::new();
+        let mut calls: Vec<(usize, usize)> = Vec::new();
+        let mut counter = 0;
+        let mut components = Vec::new();
+
+        for root in 0..size {
+            if index[root] != UNVISITED {
+                continue;
+            }
+            index[root] = counter;
+            lowlink[root] = counter;
+            counter += 1;
+            stack.push(root);
+            on_stack[root] = true;
+            calls.push((root, self.starts[root]));
+
+            while let Some(&(node, edge)) = calls.last() {
+                if edge < self.starts[node + 1] {
+                    calls.last_mut().unwrap().1 += 1;
+                    let next = self.targets[edge];
+                    if index[next] == UNVISITED {
+                        index[next] = counter;
+                        lowlink[next] = counter;
+                        counter += 1;
+                        stack.push(next);
+                        on_stack[next] = true;
+                        calls.push((next, self.starts[next]));
+                    } else if on_stack[next] {
+                        lowlink[node] = lowlink[node].min(index[next]);
+                    }
+                    continue;
+                }
+
+                calls.pop();
+                if let Some(&(parent, _)) = calls.last() {
+                    lowlink[parent] = lowlink[parent].min(lowlink[node]);
+                }
+                if lowlink[node] == index[node] {
+                    let mut component = Vec::new();
+                    loop {
+                        let member = stack.pop().unwrap();
+                        on_stack[member] = false;
+                        component.push(literal(member));
+                        if member == node {
+                            break;
+                        }
+                    }
+                    if component.len() >= 2 {
+                        components.push(component);
+                    }
+                }
+            }
+        }
+        components
+    }
+}
+
+fn node(lit: i32) -> usize {
+    debug_assert!(lit != 0 && lit != i32::MIN);
+    2 * (lit.unsigned_abs() as usize - 1) + (lit < 0) as usize
+}
+
+fn literal(node: usize) -> i32 {
+    let var = (node / 2 + 1) as i32;
+    if node & 1 == 0 {
+        var
+    } else {
+        -var
+    }
+}
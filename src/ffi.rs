@@ -0,0 +1,249 @@
+//! The raw C interface of the bundled CaDiCaL library, for functionality that
+//! is not covered by the safe `Solver` wrapper yet. The solver pointers are
+//! created by `ccadical_init` and must be released by `ccadical_release`.
+//! Mixing these calls with a `Solver` is not supported, because the wrapper
+//! keeps its own state (callbacks, scopes and assumptions) next to CaDiCaL.
+//! All functions are `unsafe`, and the caller is responsible for upholding
+//! the preconditions of the corresponding CaDiCaL API calls (in particular
+//! for calling them only in the allowed solver states).
+
+#[cfg(all(feature = "pure-rust", not(miri)))]
+pub use super::cdcl::*;
+#[cfg(miri)]
+pub use super::mockup::*;
+#[cfg(not(any(miri, feature = "pure-rust")))]
+use std::os::raw::{c_char, c_int, c_void};
+
+#[cfg(not(any(miri, feature = "pure-rust")))]
+extern "C" {
+    /// Returns the name and version of the CaDiCaL library.
+    pub fn ccadical_signature() -> *const c_char;
+    /// Creates a new solver instance.
+    pub fn ccadical_init() -> *mut c_void;
+    /// Releases a solver instance created by `ccadical_init`.
+    pub fn ccadical_release(ptr: *mut c_void);
+    /// Adds a literal to the current clause, or finishes it when zero.
+    pub fn ccadical_add(ptr: *mut c_void, lit: c_int);
+    /// Assumes a literal for the next solve call.
+    pub fn ccadical_assume(ptr: *mut c_void, lit: c_int);
+    /// Adds a literal to the constraint clause, or finishes it when zero.
+    pub fn ccadical_constrain(ptr: *mut c_void, lit: c_int);
+    /// Returns nonzero if the constraint clause was used to prove
+    /// unsatisfiability.
+    pub fn ccadical_constraint_failed(ptr: *mut c_void) -> c_int;
+    /// Removes all assumptions.
+    pub fn ccadical_reset_assumptions(ptr: *mut c_void);
+    /// Removes the constraint clause.
+    pub fn ccadical_reset_constraint(ptr: *mut c_void);
+    /// Solves the formula, returns 10 if satisfiable, 20 if unsatisfiable and
+    /// 0 if interrupted.
+    pub fn ccadical_solve(ptr: *mut c_void) -> c_int;
+    /// Solves the formula like `ccadical_solve`, but returns -1 if a native
+    /// allocation failed.
+    pub fn ccadical_solve_checked(ptr: *mut c_void) -> c_int;
+    /// Returns `lit` if the literal is true and `-lit` if it is false in the
+    /// model.
+    pub fn ccadical_val(ptr: *mut c_void, lit: c_int) -> c_int;
+    /// Returns 1 if the assumed literal was used to prove unsatisfiability.
+    pub fn ccadical_failed(ptr: *mut c_void, lit: c_int) -> c_int;
+    /// Installs or removes the terminate callback with its data pointer.
+    pub fn ccadical_set_terminate(
+        ptr: *mut c_void,
+        data: *const c_void,
+        cbs: Option<extern "C" fn(*const c_void) -> c_int>,
+    );
+    /// Installs a terminator that polls the given byte with an atomic load
+    /// and terminates the search once it is nonzero, and that publishes the
+    /// statistics into the given block with `ccadical_publish_stats` each
+    /// time it is polled. Either pointer can be null, and the terminator is
+    /// removed if both are. It replaces any callback installed by
+    /// `ccadical_set_terminate`.
+    pub fn ccadical_set_terminate_flag(ptr: *mut c_void, flag: *const u8, stats: *mut i64);
+    /// Copies the options, the irredundant clauses and the reconstruction
+    /// stack of the solver into the other one, which must be freshly created.
+    /// Returns -1 if a native allocation failed.
+    pub fn ccadical_copy(ptr: *mut c_void, other: *mut c_void) -> c_int;
+    /// Writes the sequence number, conflicts, decisions, restarts and native
+    /// memory usage into the block of five words. The sequence number is odd
+    /// while the block is being written.
+    pub fn ccadical_publish_stats(ptr: *mut c_void, stats: *mut i64);
+    /// Installs or removes the callback receiving zero terminated learned
+    /// clauses of length at most `max_len`.
+    pub fn ccadical_set_learn(
+        ptr: *mut c_void,
+        data: *const c_void,
+        max_len: c_int,
+        cbs: Option<extern "C" fn(*const c_void, *const c_int)>,
+    );
+    /// Installs or removes the callback receiving learned clauses of length
+    /// at most `max_len` and glue at most `max_glue` together with their
+    /// length, without a terminator. It replaces any callback installed by
+    /// `ccadical_set_learn`.
+    pub fn ccadical_set_learn_length(
+        ptr: *mut c_void,
+        data: *const c_void,
+        max_len: c_int,
+        max_glue: c_int,
+        cbs: Option<extern "C" fn(*const c_void, *const c_int, usize)>,
+    );
+    /// Returns the result of the last solve call (10, 20 or 0).
+    pub fn ccadical_status(ptr: *mut c_void) -> c_int;
+    /// Returns the maximal variable index.
+    pub fn ccadical_vars(ptr: *mut c_void) -> c_int;
+    /// Returns the number of active variables.
+    pub fn ccadical_active(ptr: *mut c_void) -> i64;
+    /// Returns the number of irredundant clauses.
+    pub fn ccadical_irredundant(ptr: *mut c_void) -> i64;
+    /// Reads a DIMACS file, returns an error message or null on success.
+    pub fn ccadical_read_dimacs(
+        ptr: *mut c_void,
+        path: *const c_char,
+        vars: *mut c_int,
+        strict: c_int,
+    ) -> *const c_char;
+    /// Writes the formula in DIMACS format, returns an error message or null
+    /// on success.
+    pub fn ccadical_write_dimacs(
+        ptr: *mut c_void,
+        path: *const c_char,
+        min_max_var: c_int,
+    ) -> *const c_char;
+    /// Applies a configuration preset, returns nonzero on success.
+    pub fn ccadical_configure(ptr: *mut c_void, name: *const c_char) -> c_int;
+    /// Sets a search limit for the next solve call, returns nonzero on
+    /// success.
+    pub fn ccadical_limit2(ptr: *mut c_void, name: *const c_char, limit: c_int) -> c_int;
+    /// Sets an option, returns nonzero on success.
+    pub fn ccadical_set_option2(ptr: *mut c_void, name: *const c_char, val: c_int) -> c_int;
+    /// Returns the current value of an option.
+    pub fn ccadical_get_option(ptr: *mut c_void, name: *const c_char) -> c_int;
+    /// Stores the range of an option, returns nonzero if the option exists.
+    pub fn ccadical_option_range(
+        ptr: *mut c_void,
+        name: *const c_char,
+        min: *mut c_int,
+        max: *mut c_int,
+    ) -> c_int;
+    /// Returns the default value of an option.
+    pub fn ccadical_option_default(ptr: *mut c_void, name: *const c_char) -> c_int;
+    /// Returns the name of the option with the given index, or null if the
+    /// index is past the last option.
+    pub fn ccadical_option_name(ptr: *mut c_void, index: c_int) -> *const c_char;
+    /// Sets an option in any state of the solver, returns nonzero on success.
+    pub fn ccadical_set_option_live(ptr: *mut c_void, name: *const c_char, val: c_int) -> c_int;
+    /// Adds the clause of the given length at once, without the zero
+    /// terminator. Returns -1 if a native allocation failed.
+    pub fn ccadical_add_clause(ptr: *mut c_void, lits: *const c_int, len: usize) -> c_int;
+    /// Assumes all literals of the given array for the next solve call.
+    /// Returns -1 if a native allocation failed.
+    pub fn ccadical_assume_all(ptr: *mut c_void, lits: *const c_int, len: usize) -> c_int;
+    /// Makes sure that variables up to `min_max_var` are initialized.
+    /// Returns -1 if a native allocation failed.
+    pub fn ccadical_reserve(ptr: *mut c_void, min_max_var: c_int) -> c_int;
+    /// Reserves room for the given number of additional clauses in the
+    /// clause list of the internal solver. Returns -1 if a native allocation
+    /// failed.
+    pub fn ccadical_reserve_clauses(ptr: *mut c_void, count: usize) -> c_int;
+    /// Calls the callback with each irredundant clause until it returns
+    /// zero, returns zero if the traversal was stopped.
+    pub fn ccadical_traverse_clauses(
+        ptr: *mut c_void,
+        data: *mut c_void,
+        cbs: extern "C" fn(*mut c_void, *const c_int, usize) -> c_int,
+    ) -> c_int;
+    /// Runs the given number of preprocessing rounds without search, returns
+    /// 10 if satisfiable, 20 if unsatisfiable, -1 if a native allocation
+    /// failed and 0 otherwise.
+    pub fn ccadical_simplify(ptr: *mut c_void, rounds: c_int) -> c_int;
+    /// Bumps the variable in the decision heuristics if it is in range and
+    /// still active.
+    pub fn ccadical_prioritize(ptr: *mut c_void, var: c_int);
+    /// Freezes the variable of the literal, so it is not eliminated.
+    pub fn ccadical_freeze(ptr: *mut c_void, lit: c_int);
+    /// Melts the variable of the literal, which must have been frozen.
+    pub fn ccadical_melt(ptr: *mut c_void, lit: c_int);
+    /// Returns nonzero if the variable of the literal is frozen.
+    pub fn ccadical_frozen(ptr: *mut c_void, lit: c_int) -> c_int;
+    /// Returns the number of conflicts so far.
+    pub fn ccadical_conflicts(ptr: *mut c_void) -> i64;
+    /// Returns the number of decisions so far.
+    pub fn ccadical_decisions(ptr: *mut c_void) -> i64;
+    /// Returns the number of literals propagated during search so far.
+    pub fn ccadical_propagations(ptr: *mut c_void) -> i64;
+    /// Returns the internal state of the solver as a bit mask.
+    pub fn ccadical_state(ptr: *mut c_void) -> c_int;
+    /// Returns nonzero if the empty clause has been derived.
+    pub fn ccadical_is_inconsistent(ptr: *mut c_void) -> c_int;
+    /// Returns the number of restarts so far.
+    pub fn ccadical_restarts(ptr: *mut c_void) -> i64;
+    /// Returns the current decision level.
+    pub fn ccadical_level(ptr: *mut c_void) -> c_int;
+    /// Returns the decision literal of the current decision level, or zero.
+    pub fn ccadical_decision(ptr: *mut c_void) -> c_int;
+    /// Returns the glue of a learned clause and stores the backjump level in
+    /// `jump`.
+    pub fn ccadical_learned_levels(
+        ptr: *mut c_void,
+        clause: *const c_int,
+        len: usize,
+        jump: *mut c_int,
+    ) -> c_int;
+    /// Returns the process time spent in the named profiled phase. This
+    /// requires the `profiling` feature.
+    #[cfg(feature = "profiling")]
+    pub fn ccadical_profile(ptr: *mut c_void, name: *const c_char) -> f64;
+    /// Stores the conflicts, restarts, reductions, decision level, trail
+    /// size, irredundant and redundant clauses, active variables and the
+    /// number of variables of the progress report into the nine values.
+    pub fn ccadical_report(ptr: *mut c_void, report: *mut i64);
+    /// Returns the number of learned clauses so far.
+    pub fn ccadical_learned(ptr: *mut c_void) -> i64;
+    /// Opens an anonymous temporary file on disk for the proof of a solver,
+    /// or returns null if it cannot be created. The file is removed when it
+    /// is closed.
+    pub fn ccadical_proof_open() -> *mut c_void;
+    /// Starts writing the binary DRAT proof of the solver to the opened
+    /// file. Returns 0 if the solver is not in the configuring state.
+    pub fn ccadical_trace_proof(ptr: *mut c_void, file: *mut c_void) -> c_int;
+    /// Flushes the proof of the solver and returns its size in bytes.
+    pub fn ccadical_proof_size(ptr: *mut c_void, file: *mut c_void) -> u64;
+    /// Flushes the proof of the solver and copies at most `size` bytes of
+    /// it starting at `offset` into the buffer. Returns the number of bytes
+    /// copied, which is zero at the end of the proof.
+    pub fn ccadical_proof_read(
+        ptr: *mut c_void,
+        file: *mut c_void,
+        offset: u64,
+        buffer: *mut u8,
+        size: usize,
+    ) -> usize;
+    /// Closes the proof file after the solver writing to it is released.
+    pub fn ccadical_proof_close(file: *mut c_void);
+    /// Opens the file for writing an API call trace, or returns null if it
+    /// cannot be opened. This requires the `cpp-tracing` feature.
+    #[cfg(feature = "cpp-tracing")]
+    pub fn ccadical_trace_open(path: *const c_char) -> *mut c_void;
+    /// Starts writing the API calls of the solver to the opened file.
+    #[cfg(feature = "cpp-tracing")]
+    pub fn ccadical_trace_api_calls(ptr: *mut c_void, file: *mut c_void);
+    /// Closes the trace file after the solvers writing to it are released.
+    #[cfg(feature = "cpp-tracing")]
+    pub fn ccadical_trace_close(file: *mut c_void);
+    /// Creates a new native memory account. This requires the
+    /// `memory-accounting` feature.
+    #[cfg(feature = "memory-accounting")]
+    pub fn ccadical_account_new() -> *mut c_void;
+    /// Releases a native memory account.
+    #[cfg(feature = "memory-accounting")]
+    pub fn ccadical_account_release(account: *mut c_void);
+    /// Charges the native allocations of the calling thread to the given
+    /// account (none if null), and returns the previously active account.
+    #[cfg(feature = "memory-accounting")]
+    pub fn ccadical_account_enter(account: *mut c_void) -> *mut c_void;
+    /// Returns the number of bytes currently charged to the account.
+    #[cfg(feature = "memory-accounting")]
+    pub fn ccadical_account_current(account: *mut c_void) -> i64;
+    /// Returns the maximum number of bytes ever charged to the account.
+    #[cfg(feature = "memory-accounting")]
+    pub fn ccadical_account_peak(account: *mut c_void) -> i64;
+}
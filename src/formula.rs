@@ -0,0 +1,328 @@
+//! An in-memory formula in conjunctive normal form.
+
+use super::{Callbacks, DimacsError, DimacsErrorKind, Error, Solver};
+use std::collections::HashSet;
+use std::num::IntErrorKind;
+
+/// A list of clauses in conjunctive normal form, with literals given as
+/// nonzero integers exactly as in the DIMACS format. The clauses are stored
+/// consecutively in a single buffer.
+/// # Examples
+/// ```
+/// let mut formula = cadical::CnfFormula::new();
+/// formula.add_clause([1, 2]);
+/// formula.add_clause([-1, 3]);
+/// assert_eq!(formula.len(), 2);
+/// assert_eq!(formula.max_variable(), 3);
+/// let mut sat: cadical::Solver = Default::default();
+/// sat.add_formula(&formula);
+/// assert_eq!(sat.solve(), Some(true));
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CnfFormula {
+    max_var: i32,
+    literals: Vec<i32>,
+    ends: Vec<usize>,
+}
+
+impl CnfFormula {
+    /// Creates a new empty formula.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Appends a clause to the formula. The literals must be nonzero and
+    /// different from `i32::MIN`.
+    pub fn add_clause<I>(&mut self, clause: I)
+    where
+        I: IntoIterator<Item = i32>,
+    {
+        for lit in clause {
+            debug_assert!(lit != 0 && lit != i32::MIN);
+            self.max_var = self.max_var.max(lit.abs());
+            self.literals.push(lit);
+        }
+        self.ends.push(self.literals.len());
+    }
+
+    /// Makes sure that the formula has at least `min_max_var` variables,
+    /// even if some of them do not occur in any clause.
+    pub fn reserve(&mut self, min_max_var: i32) {
+        self.max_var = self.max_var.max(min_max_var);
+    }
+
+    /// Returns the maximum variable index of the formula.
+    pub fn max_variable(&self) -> i32 {
+        self.max_var
+    }
+
+    /// Returns the number of clauses.
+    pub fn len(&self) -> usize {
+        self.ends.len()
+    }
+
+    /// Returns `true` if the formula has no clauses.
+    pub fn is_empty(&self) -> bool {
+        self.ends.is_empty()
+    }
+
+    /// Returns the total number of literals in all clauses.
+    pub fn num_literals(&self) -> usize {
+        self.literals.len()
+    }
+
+    /// Returns the clause at the given index.
+    pub fn clause(&self, index: usize) -> &[i32] {
+        let start = if index == 0 { 0 } else { self.ends[index - 1] };
+        &self.literals[start..self.ends[index]]
+    }
+
+    /// Returns an iterator over the clauses of the formula.
+    pub fn iter(&self) -> impl Iterator<Item = &[i32]> + '_ {
+        (0..self.len()).map(move |index| self.clause(index))
+    }
+
+    /// Removes the redundant clauses of the formula, which is useful before
+    /// loading machine-generated formulas into a solver: exact duplicates
+    /// (up to the order and repetition of literals), tautologies containing
+    /// a literal and its negation, and clauses subsumed by a unit or binary
+    /// clause of the formula. The first copy of duplicates is kept, and the
+    /// maximum variable is not changed. Returns the number of removed
+    /// clauses.
+    /// # Examples
+    /// ```
+    /// let mut formula = cadical::CnfFormula::new();
+    /// formula.add_clause([1, 2]);
+    /// formula.add_clause([2, 1, 1]);
+    /// formula.add_clause([3, -3]);
+    /// formula.add_clause([-4, 2, 5, 1]);
+    /// formula.add_clause([-1, 4]);
+    /// assert_eq!(formula.remove_redundant(), 3);
+    /// assert_eq!(formula.len(), 2);
+    /// ```
+    pub fn remove_redundant(&mut self) -> usize {
+        let normalize = |clause: &[i32]| {
+            // complementary literals are adjacent when sorted by variable
+            let mut lits = clause.to_vec();
+            lits.sort_unstable_by_key(|lit| (lit.abs(), *lit));
+            lits.dedup();
+            let tautology = lits.windows(2).any(|pair| pair[0] == -pair[1]);
+            (lits, tautology)
+        };
+
+        // units and binaries subsume all other clauses containing them
+        let mut units: HashSet<i32> = HashSet::new();
+        let mut binaries: HashSet<(i32, i32)> = HashSet::new();
+        for clause in self.iter() {
+            let (lits, tautology) = normalize(clause);
+            if lits.len() == 1 {
+                units.insert(lits[0]);
+            } else if lits.len() == 2 && !tautology {
+                binaries.insert((lits[0], lits[1]));
+            }
+        }
+
+        let mut seen: HashSet<Vec<i32>> = HashSet::new();
+        let mut kept = CnfFormula::new();
+        for clause in self.iter() {
+            let (lits, tautology) = normalize(clause);
+            let subsumed = lits.len() >= 2 && lits.iter().any(|lit| units.contains(lit))
+                || lits.len() >= 3
+                    && lits.iter().enumerate().any(|(i, &lit1)| {
+                        lits[(i + 1)..]
+                            .iter()
+                            .any(|&lit2| binaries.contains(&(lit1, lit2)))
+                    });
+            if !tautology && !subsumed && seen.insert(lits) {
+                kept.add_clause(clause.iter().copied());
+            }
+        }
+        kept.reserve(self.max_var);
+        let removed = self.len() - kept.len();
+        *self = kept;
+        removed
+    }
+
+    /// Parses a formula in DIMACS CNF format. Comment lines are skipped,
+    /// the variable count of the optional `p cnf` header is reserved, and
+    /// the input may end with a `%` line as in the SATLIB benchmarks. Each
+    /// clause must be terminated by zero. See `parse_dimacs_detailed` for
+    /// the position of the errors.
+    /// # Examples
+    /// ```
+    /// let text = "c example\np cnf 3 2\n1 -2 0\n2 3 0\n";
+    /// let formula = cadical::CnfFormula::parse_dimacs(text).unwrap();
+    /// assert_eq!(formula.clause(0), &[1, -2]);
+    /// assert_eq!(formula.max_variable(), 3);
+    /// ```
+    pub fn parse_dimacs(text: &str) -> Result<Self, Error> {
+        Self::parse_dimacs_detailed(text).map_err(Error::from)
+    }
+
+    /// Parses a formula in DIMACS CNF format like `parse_dimacs`, but the
+    /// error reports the line and column, the offending token, and a
+    /// suggestion how to fix the input. The clause count of the header is
+    /// not checked, it is only used to explain an unterminated last clause.
+    /// # Examples
+    /// ```
+    /// use cadical::DimacsErrorKind;
+    /// let err = cadical::CnfFormula::parse_dimacs_detailed("p cnf 2 1\n1 -x 0\n").unwrap_err();
+    /// assert_eq!(err.kind, DimacsErrorKind::InvalidLiteral);
+    /// assert_eq!((err.line, err.column), (2, 3));
+    /// assert_eq!(err.token.as_deref(), Some("-x"));
+    /// ```
+    pub fn parse_dimacs_detailed(text: &str) -> Result<Self, DimacsError> {
+        let mut formula = CnfFormula::new();
+        let mut claimed: Option<usize> = None;
+        let mut start = 0;
+        let mut last = 0;
+        for (index, full) in text.lines().enumerate() {
+            let line = full.trim_start();
+            if line.starts_with('c') || line.is_empty() {
+                continue;
+            } else if line.starts_with('%') {
+                break;
+            } else if let Some(header) = line.strip_prefix('p') {
+                let mut tokens = header.split_whitespace();
+                let vars = match (tokens.next(), tokens.next()) {
+                    (Some("cnf"), Some(vars)) => vars.parse().ok(),
+                    _ => None,
+                };
+                let vars = vars.ok_or_else(|| {
+                    let column = full.len() - line.len() + 1;
+                    DimacsError::new(DimacsErrorKind::InvalidHeader, index + 1, column, None)
+                        .suggest("the header must be `p cnf <variables> <clauses>`")
+                })?;
+                formula.reserve(vars);
+                claimed = tokens.next().and_then(|count| count.parse().ok());
+                continue;
+            }
+            for token in line.split_whitespace() {
+                let column = token.as_ptr() as usize - full.as_ptr() as usize + 1;
+                let lit = match token.parse::<i32>() {
+                    Ok(lit) if lit != i32::MIN => lit,
+                    Err(err)
+                        if err.kind() != &IntErrorKind::PosOverflow
+                            && err.kind() != &IntErrorKind::NegOverflow =>
+                    {
+                        let err = DimacsError::new(
+                            DimacsErrorKind::InvalidLiteral,
+                            index + 1,
+                            column,
+                            Some(token),
+                        );
+                        return Err(
+                            err.suggest("literals must be integers separated by whitespace")
+                        );
+                    }
+                    _ => {
+                        let err = DimacsError::new(
+                            DimacsErrorKind::LiteralOutOfRange,
+                            index + 1,
+                            column,
+                            Some(token),
+                        );
+                        return Err(err.suggest("variable indices must be less than 2^31"));
+                    }
+                };
+                last = index + 1;
+                if lit == 0 {
+                    formula.max_var = formula.literals[start..]
+                        .iter()
+                        .fold(formula.max_var, |max, lit| max.max(lit.abs()));
+                    formula.ends.push(formula.literals.len());
+                    start = formula.literals.len();
+                } else {
+                    formula.literals.push(lit);
+                }
+            }
+        }
+        if start != formula.literals.len() {
+            let err = DimacsError::new(DimacsErrorKind::UnterminatedClause, last, 0, None);
+            return Err(match claimed {
+                Some(count) if count == formula.len() + 1 => err.suggest(&format!(
+                    "the header claims {} clauses, the last one is missing its terminating 0",
+                    count
+                )),
+                _ => err.suggest("every clause must be terminated by 0"),
+            });
+        }
+        Ok(formula)
+    }
+}
+
+impl<I> Extend<I> for CnfFormula
+where
+    I: IntoIterator<Item = i32>,
+{
+    fn extend<T: IntoIterator<Item = I>>(&mut self, clauses: T) {
+        for clause in clauses {
+            self.add_clause(clause);
+        }
+    }
+}
+
+impl<I> std::iter::FromIterator<I> for CnfFormula
+where
+    I: IntoIterator<Item = i32>,
+{
+    fn from_iter<T: IntoIterator<Item = I>>(clauses: T) -> Self {
+        let mut formula = CnfFormula::new();
+        formula.extend(clauses);
+        formula
+    }
+}
+
+impl<C: Callbacks> Solver<C> {
+    /// Adds all clauses of the formula to the solver, and makes sure that all
+    /// of its variables are initialized.
+    pub fn add_formula(&mut self, formula: &CnfFormula) {
+        if formula.max_variable() > 0 {
+            self.reserve(formula.max_variable());
+        }
+        self.reserve_clauses(formula.len());
+        for clause in formula.iter() {
+            self.add_clause(clause.iter().copied());
+        }
+    }
+
+    /// Runs three rounds of `simplify` and returns the resulting irredundant
+    /// clauses, so the preprocessed formula can be passed to other engines.
+    /// The result is equisatisfiable with the current formula, but fixed and
+    /// eliminated variables that are not frozen do not occur in it. The
+    /// activation literals of scopes, removable clauses and clause groups
+    /// are left out: the clauses of the active ones are returned without
+    /// them, and the clauses of the inactive ones are skipped. If the
+    /// formula was found unsatisfiable, then the empty clause is returned.
+    /// # Examples
+    /// ```
+    /// let mut sat: cadical::Solver = Default::default();
+    /// sat.add_clause([1, 2]);
+    /// sat.add_clause([-1, 2]);
+    /// sat.add_clause([-2, 3]);
+    /// let formula = sat.simplified_formula();
+    /// assert_eq!(formula.max_variable(), 3);
+    /// ```
+    pub fn simplified_formula(&mut self) -> CnfFormula {
+        let mut formula = CnfFormula::new();
+        if self.simplify(3) == Some(false) {
+            formula.add_clause([]);
+        } else {
+            let active: HashSet<i32> = self.persistent_assumptions().collect();
+            self.traverse_clauses(|clause| {
+                let mut lits = Vec::with_capacity(clause.len());
+                for &lit in clause.iter() {
+                    if !self.is_selector(lit) {
+                        lits.push(lit);
+                    } else if !active.contains(&-lit) {
+                        return true;
+                    }
+                }
+                formula.add_clause(lits);
+                true
+            });
+        }
+        formula.reserve(self.max_variable());
+        formula
+    }
+}
@@ -0,0 +1,266 @@
+//! Generators of benchmark formulas. All generators are deterministic: the
+//! same parameters and seed produce the same formula on every platform.
+
+use super::CnfFormula;
+
+/// The SplitMix64 pseudo random number generator.
+struct Random(u64);
+
+impl Random {
+    fn new(seed: u64) -> Self {
+        Random(seed)
+    }
+
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// Returns a uniformly random number in `0..bound`.
+    fn below(&mut self, bound: u64) -> u64 {
+        debug_assert!(bound > 0);
+        let zone = u64::MAX - u64::MAX % bound;
+        loop {
+            let value = self.next();
+            if value < zone {
+                return value % bound;
+            }
+        }
+    }
+}
+
+/// Returns the clauses of a uniform random k-SAT formula, where each clause
+/// consists of `k` different variables chosen uniformly from `1..=vars`,
+/// each negated with probability one half. Use this to feed a solver
+/// directly without building the whole formula first.
+/// # Examples
+/// ```
+/// use cadical::generators::random_ksat_clauses;
+/// let mut sat: cadical::Solver = Default::default();
+/// for clause in random_ksat_clauses(100, 200, 3, 1) {
+///     sat.add_clause(clause);
+/// }
+/// assert_eq!(sat.solve(), Some(true));
+/// ```
+pub fn random_ksat_clauses(
+    vars: i32,
+    clauses: usize,
+    k: usize,
+    seed: u64,
+) -> impl Iterator<Item = Vec<i32>> {
+    assert!(vars >= 0 && k <= vars as usize);
+    let mut random = Random::new(seed);
+    (0..clauses).map(move |_| {
+        let mut clause: Vec<i32> = Vec::with_capacity(k);
+        while clause.len() < k {
+            let var = random.below(vars as u64) as i32 + 1;
+            if clause.iter().all(|lit| lit.abs() != var) {
+                clause.push(var);
+            }
+        }
+        for lit in clause.iter_mut() {
+            if random.next() & 1 != 0 {
+                *lit = -*lit;
+            }
+        }
+        clause
+    })
+}
+
+/// Returns a uniform random k-SAT formula over the variables `1..=vars`
+/// with the given number of clauses, see `random_ksat_clauses`. For `k = 3`
+/// the formulas are the hardest at around `4.26` clauses per variable.
+/// # Examples
+/// ```
+/// use cadical::generators::random_ksat;
+/// let formula = random_ksat(50, 213, 3, 7);
+/// assert_eq!(formula.len(), 213);
+/// assert_eq!(formula, random_ksat(50, 213, 3, 7));
+/// ```
+pub fn random_ksat(vars: i32, clauses: usize, k: usize, seed: u64) -> CnfFormula {
+    let mut formula: CnfFormula = random_ksat_clauses(vars, clauses, k, seed).collect();
+    formula.reserve(vars);
+    formula
+}
+
+/// Returns the pigeon hole principle for `holes + 1` pigeons and `holes`
+/// holes, which is unsatisfiable. The variable `1 + i * holes + j` is true
+/// if pigeon `i` sits in hole `j`. These formulas require resolution proofs
+/// of exponential size.
+/// # Examples
+/// ```
+/// let formula = cadical::generators::pigeon_hole(3);
+/// assert_eq!(formula.max_variable(), 12);
+/// let mut sat: cadical::Solver = Default::default();
+/// sat.add_formula(&formula);
+/// assert_eq!(sat.solve(), Some(false));
+/// ```
+pub fn pigeon_hole(holes: i32) -> CnfFormula {
+    assert!(holes >= 0);
+    let var = |i: i32, j: i32| 1 + i * holes + j;
+    let mut formula = CnfFormula::new();
+    for i in 0..(holes + 1) {
+        formula.add_clause((0..holes).map(|j| var(i, j)));
+    }
+    for j in 0..holes {
+        for i1 in 0..(holes + 1) {
+            for i2 in (i1 + 1)..(holes + 1) {
+                formula.add_clause([-var(i1, j), -var(i2, j)]);
+            }
+        }
+    }
+    formula
+}
+
+/// Adds the clauses stating that the XOR of the given variables is `parity`,
+/// which forbids each of the `2^(n-1)` assignments with the wrong parity.
+fn add_xor(formula: &mut CnfFormula, vars: &[i32], parity: bool) {
+    assert!(vars.len() < 32);
+    for bits in 0u32..(1 << vars.len()) {
+        // the clause is false exactly when the variables are equal to bits
+        if (bits.count_ones() % 2 == 1) != parity {
+            formula.add_clause(vars.iter().enumerate().map(|(i, &var)| {
+                if bits & (1 << i) != 0 {
+                    -var
+                } else {
+                    var
+                }
+            }));
+        }
+    }
+}
+
+/// Returns two chains of XOR gates computing the parity of the variables
+/// `1..=len` in two different random orders, with the two results required
+/// to differ, which is unsatisfiable. These formulas are easy for Gaussian
+/// elimination but hard for resolution.
+/// # Examples
+/// ```
+/// let formula = cadical::generators::parity(10, 1);
+/// let mut sat: cadical::Solver = Default::default();
+/// sat.add_formula(&formula);
+/// assert_eq!(sat.solve(), Some(false));
+/// ```
+pub fn parity(len: i32, seed: u64) -> CnfFormula {
+    assert!(len >= 1);
+    let mut random = Random::new(seed);
+    let mut order: Vec<i32> = (1..=len).collect();
+    for i in (1..order.len()).rev() {
+        order.swap(i, random.below(i as u64 + 1) as usize);
+    }
+
+    let mut formula = CnfFormula::new();
+    let mut last = [1, order[0]];
+    for i in 1..len {
+        let next = [len + i, 2 * len - 1 + i];
+        add_xor(&mut formula, &[last[0], 1 + i, next[0]], false);
+        add_xor(&mut formula, &[last[1], order[i as usize], next[1]], false);
+        last = next;
+    }
+    add_xor(&mut formula, &last, true);
+    formula
+}
+
+/// Returns the Tseitin formula of a random `degree`-regular graph on the
+/// given number of vertices, which is an expander with high probability.
+/// Each edge is a variable, and the XOR of the edges incident to a vertex
+/// is required to be one for the first vertex and zero for the others. The
+/// formula is unsatisfiable because the sum of these charges is odd, and
+/// it requires exponential size resolution proofs on expanders. The number
+/// of vertices times the degree must be even.
+/// # Examples
+/// ```
+/// let formula = cadical::generators::tseitin(10, 3, 1);
+/// assert_eq!(formula.max_variable(), 15);
+/// let mut sat: cadical::Solver = Default::default();
+/// sat.add_formula(&formula);
+/// assert_eq!(sat.solve(), Some(false));
+/// ```
+pub fn tseitin(vertices: usize, degree: usize, seed: u64) -> CnfFormula {
+    assert!(degree >= 1 && degree < vertices && (vertices * degree) & 1 == 0);
+    let mut random = Random::new(seed);
+
+    // pair up the endpoints until the graph is simple
+    let edges = loop {
+        let mut points: Vec<usize> = (0..vertices * degree).map(|p| p / degree).collect();
+        for i in (1..points.len()).rev() {
+            points.swap(i, random.below(i as u64 + 1) as usize);
+        }
+        let mut edges: Vec<(usize, usize)> = points
+            .chunks(2)
+            .map(|pair| (pair[0].min(pair[1]), pair[0].max(pair[1])))
+            .collect();
+        edges.sort_unstable();
+        let simple =
+            edges.iter().all(|&(a, b)| a != b) && edges.windows(2).all(|pair| pair[0] != pair[1]);
+        if simple {
+            break edges;
+        }
+    };
+
+    let mut incident: Vec<Vec<i32>> = vec![Vec::new(); vertices];
+    for (index, &(a, b)) in edges.iter().enumerate() {
+        incident[a].push(index as i32 + 1);
+        incident[b].push(index as i32 + 1);
+    }
+    let mut formula = CnfFormula::new();
+    for (vertex, vars) in incident.iter().enumerate() {
+        add_xor(&mut formula, vars, vertex == 0);
+    }
+    formula
+}
+
+/// Returns the counting principle stating that a set of `elements` elements
+/// can be partitioned into blocks of size `size`, which is unsatisfiable if
+/// `elements` is not divisible by `size`. There is a variable for each block
+/// (subset of the given size), numbered in lexicographic order, and each
+/// element must be covered by exactly one selected block. For `size = 2`
+/// this is the perfect matching principle of the complete graph.
+/// # Examples
+/// ```
+/// let formula = cadical::generators::counting(5, 2);
+/// assert_eq!(formula.max_variable(), 10);
+/// let mut sat: cadical::Solver = Default::default();
+/// sat.add_formula(&formula);
+/// assert_eq!(sat.solve(), Some(false));
+/// ```
+pub fn counting(elements: usize, size: usize) -> CnfFormula {
+    assert!(size >= 1 && size <= elements);
+    let mut blocks: Vec<Vec<usize>> = Vec::new();
+    let mut block: Vec<usize> = (0..size).collect();
+    loop {
+        blocks.push(block.clone());
+        // advance to the next subset in lexicographic order
+        let mut i = size;
+        while i > 0 && block[i - 1] == elements - size + i - 1 {
+            i -= 1;
+        }
+        if i == 0 {
+            break;
+        }
+        block[i - 1] += 1;
+        for j in i..size {
+            block[j] = block[j - 1] + 1;
+        }
+    }
+
+    let mut covering: Vec<Vec<i32>> = vec![Vec::new(); elements];
+    for (index, block) in blocks.iter().enumerate() {
+        for &element in block {
+            covering[element].push(index as i32 + 1);
+        }
+    }
+    let mut formula = CnfFormula::new();
+    for vars in covering.iter() {
+        formula.add_clause(vars.iter().copied());
+        for (i, &var1) in vars.iter().enumerate() {
+            for &var2 in vars[(i + 1)..].iter() {
+                formula.add_clause([-var1, -var2]);
+            }
+        }
+    }
+    formula
+}
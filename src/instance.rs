@@ -0,0 +1,47 @@
+//! Loading of instances parsed by the `dimacs` crate. This module is only
+//! available with the `dimacs` feature.
+
+use super::{Callbacks, Error, Solver};
+use dimacs::{Clause, Instance, Sign};
+
+impl<C: Callbacks> Solver<C> {
+    /// Adds the clauses of a CNF instance parsed by the `dimacs` crate to the
+    /// solver, and makes sure that all of its declared variables are
+    /// initialized. Returns an error for SAT instances (arbitrary formulas),
+    /// which are not in conjunctive normal form.
+    /// # Examples
+    /// ```
+    /// let instance = dimacs::parse_dimacs("p cnf 3 2\n1 -2 0\n2 3 0\n").unwrap();
+    /// let mut sat: cadical::Solver = Default::default();
+    /// sat.add_cnf(&instance).unwrap();
+    /// assert_eq!(sat.max_variable(), 3);
+    /// assert_eq!(sat.solve(), Some(true));
+    /// ```
+    pub fn add_cnf(&mut self, instance: &Instance) -> Result<(), Error> {
+        match instance {
+            Instance::Cnf { num_vars, clauses } => {
+                if *num_vars > i32::MAX as u64 {
+                    return Err(Error::new("too many variables"));
+                }
+                if *num_vars > 0 {
+                    self.reserve(*num_vars as i32);
+                }
+                for clause in clauses.iter() {
+                    self.add_dimacs_clause(clause);
+                }
+                Ok(())
+            }
+            _ => Err(Error::new("unsupported instance")),
+        }
+    }
+
+    fn add_dimacs_clause(&mut self, clause: &Clause) {
+        self.add_clause(clause.lits().iter().map(|lit| {
+            let var = lit.var().to_u64() as i32;
+            match lit.sign() {
+                Sign::Pos => var,
+                Sign::Neg => -var,
+            }
+        }));
+    }
+}
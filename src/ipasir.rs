@@ -0,0 +1,148 @@
+//! The standard IPASIR interface implemented on top of the safe `Solver`, so
+//! that the crate can be built as a drop-in IPASIR library for C and C++
+//! tools. This module is only available with the `cdylib` feature. The
+//! solver handles passed to these functions must come from `ipasir_init` and
+//! must not be used after `ipasir_release`.
+
+use super::{ccadical_signature, Callbacks, Solver};
+use std::os::raw::{c_char, c_int, c_void};
+
+/// The terminate and learn callbacks registered through IPASIR.
+struct IpasirCallbacks {
+    terminate: Option<(*mut c_void, extern "C" fn(*mut c_void) -> c_int)>,
+    learn: Option<(*mut c_void, extern "C" fn(*mut c_void, *mut c_int))>,
+    max_length: i32,
+    buffer: Vec<c_int>,
+}
+
+impl Callbacks for IpasirCallbacks {
+    fn terminate(&mut self) -> bool {
+        if let Some((data, terminate)) = self.terminate {
+            terminate(data) != 0
+        } else {
+            false
+        }
+    }
+
+    fn max_length(&self) -> i32 {
+        if self.learn.is_some() {
+            self.max_length
+        } else {
+            0
+        }
+    }
+
+    fn learn(&mut self, clause: &[i32]) {
+        if let Some((data, learn)) = self.learn {
+            self.buffer.clear();
+            self.buffer.extend_from_slice(clause);
+            self.buffer.push(0);
+            learn(data, self.buffer.as_mut_ptr());
+        }
+    }
+}
+
+/// The solver together with the clause and assumptions under construction.
+struct Ipasir {
+    solver: Solver<IpasirCallbacks>,
+    clause: Vec<i32>,
+    assumptions: Vec<i32>,
+}
+
+unsafe fn get<'a>(solver: *mut c_void) -> &'a mut Ipasir {
+    debug_assert!(!solver.is_null());
+    &mut *(solver as *mut Ipasir)
+}
+
+#[no_mangle]
+pub extern "C" fn ipasir_signature() -> *const c_char {
+    unsafe { ccadical_signature() }
+}
+
+#[no_mangle]
+pub extern "C" fn ipasir_init() -> *mut c_void {
+    let mut solver = Solver::new();
+    solver.set_callbacks(Some(IpasirCallbacks {
+        terminate: None,
+        learn: None,
+        max_length: 0,
+        buffer: Vec::new(),
+    }));
+    let ipasir = Box::new(Ipasir {
+        solver,
+        clause: Vec::new(),
+        assumptions: Vec::new(),
+    });
+    Box::into_raw(ipasir) as *mut c_void
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn ipasir_release(solver: *mut c_void) {
+    debug_assert!(!solver.is_null());
+    drop(Box::from_raw(solver as *mut Ipasir));
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn ipasir_add(solver: *mut c_void, lit_or_zero: i32) {
+    let ipasir = get(solver);
+    if lit_or_zero == 0 {
+        let clause = std::mem::take(&mut ipasir.clause);
+        ipasir.solver.add_clause(clause);
+    } else {
+        ipasir.clause.push(lit_or_zero);
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn ipasir_assume(solver: *mut c_void, lit: i32) {
+    get(solver).assumptions.push(lit);
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn ipasir_solve(solver: *mut c_void) -> c_int {
+    let ipasir = get(solver);
+    let assumptions = std::mem::take(&mut ipasir.assumptions);
+    match ipasir.solver.solve_with(assumptions) {
+        Some(true) => 10,
+        Some(false) => 20,
+        None => 0,
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn ipasir_val(solver: *mut c_void, lit: i32) -> i32 {
+    match get(solver).solver.value(lit) {
+        Some(true) => lit,
+        Some(false) => -lit,
+        None => 0,
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn ipasir_failed(solver: *mut c_void, lit: i32) -> c_int {
+    get(solver).solver.failed(lit) as c_int
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn ipasir_set_terminate(
+    solver: *mut c_void,
+    data: *mut c_void,
+    terminate: Option<extern "C" fn(*mut c_void) -> c_int>,
+) {
+    if let Some(cbs) = get(solver).solver.get_callbacks() {
+        cbs.terminate = terminate.map(|terminate| (data, terminate));
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn ipasir_set_learn(
+    solver: *mut c_void,
+    data: *mut c_void,
+    max_length: c_int,
+    learn: Option<extern "C" fn(*mut c_void, *mut c_int)>,
+) {
+    if let Some(cbs) = get(solver).solver.get_callbacks() {
+        cbs.learn = learn.map(|learn| (data, learn));
+        cbs.max_length = max_length;
+    }
+}
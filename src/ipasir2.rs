@@ -0,0 +1,410 @@
+//! The IPASIR-2 interface implemented on top of the safe `Solver`, next to
+//! the original IPASIR functions. This module is only available with the
+//! `cdylib` feature. Every function returns an error code and passes its
+//! results through output pointers. Options are namespaced: the standard
+//! limits are under `ipasir.` and the options of CaDiCaL under `cadical.`.
+//! The clause import, deletion and fixed literal callbacks are not
+//! supported.
+
+use super::{ccadical_signature, Callbacks, OptionError, Solver, State};
+use std::ffi::CStr;
+use std::os::raw::{c_char, c_int, c_void};
+
+/// The error codes returned by the IPASIR-2 functions.
+#[allow(dead_code)]
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Ipasir2ErrorCode {
+    Ok = 0,
+    Unknown = 1,
+    Unsupported = 2,
+    UnsupportedArgument = 3,
+    UnsupportedOption = 4,
+    InvalidState = 5,
+    InvalidArgument = 6,
+    InvalidOptionValue = 7,
+}
+
+/// The redundancy of an added clause. All clauses are added as irredundant,
+/// which is always sound. It is passed as a `c_int` through the C interface,
+/// since values outside of the enum would be undefined behavior.
+#[allow(dead_code)]
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Ipasir2Redundancy {
+    None = 0,
+    Forgettable = 1,
+    Equisatisfiable = 2,
+    Equivalent = 3,
+}
+
+impl Ipasir2Redundancy {
+    /// Converts the value passed by the caller, or returns `None` if it is
+    /// not a valid redundancy.
+    fn from_raw(value: c_int) -> Option<Self> {
+        match value {
+            0 => Some(Ipasir2Redundancy::None),
+            1 => Some(Ipasir2Redundancy::Forgettable),
+            2 => Some(Ipasir2Redundancy::Equisatisfiable),
+            3 => Some(Ipasir2Redundancy::Equivalent),
+            _ => None,
+        }
+    }
+}
+
+/// The states of the solver, used to tell until which state an option can
+/// be set.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Ipasir2State {
+    Config = 0,
+    Input = 1,
+    Sat = 2,
+    Unsat = 3,
+    Solving = 4,
+}
+
+/// The description of an option, the handle is the address of the option
+/// itself.
+#[repr(C)]
+#[derive(Debug)]
+pub struct Ipasir2Option {
+    pub name: *const c_char,
+    pub min: i64,
+    pub max: i64,
+    pub max_state: Ipasir2State,
+    pub tunable: c_int,
+    pub indexed: c_int,
+    pub handle: *const c_void,
+}
+
+struct OptionTable([Ipasir2Option; 15]);
+
+// the table is immutable and the names are static strings
+unsafe impl Sync for OptionTable {}
+
+const fn option(name: &'static [u8], min: i64, max: i64, max_state: Ipasir2State) -> Ipasir2Option {
+    Ipasir2Option {
+        name: name.as_ptr() as *const c_char,
+        min,
+        max,
+        max_state,
+        tunable: (max_state as c_int == 0) as c_int,
+        indexed: 0,
+        handle: std::ptr::null(),
+    }
+}
+
+static OPTIONS: OptionTable = OptionTable([
+    option(
+        b"ipasir.limits.conflicts\0",
+        -1,
+        i32::MAX as i64,
+        Ipasir2State::Unsat,
+    ),
+    option(
+        b"ipasir.limits.decisions\0",
+        -1,
+        i32::MAX as i64,
+        Ipasir2State::Unsat,
+    ),
+    option(b"cadical.seed\0", 0, 2_000_000_000, Ipasir2State::Config),
+    option(b"cadical.phase\0", 0, 1, Ipasir2State::Config),
+    option(b"cadical.stabilize\0", 0, 1, Ipasir2State::Config),
+    option(b"cadical.chrono\0", 0, 2, Ipasir2State::Config),
+    option(b"cadical.restartint\0", 1, 1_000_000, Ipasir2State::Config),
+    option(b"cadical.reduceint\0", 10, 1_000_000, Ipasir2State::Config),
+    option(b"cadical.elim\0", 0, 1, Ipasir2State::Config),
+    option(b"cadical.subsume\0", 0, 1, Ipasir2State::Config),
+    option(b"cadical.vivify\0", 0, 1, Ipasir2State::Config),
+    option(b"cadical.probe\0", 0, 1, Ipasir2State::Config),
+    option(b"cadical.walk\0", 0, 1, Ipasir2State::Config),
+    option(b"cadical.lucky\0", 0, 1, Ipasir2State::Config),
+    option(b"cadical.compact\0", 0, 1, Ipasir2State::Config),
+]);
+
+type ExportCallback = extern "C" fn(*mut c_void, *const i32, i32, *mut c_void);
+
+/// The terminate and export callbacks registered through IPASIR-2.
+struct Ipasir2Callbacks {
+    terminate: Option<(*mut c_void, extern "C" fn(*mut c_void) -> c_int)>,
+    export: Option<(*mut c_void, ExportCallback)>,
+    max_length: i32,
+}
+
+impl Callbacks for Ipasir2Callbacks {
+    fn terminate(&mut self) -> bool {
+        if let Some((data, terminate)) = self.terminate {
+            terminate(data) != 0
+        } else {
+            false
+        }
+    }
+
+    fn max_length(&self) -> i32 {
+        if self.export.is_some() {
+            self.max_length
+        } else {
+            0
+        }
+    }
+
+    fn learn(&mut self, clause: &[i32]) {
+        if let Some((data, export)) = self.export {
+            let len = clause.len() as i32;
+            export(data, clause.as_ptr(), len, std::ptr::null_mut());
+        }
+    }
+}
+
+unsafe fn get<'a>(solver: *mut c_void) -> &'a mut Solver<Ipasir2Callbacks> {
+    debug_assert!(!solver.is_null());
+    &mut *(solver as *mut Solver<Ipasir2Callbacks>)
+}
+
+/// Returns the state of the solver in IPASIR-2 terms.
+fn state(solver: &Solver<Ipasir2Callbacks>) -> Ipasir2State {
+    if solver.is_solving() {
+        return Ipasir2State::Solving;
+    }
+    match solver.state() {
+        State::Initializing | State::Configuring => Ipasir2State::Config,
+        State::Satisfied => Ipasir2State::Sat,
+        State::Unsatisfied => Ipasir2State::Unsat,
+        State::Solving => Ipasir2State::Solving,
+        _ => Ipasir2State::Input,
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn ipasir2_signature(signature: *mut *const c_char) -> Ipasir2ErrorCode {
+    if signature.is_null() {
+        return Ipasir2ErrorCode::InvalidArgument;
+    }
+    *signature = ccadical_signature();
+    Ipasir2ErrorCode::Ok
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn ipasir2_init(solver: *mut *mut c_void) -> Ipasir2ErrorCode {
+    if solver.is_null() {
+        return Ipasir2ErrorCode::InvalidArgument;
+    }
+    let mut sat = Solver::new();
+    sat.set_callbacks(Some(Ipasir2Callbacks {
+        terminate: None,
+        export: None,
+        max_length: 0,
+    }));
+    *solver = Box::into_raw(Box::new(sat)) as *mut c_void;
+    Ipasir2ErrorCode::Ok
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn ipasir2_release(solver: *mut c_void) -> Ipasir2ErrorCode {
+    debug_assert!(!solver.is_null());
+    drop(Box::from_raw(solver as *mut Solver<Ipasir2Callbacks>));
+    Ipasir2ErrorCode::Ok
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn ipasir2_options(
+    _solver: *mut c_void,
+    options: *mut *const Ipasir2Option,
+    count: *mut c_int,
+) -> Ipasir2ErrorCode {
+    if options.is_null() || count.is_null() {
+        return Ipasir2ErrorCode::InvalidArgument;
+    }
+    *options = OPTIONS.0.as_ptr();
+    *count = OPTIONS.0.len() as c_int;
+    Ipasir2ErrorCode::Ok
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn ipasir2_set_option(
+    solver: *mut c_void,
+    handle: *const Ipasir2Option,
+    value: i64,
+    _index: i64,
+) -> Ipasir2ErrorCode {
+    let option = match OPTIONS
+        .0
+        .iter()
+        .find(|option| std::ptr::eq(*option, handle))
+    {
+        Some(option) => option,
+        None => return Ipasir2ErrorCode::UnsupportedOption,
+    };
+    if value < option.min || value > option.max {
+        return Ipasir2ErrorCode::InvalidOptionValue;
+    }
+    let sat = get(solver);
+    if state(sat) > option.max_state {
+        return Ipasir2ErrorCode::InvalidState;
+    }
+
+    let name = CStr::from_ptr(option.name).to_str().unwrap();
+    if let Some(limit) = name.strip_prefix("ipasir.limits.") {
+        match sat.set_limit(limit, value as i32) {
+            Ok(()) => Ipasir2ErrorCode::Ok,
+            Err(_) => Ipasir2ErrorCode::Unknown,
+        }
+    } else {
+        match sat.set_option(name.strip_prefix("cadical.").unwrap(), value as i32) {
+            Ok(()) => Ipasir2ErrorCode::Ok,
+            Err(OptionError::ValueOutOfRange { .. }) => Ipasir2ErrorCode::InvalidOptionValue,
+            Err(OptionError::InvalidState) => Ipasir2ErrorCode::InvalidState,
+            Err(_) => Ipasir2ErrorCode::Unknown,
+        }
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn ipasir2_add(
+    solver: *mut c_void,
+    clause: *const i32,
+    len: i32,
+    redundancy: c_int,
+) -> Ipasir2ErrorCode {
+    if len < 0 || (len > 0 && clause.is_null()) || Ipasir2Redundancy::from_raw(redundancy).is_none()
+    {
+        return Ipasir2ErrorCode::InvalidArgument;
+    }
+    let sat = get(solver);
+    if state(sat) == Ipasir2State::Solving {
+        return Ipasir2ErrorCode::InvalidState;
+    }
+    let lits: &[i32] = if len == 0 {
+        &[]
+    } else {
+        std::slice::from_raw_parts(clause, len as usize)
+    };
+    if lits.iter().any(|&lit| lit == 0 || lit == i32::MIN) {
+        return Ipasir2ErrorCode::InvalidArgument;
+    }
+    sat.add_clause(lits.iter().copied());
+    Ipasir2ErrorCode::Ok
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn ipasir2_solve(
+    solver: *mut c_void,
+    result: *mut c_int,
+    assumptions: *const i32,
+    len: i32,
+) -> Ipasir2ErrorCode {
+    if result.is_null() || len < 0 || (len > 0 && assumptions.is_null()) {
+        return Ipasir2ErrorCode::InvalidArgument;
+    }
+    let lits: &[i32] = if len == 0 {
+        &[]
+    } else {
+        std::slice::from_raw_parts(assumptions, len as usize)
+    };
+    if lits.iter().any(|&lit| lit == 0 || lit == i32::MIN) {
+        return Ipasir2ErrorCode::InvalidArgument;
+    }
+    let sat = get(solver);
+    if state(sat) == Ipasir2State::Solving {
+        return Ipasir2ErrorCode::InvalidState;
+    }
+    *result = match sat.solve_with(lits.iter().copied()) {
+        Some(true) => 10,
+        Some(false) => 20,
+        None => 0,
+    };
+    Ipasir2ErrorCode::Ok
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn ipasir2_val(
+    solver: *mut c_void,
+    lit: i32,
+    result: *mut i32,
+) -> Ipasir2ErrorCode {
+    if result.is_null() || lit == 0 || lit == i32::MIN {
+        return Ipasir2ErrorCode::InvalidArgument;
+    }
+    let sat = get(solver);
+    if state(sat) != Ipasir2State::Sat {
+        return Ipasir2ErrorCode::InvalidState;
+    }
+    *result = match sat.value(lit) {
+        Some(true) => lit,
+        Some(false) => -lit,
+        None => 0,
+    };
+    Ipasir2ErrorCode::Ok
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn ipasir2_failed(
+    solver: *mut c_void,
+    lit: i32,
+    result: *mut c_int,
+) -> Ipasir2ErrorCode {
+    if result.is_null() || lit == 0 || lit == i32::MIN {
+        return Ipasir2ErrorCode::InvalidArgument;
+    }
+    let sat = get(solver);
+    if state(sat) != Ipasir2State::Unsat {
+        return Ipasir2ErrorCode::InvalidState;
+    }
+    *result = sat.failed(lit) as c_int;
+    Ipasir2ErrorCode::Ok
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn ipasir2_set_terminate(
+    solver: *mut c_void,
+    data: *mut c_void,
+    terminate: Option<extern "C" fn(*mut c_void) -> c_int>,
+) -> Ipasir2ErrorCode {
+    if let Some(cbs) = get(solver).get_callbacks() {
+        cbs.terminate = terminate.map(|terminate| (data, terminate));
+    }
+    Ipasir2ErrorCode::Ok
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn ipasir2_set_export(
+    solver: *mut c_void,
+    data: *mut c_void,
+    max_length: i32,
+    export: Option<ExportCallback>,
+) -> Ipasir2ErrorCode {
+    if let Some(cbs) = get(solver).get_callbacks() {
+        cbs.export = export.map(|export| (data, export));
+        cbs.max_length = max_length;
+    }
+    Ipasir2ErrorCode::Ok
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn ipasir2_set_delete(
+    _solver: *mut c_void,
+    _data: *mut c_void,
+    _delete: Option<extern "C" fn(*mut c_void, *const i32, i32, *mut c_void)>,
+) -> Ipasir2ErrorCode {
+    Ipasir2ErrorCode::Unsupported
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn ipasir2_set_import(
+    _solver: *mut c_void,
+    _data: *mut c_void,
+    _redundancy: c_int,
+    _import: Option<extern "C" fn(*mut c_void)>,
+) -> Ipasir2ErrorCode {
+    Ipasir2ErrorCode::Unsupported
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn ipasir2_set_fixed(
+    _solver: *mut c_void,
+    _data: *mut c_void,
+    _fixed: Option<extern "C" fn(*mut c_void, i32)>,
+) -> Ipasir2ErrorCode {
+    Ipasir2ErrorCode::Unsupported
+}
@@ -0,0 +1,384 @@
+//! A solver running in a child process, so that a crash or a runaway solve
+//! call in the C++ code cannot take down the host process. The host and the
+//! worker exchange one command or reply per line over the standard input and
+//! output of the worker:
+//! * `c <lits>`: adds a clause, without a reply,
+//! * `o <name> <value>`: sets an option, replied with `ok` or `err <msg>`,
+//! * `s <lits>`: solves under the given assumptions, replied with `r 10`
+//!   followed by the model, with `r 20` followed by the failed assumptions,
+//!   or with `r 0` if the result is unknown.
+
+use super::{ClauseStore, Error, Solver};
+use std::ffi::OsString;
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::process::{Child, ChildStdin, Command, Stdio};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::thread;
+use std::time::Duration;
+
+/// Serves the commands read from the input with a fresh solver, and writes
+/// the replies to the output, until the input is closed. This is the main
+/// loop of the worker process, as in the `cadical-worker` binary.
+pub fn serve<R, W>(input: R, mut output: W) -> io::Result<()>
+where
+    R: BufRead,
+    W: Write,
+{
+    let mut sat: Solver = Solver::new();
+    let mut lits: Vec<i32> = Vec::new();
+    for line in input.lines() {
+        let line = line?;
+        let mut words = line.split_whitespace();
+        let command = words.next();
+        if command == Some("o") {
+            let name = words.next().unwrap_or("");
+            let value = words.next().and_then(|word| word.parse().ok());
+            match value.map(|value| sat.set_option(name, value)) {
+                Some(Ok(())) => writeln!(output, "ok")?,
+                Some(Err(err)) => writeln!(output, "err {}", err)?,
+                None => writeln!(output, "err invalid value")?,
+            }
+            output.flush()?;
+            continue;
+        }
+
+        lits.clear();
+        for word in words {
+            match word.parse::<i32>() {
+                Ok(lit) if lit != 0 && lit != i32::MIN => lits.push(lit),
+                _ => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        "invalid literal",
+                    ))
+                }
+            }
+        }
+        match command {
+            Some("c") => sat.add_clause(lits.iter().copied()),
+            Some("s") => {
+                match sat.solve_with(lits.iter().copied()) {
+                    Some(true) => {
+                        write!(output, "r 10")?;
+                        for var in 1..=sat.max_variable() {
+                            let lit = if sat.value(var) == Some(false) {
+                                -var
+                            } else {
+                                var
+                            };
+                            write!(output, " {}", lit)?;
+                        }
+                    }
+                    Some(false) => {
+                        write!(output, "r 20")?;
+                        for &lit in lits.iter().filter(|&&lit| sat.failed(lit)) {
+                            write!(output, " {}", lit)?;
+                        }
+                    }
+                    None => write!(output, "r 0")?,
+                }
+                writeln!(output)?;
+                output.flush()?;
+            }
+            _ => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "invalid command",
+                ))
+            }
+        }
+    }
+    Ok(())
+}
+
+/// A running worker process.
+struct Worker {
+    child: Child,
+    input: BufWriter<ChildStdin>,
+    replies: Receiver<String>,
+}
+
+impl Worker {
+    fn spawn(command: &mut Command) -> Result<Self, Error> {
+        let mut child = command
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .map_err(|_| Error::new("cannot start worker"))?;
+        let input = BufWriter::new(child.stdin.take().unwrap());
+        let output = BufReader::new(child.stdout.take().unwrap());
+
+        // the replies are read on a separate thread, so that the host can
+        // stop waiting for a runaway worker
+        let (sender, replies) = mpsc::channel();
+        thread::spawn(move || {
+            for line in output.lines() {
+                let line = match line {
+                    Ok(line) => line,
+                    Err(_) => break,
+                };
+                if sender.send(line).is_err() {
+                    break;
+                }
+            }
+        });
+        Ok(Worker {
+            child,
+            input,
+            replies,
+        })
+    }
+
+    fn send(&mut self, command: &str, lits: &[i32]) -> io::Result<()> {
+        write!(self.input, "{}", command)?;
+        for lit in lits {
+            write!(self.input, " {}", lit)?;
+        }
+        writeln!(self.input)
+    }
+
+    fn receive(&mut self, timeout: Option<Duration>) -> Result<String, Error> {
+        self.input
+            .flush()
+            .map_err(|_| Error::new("worker crashed"))?;
+        let reply = match timeout {
+            Some(timeout) => self.replies.recv_timeout(timeout),
+            None => self
+                .replies
+                .recv()
+                .map_err(|_| RecvTimeoutError::Disconnected),
+        };
+        reply.map_err(|err| match err {
+            RecvTimeoutError::Timeout => Error::new("worker timed out"),
+            RecvTimeoutError::Disconnected => Error::new("worker crashed"),
+        })
+    }
+}
+
+impl Drop for Worker {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+/// A solver that runs in a child process with the same basic interface as
+/// `Solver`. The child process is started with the given command, which has
+/// to run `serve` on its standard input and output, like the `cadical-worker`
+/// binary built with the `isolated` feature. The clauses and options are
+/// also kept in the host, so when the worker crashes or is killed because a
+/// solve call exceeded its timeout, a new worker is started and the formula
+/// is loaded into it again before the next call. Operations that fail
+/// because of the worker return an error.
+/// # Examples
+/// ```no_run
+/// let mut sat = cadical::IsolatedSolver::new("cadical-worker", &[]);
+/// sat.add_clause([1, 2]);
+/// sat.add_clause([-1, 2]);
+/// assert_eq!(sat.solve().unwrap(), Some(true));
+/// assert_eq!(sat.value(2), Some(true));
+/// ```
+pub struct IsolatedSolver {
+    program: OsString,
+    args: Vec<OsString>,
+    worker: Option<Worker>,
+    clauses: ClauseStore,
+    options: Vec<(String, i32)>,
+    max_var: i32,
+    timeout: Option<Duration>,
+    status: Option<bool>,
+    model: Vec<i32>,
+    failed: Vec<i32>,
+    restarts: usize,
+}
+
+impl IsolatedSolver {
+    /// Creates a solver whose worker process is started with the given
+    /// program and arguments on first use.
+    pub fn new<S: Into<OsString> + Clone>(program: S, args: &[S]) -> Self {
+        IsolatedSolver {
+            program: program.into(),
+            args: args.iter().cloned().map(Into::into).collect(),
+            worker: None,
+            clauses: ClauseStore::new(),
+            options: Vec::new(),
+            max_var: 0,
+            timeout: None,
+            status: None,
+            model: Vec::new(),
+            failed: Vec::new(),
+            restarts: 0,
+        }
+    }
+
+    /// Sets the time limit of the solve calls. A worker that does not answer
+    /// in time is killed, the call returns an error, and a new worker is
+    /// started for the next call.
+    pub fn set_timeout(&mut self, timeout: Option<Duration>) {
+        self.timeout = timeout;
+    }
+
+    /// Returns the number of times a worker had to be restarted after a
+    /// crash or a timeout.
+    pub fn restarts(&self) -> usize {
+        self.restarts
+    }
+
+    /// Returns the running worker, after starting a new one and loading the
+    /// formula into it if necessary.
+    fn worker(&mut self) -> Result<&mut Worker, Error> {
+        if self.worker.is_none() {
+            let mut command = Command::new(&self.program);
+            command.args(&self.args);
+            let mut worker = Worker::spawn(&mut command)?;
+            for (name, value) in self.options.iter() {
+                let command = format!("o {} {}", name, value);
+                worker
+                    .send(&command, &[])
+                    .map_err(|_| Error::new("worker crashed"))?;
+                if worker.receive(None)? != "ok" {
+                    return Err(Error::new("invalid option"));
+                }
+            }
+            for index in 0..self.clauses.len() {
+                let clause: Vec<i32> = self.clauses.clause(index).collect();
+                worker
+                    .send("c", &clause)
+                    .map_err(|_| Error::new("worker crashed"))?;
+            }
+            self.worker = Some(worker);
+        }
+        Ok(self.worker.as_mut().unwrap())
+    }
+
+    /// Drops the worker after a failure, so that a new one is started.
+    fn failure<T>(&mut self, err: Error) -> Result<T, Error> {
+        if self.worker.take().is_some() {
+            self.restarts += 1;
+        }
+        self.status = None;
+        Err(err)
+    }
+
+    /// Adds the given clause to the solver. The clause is sent to the
+    /// worker lazily, a crashed worker is detected by the next solve call.
+    pub fn add_clause<I>(&mut self, clause: I)
+    where
+        I: IntoIterator<Item = i32>,
+    {
+        let clause: Vec<i32> = clause.into_iter().collect();
+        debug_assert!(clause.iter().all(|&lit| lit != 0 && lit != i32::MIN));
+        for &lit in clause.iter() {
+            self.max_var = self.max_var.max(lit.abs());
+        }
+        self.clauses.push(&clause);
+        self.status = None;
+        if let Some(worker) = self.worker.as_mut() {
+            if worker.send("c", &clause).is_err() {
+                self.worker = None;
+                self.restarts += 1;
+            }
+        }
+    }
+
+    /// Sets the value of the given option in the worker.
+    pub fn set_option(&mut self, name: &str, value: i32) -> Result<(), Error> {
+        if name.is_empty() || name.contains(char::is_whitespace) {
+            return Err(Error::new("unknown option"));
+        }
+        let command = format!("o {} {}", name, value);
+        let reply = match self.worker() {
+            Ok(worker) => match worker.send(&command, &[]) {
+                Ok(()) => worker.receive(None),
+                Err(_) => Err(Error::new("worker crashed")),
+            },
+            Err(err) => Err(err),
+        };
+        match reply {
+            Ok(reply) if reply == "ok" => {
+                self.options.push((name.to_string(), value));
+                Ok(())
+            }
+            Ok(reply) => Err(Error::new(reply.trim_start_matches("err "))),
+            Err(err) => self.failure(err),
+        }
+    }
+
+    /// Solves the formula defined by the added clauses.
+    pub fn solve(&mut self) -> Result<Option<bool>, Error> {
+        self.solve_with(None)
+    }
+
+    /// Solves the formula under the given assumptions. Returns an error if
+    /// the worker crashed or did not answer within the timeout.
+    pub fn solve_with<I>(&mut self, assumptions: I) -> Result<Option<bool>, Error>
+    where
+        I: IntoIterator<Item = i32>,
+    {
+        let assumptions: Vec<i32> = assumptions.into_iter().collect();
+        debug_assert!(assumptions.iter().all(|&lit| lit != 0 && lit != i32::MIN));
+        let timeout = self.timeout;
+        let reply = match self.worker() {
+            Ok(worker) => match worker.send("s", &assumptions) {
+                Ok(()) => worker.receive(timeout),
+                Err(_) => Err(Error::new("worker crashed")),
+            },
+            Err(err) => Err(err),
+        };
+        let reply = match reply {
+            Ok(reply) => reply,
+            Err(err) => return self.failure(err),
+        };
+
+        let mut words = reply.split_whitespace();
+        let code = match (words.next(), words.next()) {
+            (Some("r"), Some(code)) => code,
+            _ => return self.failure(Error::new("invalid reply")),
+        };
+        let lits: Result<Vec<i32>, _> = words.map(|word| word.parse::<i32>()).collect();
+        let lits = match lits {
+            Ok(lits) => lits,
+            Err(_) => return self.failure(Error::new("invalid reply")),
+        };
+        self.status = match code {
+            "10" => {
+                self.model = lits;
+                Some(true)
+            }
+            "20" => {
+                self.failed = lits;
+                Some(false)
+            }
+            _ => None,
+        };
+        Ok(self.status)
+    }
+
+    /// Returns the result of the last solve call, or `None` if it was
+    /// unknown or the formula was changed since then.
+    pub fn status(&self) -> Option<bool> {
+        self.status
+    }
+
+    /// Returns the maximum variable index of the added clauses.
+    pub fn max_variable(&self) -> i32 {
+        self.max_var
+    }
+
+    /// Returns the value of the given literal in the last solution. The
+    /// state of the solver must be `Some(true)`.
+    pub fn value(&self, lit: i32) -> Option<bool> {
+        debug_assert!(self.status == Some(true));
+        debug_assert!(lit != 0 && lit != i32::MIN);
+        let val = *self.model.get(lit.unsigned_abs() as usize - 1)?;
+        Some((val > 0) == (lit > 0))
+    }
+
+    /// Checks if the given assumed literal was used in the proof of the
+    /// unsatisfiability of the formula. The state of the solver must be
+    /// `Some(false)`.
+    pub fn failed(&self, lit: i32) -> bool {
+        debug_assert!(self.status == Some(false));
+        self.failed.contains(&lit)
+    }
+}
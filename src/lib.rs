@@ -7,60 +7,111 @@
 //! overall place. It was written by Armin Biere, and it is available under the
 //! MIT license.
 
+use std::any::Any;
 use std::cell::Cell;
+use std::collections::BTreeMap;
+use std::convert::TryFrom;
 use std::ffi::{CStr, CString};
-use std::mem::ManuallyDrop;
 use std::os::raw::{c_char, c_int, c_void};
+use std::panic::{self, AssertUnwindSafe};
 use std::path::Path;
-use std::ptr::null;
-use std::time::Instant;
+use std::ptr::{null, null_mut};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, OnceLock};
+use std::time::{Duration, Instant};
 use std::{fmt, slice};
 
+mod api_trace;
+mod appender;
+mod background;
+mod blif;
+mod clause;
+mod components;
+pub mod configs;
+#[cfg(feature = "diagnostics")]
+pub mod diagnostics;
+mod dimacs_error;
+pub mod encode;
+mod equivalence;
+mod formula;
+pub mod generators;
+#[cfg(feature = "dimacs")]
+mod instance;
+#[cfg(feature = "cdylib")]
+mod ipasir;
+#[cfg(feature = "cdylib")]
+mod ipasir2;
+#[cfg(feature = "isolated")]
+pub mod isolated;
+mod manifest;
+mod memory;
+mod opb;
+mod optimize;
+mod option_error;
+mod options;
+#[cfg(feature = "smallvec")]
+mod pool;
+mod proof;
+mod report;
+#[cfg(feature = "rustsat")]
+mod rustsat_traits;
+mod sanitize;
+mod selfcheck;
+mod shards;
+mod shared;
+mod stats;
+mod store;
+#[cfg(feature = "metrics")]
+mod telemetry;
+#[cfg(feature = "testing")]
+pub mod testing;
+#[cfg(feature = "trace-viz")]
+mod trace;
+pub mod typestate;
+#[cfg(feature = "varisat")]
+mod varisat;
+#[cfg(feature = "verify")]
+mod verify;
+mod zero_policy;
+pub use api_trace::ApiTrace;
+pub use background::SolveHandle;
+pub use blif::{BlifNetwork, BlifTable};
+pub use clause::{AsClause, ClauseBuilder};
+pub use components::ComponentResult;
+pub use dimacs_error::{DimacsError, DimacsErrorKind, ReadDimacsError};
+pub use formula::CnfFormula;
+#[cfg(feature = "isolated")]
+pub use isolated::IsolatedSolver;
+pub use manifest::Manifest;
+pub use opb::OpbInstance;
+pub use optimize::Optimum;
+pub use option_error::OptionError;
+pub use options::OptionsSnapshot;
+#[cfg(feature = "smallvec")]
+pub use pool::{ClausePool, LearnedClause};
+pub use report::ReportEvent;
+#[cfg(feature = "rustsat")]
+pub use rustsat_traits::Interrupter;
+pub use sanitize::SanitizeReport;
+pub use shared::SharedSolver;
+#[cfg(target_has_atomic = "64")]
+pub use stats::LiveStats;
+#[cfg(feature = "profiling")]
+pub use stats::Profile;
+pub use stats::{EffortStats, FormulaStats, StatsSnapshot};
+pub use store::ClauseStore;
+#[cfg(feature = "trace-viz")]
+pub use trace::{Trace, TraceEvent};
+pub use zero_policy::ZeroLiteralPolicy;
+
+#[cfg(all(feature = "pure-rust", feature = "memory-accounting"))]
+compile_error!("the memory-accounting feature requires the CaDiCaL backend");
+#[cfg(all(feature = "pure-rust", not(miri)))]
+mod cdcl;
+pub mod ffi;
 #[cfg(miri)]
 mod mockup;
-#[cfg(miri)]
-use mockup::*;
-
-#[cfg(not(miri))]
-extern "C" {
-    fn ccadical_signature() -> *const c_char;
-    fn ccadical_init() -> *mut c_void;
-    fn ccadical_release(ptr: *mut c_void);
-    fn ccadical_add(ptr: *mut c_void, lit: c_int);
-    fn ccadical_assume(ptr: *mut c_void, lit: c_int);
-    fn ccadical_solve(ptr: *mut c_void) -> c_int;
-    fn ccadical_val(ptr: *mut c_void, lit: c_int) -> c_int;
-    fn ccadical_failed(ptr: *mut c_void, lit: c_int) -> c_int;
-    fn ccadical_set_terminate(
-        ptr: *mut c_void,
-        data: *const c_void,
-        cbs: Option<extern "C" fn(*const c_void) -> c_int>,
-    );
-    fn ccadical_set_learn(
-        ptr: *mut c_void,
-        data: *const c_void,
-        max_len: c_int,
-        cbs: Option<extern "C" fn(*const c_void, *const c_int)>,
-    );
-    fn ccadical_status(ptr: *mut c_void) -> c_int;
-    fn ccadical_vars(ptr: *mut c_void) -> c_int;
-    fn ccadical_active(ptr: *mut c_void) -> i64;
-    fn ccadical_irredundant(ptr: *mut c_void) -> i64;
-    fn ccadical_read_dimacs(
-        ptr: *mut c_void,
-        path: *const c_char,
-        vars: *mut c_int,
-        strict: c_int,
-    ) -> *const c_char;
-    fn ccadical_write_dimacs(
-        ptr: *mut c_void,
-        path: *const c_char,
-        min_max_var: c_int,
-    ) -> *const c_char;
-    fn ccadical_configure(ptr: *mut c_void, name: *const c_char) -> c_int;
-    fn ccadical_limit2(ptr: *mut c_void, name: *const c_char, limit: c_int) -> c_int;
-    fn ccadical_reserve(ptr: *mut c_void, min_max_var: c_int);
-}
+use ffi::*;
 
 /// The CaDiCaL incremental SAT solver. The literals are unwrapped positive
 /// and negative integers, exactly as in the DIMACS format. The common IPASIR
@@ -76,13 +127,129 @@ extern "C" {
 pub struct Solver<C: Callbacks = Timeout> {
     ptr: *mut c_void,
     cbs: Option<Box<C>>,
+    clause: Vec<i32>,
+    scopes: Vec<i32>,
+    removable: Vec<i32>,
+    groups: BTreeMap<String, AssumptionGroup>,
+    sticky: Vec<i32>,
+    retired: Vec<i32>,
+    selectors: Vec<i32>,
+    solve_time: Option<Duration>,
+    // the seed to restore when the deterministic mode is turned off
+    deterministic: Option<i32>,
+    config: Option<String>,
+    options: BTreeMap<String, i32>,
+    limits: BTreeMap<String, i32>,
+    propagation_limit: Option<i32>,
+    interrupt: Option<Arc<AtomicBool>>,
+    #[cfg(feature = "rustsat")]
+    assumptions: Vec<i32>,
+    #[cfg(feature = "metrics")]
+    metrics_labels: Vec<(String, String)>,
+    dimacs_log: Option<appender::DimacsLog>,
+    memory_limit: Option<u64>,
+    account: memory::Account,
+    #[cfg(target_has_atomic = "64")]
+    live_stats: Option<LiveStats>,
+    #[cfg(feature = "diagnostics")]
+    registration: diagnostics::Registration,
+    #[cfg(feature = "verify")]
+    shadow: ClauseStore,
+    unsat_check: Option<selfcheck::UnsatCheck>,
+    proof: Option<proof::ProofLog>,
+    sanitizer: Option<sanitize::Sanitizer>,
+    out_of_memory: bool,
+    // atomic, so the flag is really set while the native code runs, which
+    // can reenter the solver through raw pointers
+    in_solve: AtomicBool,
+    zero_policy: ZeroLiteralPolicy,
+    #[cfg(feature = "smallvec")]
+    clause_pool: ClausePool,
+    #[cfg(feature = "cpp-tracing")]
+    api_trace_file: *mut c_void,
+}
+
+/// The data shared with the terminate and learn callbacks during solving.
+struct CallbackData<C> {
+    ptr: *mut c_void,
+    cbs: Cell<Option<Box<C>>>,
+    deadline: Option<Instant>,
+    propagations: Option<i64>,
+    interrupt: Option<Arc<AtomicBool>>,
+    max_length: i32,
+    max_glue: i32,
+    conflicts: bool,
+    restarts: Cell<Option<i64>>,
+    restart_events: bool,
+    reports: Option<Instant>,
+    account: *const memory::Account,
+    memory_limit: Option<u64>,
+    stats: *mut i64,
+    panic: Cell<Option<Box<dyn Any + Send>>>,
+    #[cfg(feature = "debug-hooks")]
+    decisions: Cell<Option<(i64, u64)>>,
+    #[cfg(feature = "smallvec")]
+    owned: bool,
+    #[cfg(feature = "smallvec")]
+    pool: ClausePool,
+}
+
+/// A named set of assumptions that can be switched on and off.
+#[derive(Clone)]
+struct AssumptionGroup {
+    lits: Vec<i32>,
+    active: bool,
 }
 
 impl<C: Callbacks> Solver<C> {
     /// Constructs a new solver instance.
     pub fn new() -> Self {
-        let ptr = unsafe { ccadical_init() };
-        Self { ptr, cbs: None }
+        let account = memory::Account::new();
+        let ptr = {
+            let _account = account.enter();
+            unsafe { ccadical_init() }
+        };
+        Self {
+            ptr,
+            cbs: None,
+            clause: Vec::new(),
+            scopes: Vec::new(),
+            removable: Vec::new(),
+            groups: BTreeMap::new(),
+            sticky: Vec::new(),
+            retired: Vec::new(),
+            selectors: Vec::new(),
+            solve_time: None,
+            deterministic: None,
+            config: None,
+            options: BTreeMap::new(),
+            limits: BTreeMap::new(),
+            propagation_limit: None,
+            interrupt: None,
+            #[cfg(feature = "rustsat")]
+            assumptions: Vec::new(),
+            #[cfg(feature = "metrics")]
+            metrics_labels: Vec::new(),
+            dimacs_log: None,
+            memory_limit: None,
+            account,
+            #[cfg(target_has_atomic = "64")]
+            live_stats: None,
+            #[cfg(feature = "diagnostics")]
+            registration: diagnostics::Registration::new(),
+            #[cfg(feature = "verify")]
+            shadow: ClauseStore::new(),
+            unsat_check: None,
+            proof: None,
+            sanitizer: None,
+            in_solve: AtomicBool::new(false),
+            zero_policy: ZeroLiteralPolicy::Unchecked,
+            #[cfg(feature = "smallvec")]
+            clause_pool: ClausePool::new(),
+            #[cfg(feature = "cpp-tracing")]
+            api_trace_file: std::ptr::null_mut(),
+            out_of_memory: false,
+        }
     }
 
     /// Constructs a new solver with one of the following pre-defined
@@ -92,474 +259,4152 @@ impl<C: Callbacks> Solver<C> {
     /// * `sat`: set internal options to target satisfiable instances
     /// * `unsat`: set internal options to target unsatisfiable instances
     pub fn with_config(config: &str) -> Result<Self, Error> {
-        let sat: Self = Default::default();
-        let config = CString::new(config).map_err(|_| Error::new("invalid string"))?;
-        let res = unsafe { ccadical_configure(sat.ptr, config.as_ptr()) };
+        let mut sat: Self = Default::default();
+        let name = CString::new(config).map_err(|_| Error::new("invalid string"))?;
+        let res = unsafe { ccadical_configure(sat.ptr, name.as_ptr()) };
         if res != 0 {
+            sat.config = Some(config.to_string());
             Ok(sat)
         } else {
             Err(Error::new("invalid config"))
         }
     }
 
-    /// Returns the name and version of the CaDiCaL library.
+    /// Returns the solver to its freshly constructed state: the formula,
+    /// the options, the limits including the memory limit, the scopes, the
+    /// removable clauses and all kinds of assumptions are cleared, and a
+    /// pending interrupt is dropped. CaDiCaL does not support clearing its
+    /// formula, so the native solver is released and a new instance is
+    /// created. The installed callbacks (use `set_callbacks(None)` to remove
+    /// them), the interrupt flag returned by `interrupt_flag`, the live
+    /// statistics, the zero literal policy and the metrics labels are kept,
+    /// and the proof logging, the unsat check and the sanitizer stay enabled
+    /// but start over with the empty formula.
+    /// # Examples
+    /// ```
+    /// let mut sat: cadical::Solver = Default::default();
+    /// sat.add_clause([1]);
+    /// sat.add_clause([-1]);
+    /// assert_eq!(sat.solve(), Some(false));
+    /// sat.reset();
+    /// assert_eq!(sat.max_variable(), 0);
+    /// assert_eq!(sat.solve(), Some(true));
+    /// ```
+    pub fn reset(&mut self) {
+        self.check_reentrancy("reset");
+        let _account = self.account.enter();
+        unsafe {
+            ccadical_release(self.ptr);
+            self.ptr = ccadical_init();
+            // the trace continues with the calls of the new solver
+            #[cfg(feature = "cpp-tracing")]
+            if !self.api_trace_file.is_null() {
+                ccadical_trace_api_calls(self.ptr, self.api_trace_file);
+            }
+        }
+        self.scopes.clear();
+        self.removable.clear();
+        self.groups.clear();
+        self.sticky.clear();
+        self.retired.clear();
+        self.selectors.clear();
+        self.solve_time = None;
+        self.deterministic = None;
+        self.config = None;
+        self.options.clear();
+        self.limits.clear();
+        self.propagation_limit = None;
+        self.memory_limit = None;
+        if let Some(flag) = &self.interrupt {
+            flag.store(false, Ordering::Relaxed);
+        }
+        #[cfg(feature = "rustsat")]
+        self.assumptions.clear();
+        self.dimacs_log = None;
+        #[cfg(feature = "verify")]
+        self.shadow.clear();
+        if self.unsat_check.is_some() {
+            self.unsat_check = Some(Default::default());
+        }
+        if self.proof.is_some() {
+            // the old solver is released, so its proof file can be closed
+            self.proof = proof::ProofLog::open(self.ptr).ok();
+        }
+        if self.sanitizer.is_some() {
+            self.sanitizer = Some(Default::default());
+        }
+        self.out_of_memory = false;
+        #[cfg(feature = "diagnostics")]
+        self.update_diagnostics();
+    }
+
+    /// Returns a new solver with a copy of the current formula, so that a
+    /// formula can be loaded and simplified once as a template, and then
+    /// cloned cheaply for many independent queries, possibly on different
+    /// threads. The irredundant clauses, the reconstruction stack of the
+    /// eliminated variables, the options, the scopes and all kinds of
+    /// persistent assumptions are copied, while learned clauses, callbacks,
+    /// limits and statistics are not.
+    /// # Examples
+    /// ```
+    /// let mut base: cadical::Solver = Default::default();
+    /// base.add_formula(&cadical::generators::random_ksat(50, 150, 3, 1));
+    /// base.simplify(2);
+    /// let handles: Vec<_> = (1..=4)
+    ///     .map(|var| {
+    ///         let mut sat: cadical::Solver = base.fork();
+    ///         std::thread::spawn(move || sat.solve_with([var]))
+    ///     })
+    ///     .collect();
+    /// for handle in handles {
+    ///     assert!(handle.join().unwrap().is_some());
+    /// }
+    /// ```
+    pub fn fork<D: Callbacks>(&self) -> Solver<D> {
+        let mut other: Solver<D> = Solver::new();
+        {
+            let _account = other.account.enter();
+            let ret = unsafe { ccadical_copy(self.ptr, other.ptr) };
+            other.allocated(ret);
+        }
+        other.scopes = self.scopes.clone();
+        other.removable = self.removable.clone();
+        other.groups = self.groups.clone();
+        other.sticky = self.sticky.clone();
+        other.retired = self.retired.clone();
+        other.selectors = self.selectors.clone();
+        other.deterministic = self.deterministic;
+        other.config = self.config.clone();
+        other.options = self.options.clone();
+        #[cfg(feature = "verify")]
+        other.shadow.clone_from(&self.shadow);
+        other.unsat_check = self.unsat_check.clone();
+        other.sanitizer = self.sanitizer.clone();
+        other.zero_policy = self.zero_policy;
+        other
+    }
+
+    /// Returns the name and version of the CaDiCaL library. Invalid UTF-8
+    /// sequences are replaced with `U+FFFD`, use `signature_bytes` for the
+    /// original bytes.
     pub fn signature(&self) -> &str {
-        let sig = unsafe { CStr::from_ptr(ccadical_signature()) };
-        sig.to_str().unwrap_or("invalid")
+        signature()
+    }
+
+    /// Returns the name and version of the CaDiCaL library as raw bytes.
+    pub fn signature_bytes(&self) -> &[u8] {
+        unsafe { CStr::from_ptr(ccadical_signature()) }.to_bytes()
     }
 
     /// Adds the given clause to the solver. Negated literals are negative
     /// integers, positive literals are positive ones. All literals must be
-    /// non-zero and different from `i32::MIN`.
+    /// different from `i32::MIN`, and zeros are handled according to the
+    /// `ZeroLiteralPolicy` of the solver. The literals are collected in a
+    /// reused buffer and passed to CaDiCaL in a single call.
     #[inline]
     pub fn add_clause<I>(&mut self, clause: I)
     where
         I: IntoIterator<Item = i32>,
     {
-        for lit in clause {
-            debug_assert!(lit != 0 && lit != i32::MIN);
-            unsafe { ccadical_add(self.ptr, lit) };
-        }
-        unsafe { ccadical_add(self.ptr, 0) };
+        self.check_reentrancy("add_clause");
+        self.clause.clear();
+        self.clause.extend(clause);
+        self.check_selectors(&self.clause);
+        self.add_clause_buffer();
     }
 
-    /// Solves the formula defined by the added clauses. If the formula is
-    /// satisfiable, then `Some(true)` is returned. If the formula is
-    /// unsatisfiable, then `Some(false)` is returned. If the solver runs out
-    /// of resources or was terminated, then `None` is returned.
-    pub fn solve(&mut self) -> Option<bool> {
-        // We need a cell (interior mutability) becase we need multiple pointers
-        // to the callback object, one stored here in the solver, the others in
-        // the CaDiCal library. Since it is put behind a cell we can access it
-        // mutabily when needed (from the same thread).
-        let data = Cell::new(None);
-
-        // put the cbs pointer into the cell
-        if let Some(mut cbs) = self.cbs.take() {
-            cbs.started();
-            let max_length = cbs.max_length();
-
-            data.set(Some(cbs));
-            let data = &data as *const Cell<Option<Box<C>>> as *const c_void;
-            unsafe {
-                ccadical_set_terminate(self.ptr, data, Some(Self::terminate_cb));
-                ccadical_set_learn(self.ptr, data, max_length, Some(Self::learn_cb));
-            }
+    /// Adds the clause in the buffer, which might contain zeros.
+    #[inline]
+    fn add_clause_buffer(&mut self) {
+        if self.zero_policy != ZeroLiteralPolicy::Unchecked && self.clause.contains(&0) {
+            self.add_zero_separated();
         } else {
-            unsafe {
-                ccadical_set_terminate(self.ptr, null(), None);
-                ccadical_set_learn(self.ptr, null(), 0, None);
-            }
+            self.add_buffered_clause();
         }
+    }
 
-        let ret = unsafe { ccadical_solve(self.ptr) };
-
-        // move the cbs pointer back
-        if let Some(cbs) = data.replace(None) {
-            self.cbs = Some(cbs);
-
-            unsafe {
-                ccadical_set_terminate(self.ptr, null(), None);
-                ccadical_set_learn(self.ptr, null(), 0, None);
+    /// Adds the clause in the buffer.
+    #[inline]
+    fn add_buffered_clause(&mut self) {
+        let _account = self.account.enter();
+        debug_assert!(self.clause.iter().all(|&lit| lit != 0 && lit != i32::MIN));
+        if let Some(sanitizer) = self.sanitizer.as_mut() {
+            if !sanitizer.sanitize(&mut self.clause) {
+                return;
             }
         }
-
-        if ret == 10 {
-            Some(true)
-        } else if ret == 20 {
-            Some(false)
-        } else {
-            None
+        if let Some(&act) = self.scopes.last() {
+            self.clause.push(-act);
+        }
+        if let Some(log) = self.dimacs_log.as_mut() {
+            log.record(&self.clause);
         }
+        #[cfg(feature = "verify")]
+        self.shadow.push(&self.clause);
+        if let Some(check) = self.unsat_check.as_mut() {
+            check.record(&self.clause);
+        }
+        if let Some(log) = self.proof.as_mut() {
+            log.record(self.ptr, &self.clause);
+        }
+        let ret = unsafe { ccadical_add_clause(self.ptr, self.clause.as_ptr(), self.clause.len()) };
+        self.allocated(ret);
     }
 
-    /// Solves the formula defined by the set of clauses under the given
-    /// assumptions.
-    pub fn solve_with<I>(&mut self, assumptions: I) -> Option<bool>
+    /// Adds the given clause stored as contiguous literals, such as a slice,
+    /// an array or a vector. Outside of assertion scopes and the
+    /// sanitization mode the literals are passed to CaDiCaL directly,
+    /// without copying them into the buffer used by `add_clause`. All
+    /// literals must be non-zero and different from `i32::MIN`.
+    /// # Examples
+    /// ```
+    /// let mut sat: cadical::Solver = Default::default();
+    /// let clause = vec![1, 2];
+    /// sat.add_clause_slice(&clause);
+    /// sat.add_clause_slice(&[-1]);
+    /// sat.add_clause_slice(&clause[1..]);
+    /// assert_eq!(sat.solve_with([-2]), Some(false));
+    /// ```
+    #[inline]
+    pub fn add_clause_slice<A>(&mut self, clause: &A)
     where
-        I: IntoIterator<Item = i32>,
+        A: AsClause + ?Sized,
     {
-        for lit in assumptions {
-            debug_assert!(lit != 0 && lit != i32::MIN);
-            unsafe { ccadical_assume(self.ptr, lit) };
+        let lits = clause.as_clause();
+        if self.zero_policy != ZeroLiteralPolicy::Unchecked {
+            self.add_clause(lits.iter().copied());
+            return;
         }
-        self.solve()
+        debug_assert!(lits.iter().all(|&lit| lit != 0 && lit != i32::MIN));
+        unsafe { self.add_clause_unchecked(lits) };
     }
 
-    /// Returns the status of the solver as returned by the last call to
-    /// `solve` or `solve_with`. The state becomes `None` if a new clause
-    /// is added.
-    #[inline]
-    pub fn status(&self) -> Option<bool> {
-        let r = unsafe { ccadical_status(self.ptr) };
-        if r == 10 {
-            Some(true)
-        } else if r == 20 {
-            Some(false)
-        } else {
-            None
+    /// Adds a unit clause without the activation literal of the open scope.
+    fn add_unit(&mut self, lit: i32) {
+        let _account = self.account.enter();
+        if let Some(log) = self.dimacs_log.as_mut() {
+            log.record(&[lit]);
+        }
+        #[cfg(feature = "verify")]
+        self.shadow.push(&[lit]);
+        if let Some(check) = self.unsat_check.as_mut() {
+            check.record(&[lit]);
+        }
+        if let Some(log) = self.proof.as_mut() {
+            log.record(self.ptr, &[lit]);
         }
+        let ret = unsafe { ccadical_add_clause(self.ptr, &lit, 1) };
+        self.allocated(ret);
     }
 
-    /// Returns the value of the given literal in the last solution. The
-    /// state of the solver must be `Some(true)`. The returned value is
-    /// `None` if the formula is satisfied regardless of the value of the
-    /// literal.
+    /// Adds the given clause to the solver without checking the literals
+    /// and, outside of assertion scopes and the sanitization mode, without
+    /// copying them.
+    /// # Safety
+    /// All literals must be non-zero and different from `i32::MIN`, which
+    /// is not checked even in debug builds. Zero would terminate the clause
+    /// early and leave the rest of the literals as a new clause.
+    /// # Examples
+    /// ```
+    /// let mut sat: cadical::Solver = Default::default();
+    /// unsafe { sat.add_clause_unchecked(&[1, 2]) };
+    /// unsafe { sat.add_clause_unchecked(&[-1]) };
+    /// assert_eq!(sat.solve_with([-2]), Some(false));
+    /// ```
     #[inline]
-    pub fn value(&self, lit: i32) -> Option<bool> {
-        debug_assert!(self.status() == Some(true));
-        debug_assert!(lit != 0 && lit != i32::MIN);
-        let val = unsafe { ccadical_val(self.ptr, lit) };
-        if val == lit.abs() {
-            Some(true)
-        } else if val == -lit.abs() {
-            Some(false)
+    pub unsafe fn add_clause_unchecked(&mut self, lits: &[i32]) {
+        if self.scopes.is_empty() && self.sanitizer.is_none() {
+            self.check_reentrancy("add_clause");
+            self.check_selectors(lits);
+            let _account = self.account.enter();
+            if let Some(log) = self.dimacs_log.as_mut() {
+                log.record(lits);
+            }
+            #[cfg(feature = "verify")]
+            self.shadow.push(lits);
+            if let Some(check) = self.unsat_check.as_mut() {
+                check.record(lits);
+            }
+            if let Some(log) = self.proof.as_mut() {
+                log.record(self.ptr, lits);
+            }
+            let ret = ccadical_add_clause(self.ptr, lits.as_ptr(), lits.len());
+            self.allocated(ret);
         } else {
-            None
+            self.add_clause(lits.iter().copied());
         }
     }
 
-    /// Checks if the given assumed literal (passed to `solve_with`) was used
-    /// in the proof of the unsatisfiability of the formula. The state of the
-    /// solver must be `Some(false)`.
+    /// Opens a new assertion scope. All clauses added until the matching
+    /// `pop` call are retracted by that call. Internally each scope is
+    /// implemented with a fresh activation variable (the next one after
+    /// `max_variable`), which is added negated to each clause of the scope
+    /// and assumed by every `solve` and `solve_with` call while the scope is
+    /// open. Activation variables are reserved, so later variables must be
+    /// allocated with `new_variable`, see `is_selector`.
+    /// # Examples
+    /// ```
+    /// let mut sat: cadical::Solver = Default::default();
+    /// sat.add_clause([1, 2]);
+    /// sat.push();
+    /// sat.add_clause([-1]);
+    /// sat.add_clause([-2]);
+    /// assert_eq!(sat.solve(), Some(false));
+    /// sat.pop().unwrap();
+    /// assert_eq!(sat.solve(), Some(true));
+    /// ```
+    pub fn push(&mut self) {
+        let act = self.new_selector(0);
+        self.scopes.push(act);
+    }
+
+    /// Closes the innermost assertion scope opened by `push` and permanently
+    /// retracts all clauses added within it. Returns an error if there is
+    /// no open scope.
+    pub fn pop(&mut self) -> Result<(), Error> {
+        let act = self
+            .scopes
+            .pop()
+            .ok_or_else(|| Error::new("no open scope"))?;
+        self.add_unit(-act);
+        Ok(())
+    }
+
+    /// Returns the number of assertion scopes that are currently open.
     #[inline]
-    pub fn failed(&self, lit: i32) -> bool {
-        debug_assert!(self.status() == Some(false));
-        debug_assert!(lit != 0 && lit != i32::MIN);
-        let val = unsafe { ccadical_failed(self.ptr, lit) };
-        val == 1
+    pub fn scope_depth(&self) -> usize {
+        self.scopes.len()
     }
 
-    /// Returns the maximum variable index in the problem as maintained by
-    /// the solver.
+    /// Adds a clause that can be removed later with `remove_clause` using
+    /// the returned handle. The clause is extended with the negation of a
+    /// fresh activation variable, which is assumed by every `solve` and
+    /// `solve_with` call until the clause is removed.
     /// # Examples
     /// ```
     /// let mut sat: cadical::Solver = Default::default();
-    /// sat.add_clause([1, -3]);
-    /// assert_eq!(sat.max_variable(), 3);
-    /// assert_eq!(sat.num_variables(), 2);
-    /// assert_eq!(sat.num_clauses(), 1);
+    /// sat.add_clause([1]);
+    /// let handle = sat.add_removable_clause([-1]);
+    /// assert_eq!(sat.solve(), Some(false));
+    /// sat.remove_clause(handle).unwrap();
+    /// assert_eq!(sat.solve(), Some(true));
     /// ```
-    #[inline]
-    pub fn max_variable(&self) -> i32 {
-        unsafe { ccadical_vars(self.ptr) }
+    pub fn add_removable_clause<I>(&mut self, clause: I) -> ClauseHandle
+    where
+        I: IntoIterator<Item = i32>,
+    {
+        self.check_reentrancy("add_clause");
+        self.clause.clear();
+        self.clause.extend(clause);
+        self.check_selectors(&self.clause);
+        let act = self.new_selector(max_var(&self.clause));
+        self.clause.push(-act);
+        self.add_clause_buffer();
+        self.removable.push(act);
+        ClauseHandle(act)
     }
 
-    /// Returns the number of active variables in the problem. Variables become
-    /// active if a clause is added with it. They become inactive, if they
-    /// are eliminated or become fixed at the root level.
-    #[inline]
-    pub fn num_variables(&self) -> i32 {
-        unsafe { ccadical_active(self.ptr) as i32 }
+    /// Permanently removes a clause added by `add_removable_clause` by
+    /// adding the negated activation variable as a unit clause. Returns an
+    /// error if the clause was already removed.
+    pub fn remove_clause(&mut self, handle: ClauseHandle) -> Result<(), Error> {
+        let pos = self.removable.iter().position(|&act| act == handle.0);
+        let pos = pos.ok_or_else(|| Error::new("unknown clause"))?;
+        self.removable.swap_remove(pos);
+        self.add_unit(-handle.0);
+        Ok(())
     }
 
-    /// Returns the number of active irredundant clauses. Clauses become
-    /// inactive if they are satisfied, subsumed or eliminated.
-    #[inline]
-    pub fn num_clauses(&self) -> usize {
-        unsafe { ccadical_irredundant(self.ptr) as usize }
+    /// Registers a group of assumption literals under the given name,
+    /// replacing any previous group of the same name. The literals of all
+    /// active groups are assumed by every `solve` and `solve_with` call.
+    /// Newly registered groups are active.
+    /// # Examples
+    /// ```
+    /// let mut sat: cadical::Solver = Default::default();
+    /// sat.add_clause([-1, -2]);
+    /// sat.assumption_group("first", [1]);
+    /// sat.assumption_group("second", [2]);
+    /// assert_eq!(sat.solve(), Some(false));
+    /// assert_eq!(sat.failed_groups(), vec!["first", "second"]);
+    /// sat.set_group_active("second", false).unwrap();
+    /// assert_eq!(sat.solve(), Some(true));
+    /// ```
+    pub fn assumption_group<I>(&mut self, name: &str, lits: I)
+    where
+        I: IntoIterator<Item = i32>,
+    {
+        let lits: Vec<i32> = lits.into_iter().collect();
+        debug_assert!(lits.iter().all(|&lit| lit != 0 && lit != i32::MIN));
+        self.check_selectors(&lits);
+        let group = AssumptionGroup { lits, active: true };
+        self.groups.insert(name.to_string(), group);
     }
 
-    /// Sets a solver limit with the corresponding name to the given value.
-    /// These limits are only valid for the next `solve` or `solve_with` call
-    /// and reset to their default values, which disables them.
-    /// The following limits are supported:
-    /// * `preprocessing`: the number of preprocessing rounds to be performed
-    ///    during the search (defaults to `0`).
-    /// * `localsearch`: the number of local search rounds to be performed
-    ///    during the search (defaults to `0`).
-    /// * `terminate`: this value is regularly decremented and aborts the
-    ///    solver when it reaches zero (defaults to `0`).
-    /// * `conflicts`: decremented when a conflict is detected
-    ///    and aborts the solver when it becomes negative (defaults to `-1`).
-    /// * `decisions`: decremented when a decision is made
-    ///    and aborts the solver when it becomes negative (defaults to `-1`).
-    pub fn set_limit(&mut self, name: &str, limit: i32) -> Result<(), Error> {
-        let name = CString::new(name).map_err(|_| Error::new("invalid string"))?;
-        let valid = unsafe { ccadical_limit2(self.ptr, name.as_ptr(), limit) };
-        if valid != 0 {
-            Ok(())
-        } else {
-            Err(Error::new("unknown limit"))
-        }
+    /// Activates or deactivates the assumption group with the given name.
+    /// Returns an error if there is no such group.
+    pub fn set_group_active(&mut self, name: &str, active: bool) -> Result<(), Error> {
+        let group = self.groups.get_mut(name);
+        group.ok_or_else(|| Error::new("unknown group"))?.active = active;
+        Ok(())
     }
 
-    /// Sets the callbacks to be called while the solver is running.
+    /// Removes the assumption group with the given name. Returns an error if
+    /// there is no such group.
+    pub fn remove_group(&mut self, name: &str) -> Result<(), Error> {
+        let group = self.groups.remove(name);
+        group.map(|_| ()).ok_or_else(|| Error::new("unknown group"))
+    }
+
+    /// Returns the names of the active assumption groups that have at least
+    /// one literal used in the proof of unsatisfiability, in alphabetical
+    /// order. The state of the solver must be `Some(false)`.
+    pub fn failed_groups(&self) -> Vec<&str> {
+        debug_assert!(self.status() == Some(false));
+        self.groups
+            .iter()
+            .filter(|(_, group)| group.active)
+            .filter(|(_, group)| {
+                let mut lits = group.lits.iter();
+                lits.any(|&lit| unsafe { ccadical_failed(self.ptr, lit) } == 1)
+            })
+            .map(|(name, _)| name.as_str())
+            .collect()
+    }
+
+    /// Adds the given literals to the sticky assumptions, which are assumed
+    /// by every subsequent `solve` and `solve_with` call until they are
+    /// cleared with `clear_sticky_assumptions`.
     /// # Examples
     /// ```
     /// let mut sat: cadical::Solver = Default::default();
     /// sat.add_clause([1, 2]);
-    /// sat.set_callbacks(Some(cadical::Timeout::new(0.0)));
-    /// assert_eq!(sat.solve(), None);
+    /// sat.assume_sticky([-1]);
+    /// assert_eq!(sat.solve_with([-2]), Some(false));
+    /// assert_eq!(sat.solve(), Some(true));
+    /// assert_eq!(sat.value(1), Some(false));
     /// ```
-    pub fn set_callbacks(&mut self, cbs: Option<C>) {
-        if let Some(cbs) = cbs {
-            self.cbs = Some(Box::new(cbs));
-        } else {
-            self.cbs = None;
+    pub fn assume_sticky<I>(&mut self, lits: I)
+    where
+        I: IntoIterator<Item = i32>,
+    {
+        for lit in lits {
+            debug_assert!(lit != 0 && lit != i32::MIN);
+            self.check_selectors(&[lit]);
+            self.sticky.push(lit);
         }
     }
 
-    extern "C" fn terminate_cb(data: *const c_void) -> c_int {
-        debug_assert!(!data.is_null());
-        let data = unsafe { &*(data as *const Cell<Option<Box<C>>>) };
+    /// Returns the current list of sticky assumptions.
+    #[inline]
+    pub fn sticky_assumptions(&self) -> &[i32] {
+        &self.sticky
+    }
 
-        if let Some(mut cbs) = data.replace(None) {
-            let ret = cbs.terminate();
-            data.set(Some(cbs));
-            ret as c_int
-        } else {
-            0
-        }
+    /// Removes all sticky assumptions.
+    pub fn clear_sticky_assumptions(&mut self) {
+        self.sticky.clear();
     }
 
-    extern "C" fn learn_cb(data: *const c_void, clause: *const c_int) {
-        debug_assert!(!clause.is_null());
-        let mut len: isize = 0;
-        while unsafe { clause.offset(len).read() } != 0 {
-            len += 1;
+    /// Assumes the activation literals of the open scopes and the removable
+    /// clauses, the literals of the active groups and the sticky assumptions.
+    fn assume_persistent(&self) {
+        for lit in self.persistent_assumptions() {
+            unsafe { ccadical_assume(self.ptr, lit) };
         }
-        let clause = unsafe { slice::from_raw_parts(clause, len as usize) };
-        let clause = ManuallyDrop::new(clause);
+    }
 
-        debug_assert!(!data.is_null());
-        let data = unsafe { &*(data as *const Cell<Option<Box<C>>>) };
+    /// Returns the activation literals of the open scopes, the removable
+    /// clauses and the active groups, and the sticky assumptions, which are
+    /// assumed by every solve call.
+    pub(crate) fn persistent_assumptions(&self) -> impl Iterator<Item = i32> + '_ {
+        let groups = self.groups.values().filter(|group| group.active);
+        self.scopes
+            .iter()
+            .chain(self.removable.iter())
+            .chain(groups.flat_map(|group| group.lits.iter()))
+            .chain(self.sticky.iter())
+            .copied()
+    }
 
-        if let Some(mut cbs) = data.replace(None) {
-            cbs.learn(&clause);
-            data.set(Some(cbs));
+    /// Permanently disables the temporary clauses of earlier queries by adding
+    /// their negated activation literals as unit clauses. This is postponed
+    /// to the next `solve` call so that the model or the failed assumptions
+    /// of the previous query remain accessible.
+    fn retire_selectors(&mut self) {
+        for act in std::mem::take(&mut self.retired) {
+            self.add_unit(-act);
         }
     }
 
-    /// Returns a mutable reference to the callbacks.
-    pub fn get_callbacks(&mut self) -> Option<&mut C> {
-        self.cbs.as_deref_mut()
+    /// Returns the flag that terminates the running solve call when set from
+    /// another thread. The flag is cleared after each solve call. If no
+    /// callbacks, timeouts or limits are installed, then the flag is polled
+    /// directly by CaDiCaL without calling back into Rust.
+    /// # Examples
+    /// ```
+    /// use std::sync::atomic::Ordering;
+    /// let mut sat: cadical::Solver = Default::default();
+    /// let flag = sat.interrupt_flag();
+    /// std::thread::spawn(move || flag.store(true, Ordering::Relaxed));
+    /// ```
+    pub fn interrupt_flag(&mut self) -> Arc<AtomicBool> {
+        self.interrupt
+            .get_or_insert_with(|| Arc::new(AtomicBool::new(false)))
+            .clone()
     }
 
-    /// Writes the problem in DIMACS format to the given file.
-    pub fn write_dimacs(&mut self, path: &Path) -> Result<(), Error> {
-        let path = dimacs_path(path)?;
-        let err = unsafe { ccadical_write_dimacs(self.ptr, path.as_ptr(), 0) };
-        if err.is_null() {
-            Ok(())
-        } else {
-            Err(dimacs_error(err))
-        }
+    /// Returns the number of bytes of native memory currently allocated by
+    /// CaDiCaL for this solver. This requires the `memory-accounting`
+    /// feature, and only counts allocations made through the C++ allocation
+    /// functions from within the methods of this solver.
+    #[cfg(feature = "memory-accounting")]
+    pub fn memory_usage(&self) -> u64 {
+        self.account.current()
     }
 
-    /// Reads a problem in DIMACS format from the given file. You must call
-    /// this function during configuration time, before adding any clauses.
-    /// Returns the number of variables as reported by the loader.
-    pub fn read_dimacs(&mut self, path: &Path) -> Result<i32, Error> {
-        if self.max_variable() != 0 {
-            return Err(Error::new("invalid state"));
-        }
-        let path = dimacs_path(path)?;
-        let mut vars: c_int = 0;
-        let err =
-            unsafe { ccadical_read_dimacs(self.ptr, path.as_ptr(), &mut vars as *mut c_int, 0) };
-        if err.is_null() {
-            Ok(vars)
-        } else {
-            Err(dimacs_error(err))
-        }
+    /// Returns the maximum number of bytes of native memory allocated by
+    /// CaDiCaL for this solver at any time. This requires the
+    /// `memory-accounting` feature.
+    #[cfg(feature = "memory-accounting")]
+    pub fn peak_memory_usage(&self) -> u64 {
+        self.account.peak()
     }
 
-    /// Increase the maximum variable index explicitly.
-    ///
-    /// This function makes sure that at least 'min_max_var' variables are initialized.
-    /// A call to this function sets the solver status to `None`.
-    pub fn reserve(&mut self, min_max_var: i32) {
-        unsafe { ccadical_reserve(self.ptr, min_max_var) }
+    /// Sets the maximum number of bytes of native memory the solver may use
+    /// (`None` for no limit). The limit is checked when CaDiCaL polls for
+    /// termination, and the solve call returns `None` once it is exceeded,
+    /// so the memory usage can briefly go above the limit. This requires the
+    /// `memory-accounting` feature.
+    /// # Examples
+    /// ```
+    /// let mut sat: cadical::Solver = Default::default();
+    /// sat.add_formula(&cadical::generators::pigeon_hole(9));
+    /// sat.set_memory_limit(Some(sat.memory_usage()));
+    /// assert_eq!(sat.solve(), None);
+    /// ```
+    #[cfg(feature = "memory-accounting")]
+    pub fn set_memory_limit(&mut self, limit: Option<u64>) {
+        self.memory_limit = limit;
     }
-}
 
-fn dimacs_path(path: &Path) -> Result<CString, Error> {
-    let path = path.to_str().ok_or_else(|| Error::new("invalid path"))?;
-    CString::new(path).map_err(|_| Error::new("invalid path"))
-}
+    /// Returns a handle for reading the conflicts, decisions, restarts and
+    /// native memory usage of this solver from other threads while it is
+    /// solving. The counters are updated during all later solve calls. This
+    /// is only available on targets with 64-bit atomics.
+    /// # Examples
+    /// ```
+    /// let mut sat: cadical::Solver = Default::default();
+    /// let stats = sat.live_stats();
+    /// let monitor = std::thread::spawn(move || stats.snapshot().conflicts);
+    /// sat.add_formula(&cadical::generators::pigeon_hole(5));
+    /// assert_eq!(sat.solve(), Some(false));
+    /// monitor.join().unwrap();
+    /// assert!(sat.live_stats().snapshot().conflicts > 0);
+    /// ```
+    #[cfg(target_has_atomic = "64")]
+    pub fn live_stats(&mut self) -> LiveStats {
+        self.live_stats.get_or_insert_with(LiveStats::new).clone()
+    }
 
-fn dimacs_error(err: *const c_char) -> Error {
-    let err = unsafe { CStr::from_ptr(err) };
-    Error::new(err.to_str().unwrap_or("invalid response"))
-}
+    /// Allocates a fresh variable for internal bookkeeping purposes, which
+    /// is above the given variable and is rejected in user clauses and
+    /// assumptions from now on.
+    fn new_selector(&mut self, above: i32) -> i32 {
+        if above > self.max_variable() {
+            self.reserve(above);
+        }
+        let act = self.new_variable();
+        self.selectors.push(act);
+        act
+    }
 
-impl<C: Callbacks> Default for Solver<C> {
-    fn default() -> Self {
+    /// Checks if the variable of the given literal was allocated by the
+    /// solver as the activation literal of a scope, a removable clause or
+    /// the temporary clauses of `solve_with_clauses`. These variables are
+    /// reserved, using them in clauses or assumptions panics.
+    /// # Examples
+    /// ```
+    /// let mut sat: cadical::Solver = Default::default();
+    /// sat.add_clause([1, 2]);
+    /// let handle = sat.add_removable_clause([-1]);
+    /// assert!(sat.is_selector(3));
+    /// sat.remove_clause(handle).unwrap();
+    /// assert_eq!(sat.solve(), Some(true));
+    /// let var = sat.new_variable();
+    /// assert!(!sat.is_selector(var));
+    /// sat.add_clause([var]);
+    /// assert_eq!(sat.solve(), Some(true));
+    /// ```
+    pub fn is_selector(&self, lit: i32) -> bool {
+        // selectors are allocated in increasing order
+        self.selectors.binary_search(&lit.wrapping_abs()).is_ok()
+    }
+
+    /// Panics if one of the literals is a reserved activation variable,
+    /// which would silently change the meaning of the formula.
+    #[inline]
+    fn check_selectors(&self, lits: &[i32]) {
+        if let Some(&first) = self.selectors.first() {
+            for &lit in lits.iter() {
+                if lit.wrapping_abs() >= first && self.is_selector(lit) {
+                    panic!("literal {} is a reserved activation variable", lit);
+                }
+            }
+        }
+    }
+
+    /// Returns a fresh variable, the next one after `max_variable`, and
+    /// makes sure that it is initialized.
+    /// # Panics
+    /// If `max_variable` is already `i32::MAX`, see `try_new_variable`.
+    pub fn new_variable(&mut self) -> i32 {
+        match self.try_new_variable() {
+            Ok(var) => var,
+            Err(err) => panic!("{}", err),
+        }
+    }
+
+    /// Returns a fresh variable like `new_variable`, or an error if the
+    /// variable indices are exhausted, which can happen with encodings of
+    /// huge instances that introduce many auxiliary variables.
+    /// # Examples
+    /// ```
+    /// let mut sat: cadical::Solver = Default::default();
+    /// assert_eq!(sat.try_new_variable().unwrap(), 1);
+    /// ```
+    pub fn try_new_variable(&mut self) -> Result<i32, Error> {
+        match self.max_variable().checked_add(1) {
+            Some(var) => {
+                self.reserve(var);
+                Ok(var)
+            }
+            None => Err(Error::new("variable index overflow")),
+        }
+    }
+
+    /// Solves the formula defined by the added clauses. If the formula is
+    /// satisfiable, then `Some(true)` is returned. If the formula is
+    /// unsatisfiable, then `Some(false)` is returned. If the solver runs out
+    /// of resources or was terminated, then `None` is returned.
+    pub fn solve(&mut self) -> Option<bool> {
+        self.run(None, None, None)
+    }
+
+    /// Runs the given number of preprocessing rounds (probing, variable
+    /// elimination, subsumption and so on) on the current formula without
+    /// searching for a model. Returns `Some(true)` or `Some(false)` if this
+    /// already decides the formula, and `None` otherwise. Like `solve`, this
+    /// respects the open scopes, the active clause groups and the sticky
+    /// assumptions.
+    pub fn simplify(&mut self, rounds: u32) -> Option<bool> {
+        let rounds = i32::try_from(rounds).unwrap_or(i32::MAX);
+        self.run(None, None, Some(rounds))
+    }
+
+    /// Runs the solver with the installed callbacks, and also terminates it
+    /// once the optional deadline has passed or the propagation counter has
+    /// reached the optional limit. With the number of simplification rounds
+    /// it only preprocesses the formula instead of searching for a model.
+    fn run(
+        &mut self,
+        deadline: Option<Instant>,
+        propagations: Option<i64>,
+        simplify: Option<i32>,
+    ) -> Option<bool> {
+        self.check_reentrancy(if simplify.is_some() {
+            "simplify"
+        } else {
+            "solve"
+        });
+        if self.out_of_memory {
+            return None;
+        }
+
+        // We need a cell (interior mutability) becase we need multiple pointers
+        // to the callback object, one stored here in the solver, the others in
+        // the CaDiCal library. Since it is put behind a cell we can access it
+        // mutabily when needed (from the same thread).
+        let mut data = CallbackData {
+            ptr: self.ptr,
+            cbs: Cell::new(None),
+            deadline,
+            propagations,
+            interrupt: self.interrupt.clone(),
+            max_length: 0,
+            max_glue: i32::MAX,
+            conflicts: false,
+            restarts: Cell::new(None),
+            restart_events: false,
+            reports: None,
+            account: &self.account,
+            memory_limit: self.memory_limit,
+            #[cfg(target_has_atomic = "64")]
+            stats: self
+                .live_stats
+                .as_ref()
+                .map_or(null_mut(), LiveStats::as_ptr),
+            #[cfg(not(target_has_atomic = "64"))]
+            stats: null_mut(),
+            panic: Cell::new(None),
+            #[cfg(feature = "debug-hooks")]
+            decisions: Cell::new(None),
+            #[cfg(feature = "smallvec")]
+            owned: false,
+            #[cfg(feature = "smallvec")]
+            pool: self.clause_pool.clone(),
+        };
+
+        let _account = self.account.enter();
+        self.retire_selectors();
+        self.assume_persistent();
+        let persistent: Vec<i32> = match self.dimacs_log {
+            Some(_) if simplify.is_none() => self.persistent_assumptions().collect(),
+            _ => Vec::new(),
+        };
+        if let Some(log) = self.dimacs_log.as_mut() {
+            if simplify.is_none() {
+                log.record_query(persistent);
+            } else {
+                log.clear_assumptions();
+            }
+        }
+
+        if let Some(limit) = self.propagation_limit.take() {
+            let limit = unsafe { ccadical_propagations(self.ptr) }.saturating_add(i64::from(limit));
+            data.propagations = Some(data.propagations.map_or(limit, |other| other.min(limit)));
+        }
+
+        // put the cbs pointer into the cell
+        if let Some(cbs) = self.cbs.as_mut() {
+            cbs.started();
+            data.max_length = cbs.max_length();
+            data.max_glue = cbs.max_glue();
+            data.conflicts = cbs.observe_conflicts();
+            #[cfg(feature = "smallvec")]
+            {
+                data.owned = cbs.learn_owned_clauses();
+            }
+        }
+        let ptr = &data as *const CallbackData<C> as *const c_void;
+        if let Some(cbs) = self.cbs.take() {
+            // conflicts are observed through all learned clauses, otherwise
+            // the clauses are filtered before leaving the native code
+            let (max_length, max_glue) = if data.conflicts {
+                (i32::MAX, i32::MAX)
+            } else {
+                (data.max_length, data.max_glue)
+            };
+            data.restart_events = cbs.observe_restarts();
+            if cbs.observe_reports() {
+                data.reports = Some(Instant::now());
+            }
+            if data.restart_events || data.reports.is_some() {
+                data.restarts
+                    .set(Some(unsafe { ccadical_restarts(self.ptr) }));
+            }
+            #[cfg(feature = "debug-hooks")]
+            {
+                let interval = cbs.decision_interval();
+                if interval != 0 {
+                    let decisions = unsafe { ccadical_decisions(self.ptr) };
+                    data.decisions.set(Some((decisions, interval)));
+                }
+            }
+
+            data.cbs.set(Some(cbs));
+            unsafe {
+                ccadical_set_terminate(self.ptr, ptr, Some(Self::terminate_cb));
+                ccadical_set_learn_length(
+                    self.ptr,
+                    ptr,
+                    max_length,
+                    max_glue,
+                    Some(Self::learn_cb),
+                );
+            }
+        } else if data.deadline.is_some()
+            || data.propagations.is_some()
+            || data.memory_limit.is_some()
+        {
+            unsafe { ccadical_set_terminate(self.ptr, ptr, Some(Self::terminate_cb)) };
+        } else if data.interrupt.is_some() || !data.stats.is_null() {
+            // AtomicBool has the same in-memory representation as a byte
+            let flag = data.interrupt.as_ref();
+            let flag = flag.map_or(null(), |flag| &**flag as *const AtomicBool as *const u8);
+            unsafe { ccadical_set_terminate_flag(self.ptr, flag, data.stats) };
+        }
+
+        #[cfg(feature = "tracing")]
+        let span = tracing::debug_span!(
+            "solve",
+            variables = self.max_variable(),
+            clauses = self.num_clauses()
+        )
+        .entered();
+
+        #[cfg(feature = "metrics")]
+        let before = self.metrics_snapshot();
+
+        let started = Instant::now();
+        self.in_solve.store(true, Ordering::SeqCst);
+        let ret = match simplify {
+            Some(rounds) => unsafe { ccadical_simplify(self.ptr, rounds) },
+            None => unsafe { ccadical_solve_checked(self.ptr) },
+        };
+        self.in_solve.store(false, Ordering::SeqCst);
+        self.allocated(ret);
+        self.solve_time = Some(started.elapsed());
+        #[cfg(feature = "diagnostics")]
+        self.update_diagnostics();
+
+        #[cfg(feature = "metrics")]
+        self.record_metrics(before, ret, started.elapsed());
+
+        #[cfg(feature = "tracing")]
+        {
+            tracing::debug!(
+                status = ret,
+                conflicts = unsafe { ccadical_conflicts(self.ptr) },
+                decisions = unsafe { ccadical_decisions(self.ptr) },
+                propagations = unsafe { ccadical_propagations(self.ptr) },
+                micros = started.elapsed().as_micros() as u64,
+                "solved"
+            );
+            drop(span);
+        }
+        self.limits.clear();
+        if let Some(flag) = &self.interrupt {
+            flag.store(false, Ordering::Relaxed);
+        }
+
+        // move the cbs pointer back
+        if let Some(cbs) = data.cbs.replace(None) {
+            self.cbs = Some(cbs);
+        }
+        if !data.stats.is_null() {
+            unsafe { ccadical_publish_stats(self.ptr, data.stats) };
+        }
+        unsafe {
+            ccadical_set_terminate_flag(self.ptr, null(), null_mut());
+            ccadical_set_terminate(self.ptr, null(), None);
+            ccadical_set_learn_length(self.ptr, null(), 0, 0, None);
+        }
+
+        // a panic of the callbacks stopped the search, continue unwinding
+        // now that no native frames are left
+        if let Some(payload) = data.panic.take() {
+            panic::resume_unwind(payload);
+        }
+
+        let confirmed = match self.unsat_check.as_ref() {
+            Some(check) if ret == 20 => self.check_unsat(check),
+            _ => true,
+        };
+        if let Some(check) = self.unsat_check.as_mut() {
+            check.set_constraint(None);
+        }
+        if let Some(log) = self.proof.as_mut() {
+            log.solved();
+        }
+        #[cfg(feature = "tracing")]
+        if !confirmed {
+            tracing::warn!("unsat check failed");
+        }
+
+        if ret == 10 {
+            Some(true)
+        } else if ret == 20 && confirmed {
+            Some(false)
+        } else {
+            None
+        }
+    }
+
+    /// Solves the formula defined by the set of clauses under the given
+    /// assumptions.
+    pub fn solve_with<I>(&mut self, assumptions: I) -> Option<bool>
+    where
+        I: IntoIterator<Item = i32>,
+    {
+        self.check_reentrancy("solve");
+        // the assumptions are collected in the clause buffer
+        self.clause.clear();
+        self.clause.extend(assumptions);
+        debug_assert!(self.clause.iter().all(|&lit| lit != 0 && lit != i32::MIN));
+        self.check_selectors(&self.clause);
+        if let Some(log) = self.dimacs_log.as_mut() {
+            log.assume(&self.clause);
+        }
+        let ret = unsafe { ccadical_assume_all(self.ptr, self.clause.as_ptr(), self.clause.len()) };
+        self.allocated(ret);
+        self.solve()
+    }
+
+    /// Solves the formula extended with the given temporary clauses under the
+    /// given assumptions. The temporary clauses are guarded by a fresh
+    /// activation literal that is assumed for this call only, and they are
+    /// permanently disabled before the next `solve` call. The activation
+    /// literal is the next variable after the ones in the formula and in
+    /// the clauses, and it is reserved, so variables used later must be
+    /// allocated with `new_variable`, see `is_selector`.
+    /// # Examples
+    /// ```
+    /// let mut sat: cadical::Solver = Default::default();
+    /// sat.add_clause([1, 2]);
+    /// let x = sat.new_variable();
+    /// assert_eq!(sat.solve_with_clauses([], [[-1], [-2]]), Some(false));
+    /// assert_eq!(sat.solve_with_clauses([-1], [[1, -2]]), Some(false));
+    /// assert_eq!(sat.solve_with_clauses([-1], [[-2, x]]), Some(true));
+    /// assert_eq!(sat.value(x), Some(true));
+    /// assert_eq!(sat.solve(), Some(true));
+    /// ```
+    pub fn solve_with_clauses<I, J, K>(&mut self, assumptions: I, clauses: J) -> Option<bool>
+    where
+        I: IntoIterator<Item = i32>,
+        J: IntoIterator<Item = K>,
+        K: IntoIterator<Item = i32>,
+    {
+        self.check_reentrancy("solve");
+        self.retire_selectors();
+        let clauses: Vec<Vec<i32>> = clauses
+            .into_iter()
+            .map(|clause| clause.into_iter().collect())
+            .collect();
+        for clause in clauses.iter() {
+            self.check_selectors(clause);
+        }
+        let above = clauses.iter().map(|clause| max_var(clause)).max();
+        let act = self.new_selector(above.unwrap_or(0));
+        for clause in clauses {
+            self.clause.clear();
+            self.clause.extend(clause);
+            self.clause.push(-act);
+            self.add_clause_buffer();
+        }
+        if let Some(log) = self.dimacs_log.as_mut() {
+            log.assume(&[act]);
+        }
+        unsafe { ccadical_assume(self.ptr, act) };
+        let result = self.solve_with(assumptions);
+        self.retired.push(act);
+        result
+    }
+
+    /// Solves the formula under the given resource budget, and returns the
+    /// result together with the effort spent on this call. The result is
+    /// `None` if the budget was exhausted or the installed callbacks
+    /// terminated the solver.
+    /// # Examples
+    /// ```
+    /// let mut sat: cadical::Solver = Default::default();
+    /// sat.add_clause([1, 2]);
+    /// let budget = cadical::Budget {
+    ///     conflicts: Some(1000),
+    ///     ..Default::default()
+    /// };
+    /// let (result, effort) = sat.solve_limited(&budget);
+    /// assert_eq!(result, Some(true));
+    /// assert!(effort.conflicts <= 1000);
+    /// ```
+    pub fn solve_limited(&mut self, budget: &Budget) -> (Option<bool>, EffortStats) {
+        // the native limits are 32-bit, larger ones are effectively unlimited
+        if let Some(conflicts) = budget.conflicts {
+            let conflicts = narrow(conflicts).unwrap_or(i32::MAX);
+            self.set_limit("conflicts", conflicts).unwrap();
+        }
+        if let Some(decisions) = budget.decisions {
+            let decisions = narrow(decisions).unwrap_or(i32::MAX);
+            self.set_limit("decisions", decisions).unwrap();
+        }
+        let conflicts = unsafe { ccadical_conflicts(self.ptr) };
+        let decisions = unsafe { ccadical_decisions(self.ptr) };
+        let propagations = unsafe { ccadical_propagations(self.ptr) };
+        let started = Instant::now();
+        let deadline = budget.time.filter(|_| self.deterministic.is_none());
+        let result = self.run(
+            deadline.map(|time| started + time),
+            budget
+                .propagations
+                .map(|limit| propagations.saturating_add(i64::try_from(limit).unwrap_or(i64::MAX))),
+            None,
+        );
+        let effort = EffortStats {
+            conflicts: (unsafe { ccadical_conflicts(self.ptr) } - conflicts) as u64,
+            decisions: (unsafe { ccadical_decisions(self.ptr) } - decisions) as u64,
+            propagations: (unsafe { ccadical_propagations(self.ptr) } - propagations) as u64,
+            time: started.elapsed(),
+        };
+        (result, effort)
+    }
+
+    /// Solves the formula under the given resource budget. This is the same
+    /// as `solve_limited` without the effort statistics, and it replaces the
+    /// setting of the individual limits with `set_limit` before `solve`.
+    /// # Examples
+    /// ```
+    /// use std::time::Duration;
+    /// let mut sat: cadical::Solver = Default::default();
+    /// sat.add_clause([1, 2]);
+    /// let budget = cadical::Budget {
+    ///     propagations: Some(1_000_000),
+    ///     time: Some(Duration::from_secs(10)),
+    ///     ..Default::default()
+    /// };
+    /// assert_eq!(sat.solve_budgeted(&budget), Some(true));
+    /// ```
+    pub fn solve_budgeted(&mut self, budget: &Budget) -> Option<bool> {
+        self.solve_limited(budget).0
+    }
+
+    /// Returns the wall clock time spent in the native solver during the last
+    /// `solve` call (of any variant), or `None` if the solver has not been
+    /// run since its construction or the last `reset`.
+    /// # Examples
+    /// ```
+    /// let mut sat: cadical::Solver = Default::default();
+    /// assert_eq!(sat.last_solve_time(), None);
+    /// sat.add_clause([1, 2]);
+    /// sat.solve();
+    /// assert!(sat.last_solve_time().is_some());
+    /// ```
+    #[inline]
+    pub fn last_solve_time(&self) -> Option<Duration> {
+        self.solve_time
+    }
+
+    /// Returns `true` while the native solver is running a `solve` or
+    /// `simplify` call, which can only be observed through raw pointers,
+    /// for example from the callbacks of the IPASIR interface.
+    #[inline]
+    pub fn is_solving(&self) -> bool {
+        self.in_solve.load(Ordering::SeqCst)
+    }
+
+    /// Panics if the solver is reentered from a callback during solving,
+    /// which would corrupt it.
+    #[inline]
+    fn check_reentrancy(&self, call: &str) {
+        if self.is_solving() {
+            panic!("Solver::{} called from a callback during solving", call);
+        }
+    }
+
+    /// Adds an assumption for the next `solve` or `solve_with` call. The
+    /// assumptions are cleared after solving, or by `clear_assumptions`.
+    #[inline]
+    pub fn assume(&mut self, lit: i32) {
+        self.check_reentrancy("assume");
+        debug_assert!(lit != 0 && lit != i32::MIN);
+        self.check_selectors(&[lit]);
+        if let Some(log) = self.dimacs_log.as_mut() {
+            log.assume(&[lit]);
+        }
+        unsafe { ccadical_assume(self.ptr, lit) };
+    }
+
+    /// Adds all literals as assumptions for the next `solve` or `solve_with`
+    /// call with a single call into CaDiCaL, which is faster than calling
+    /// `assume` for each literal when there are many assumptions.
+    /// # Examples
+    /// ```
+    /// let mut sat: cadical::Solver = Default::default();
+    /// sat.add_clause([1, 2, 3]);
+    /// sat.assume_all(&[-1, -2, -3]);
+    /// assert_eq!(sat.solve(), Some(false));
+    /// assert_eq!(sat.solve(), Some(true));
+    /// ```
+    pub fn assume_all(&mut self, lits: &[i32]) {
+        self.check_reentrancy("assume");
+        debug_assert!(lits.iter().all(|&lit| lit != 0 && lit != i32::MIN));
+        self.check_selectors(lits);
+        if let Some(log) = self.dimacs_log.as_mut() {
+            log.assume(lits);
+        }
+        let ret = unsafe { ccadical_assume_all(self.ptr, lits.as_ptr(), lits.len()) };
+        self.allocated(ret);
+    }
+
+    /// Removes the assumptions added by `assume` (or left over by an aborted
+    /// query) without solving. Sticky assumptions and assumption groups are
+    /// not affected.
+    pub fn clear_assumptions(&mut self) {
+        self.check_reentrancy("clear_assumptions");
+        if let Some(log) = self.dimacs_log.as_mut() {
+            log.clear_assumptions();
+        }
+        unsafe { ccadical_reset_assumptions(self.ptr) };
+    }
+
+    /// Sets the constraint clause for the next `solve` or `solve_with` call,
+    /// which must then satisfy at least one of its literals, just like an
+    /// assumption but for a disjunction of literals. The constraint is
+    /// cleared after solving, or by `clear_constraint`.
+    /// # Examples
+    /// ```
+    /// let mut sat: cadical::Solver = Default::default();
+    /// sat.add_clause([-1, -2]);
+    /// sat.constrain([1, 2]);
+    /// assert_eq!(sat.solve_with([-1]), Some(true));
+    /// assert_eq!(sat.value(2), Some(true));
+    /// sat.constrain([1]);
+    /// assert_eq!(sat.solve_with([2]), Some(false));
+    /// assert!(sat.constraint_failed());
+    /// ```
+    pub fn constrain<I>(&mut self, clause: I)
+    where
+        I: IntoIterator<Item = i32>,
+    {
+        self.check_reentrancy("constrain");
+        let clause: Vec<i32> = clause.into_iter().collect();
+        self.check_selectors(&clause);
+        for &lit in clause.iter() {
+            debug_assert!(lit != 0 && lit != i32::MIN);
+            unsafe { ccadical_constrain(self.ptr, lit) };
+        }
+        unsafe { ccadical_constrain(self.ptr, 0) };
+        if let Some(log) = self.proof.as_mut() {
+            log.set_constraint(Some(clause.clone()));
+        }
+        if let Some(check) = self.unsat_check.as_mut() {
+            check.set_constraint(Some(clause));
+        }
+    }
+
+    /// Removes the constraint clause set by `constrain` without solving.
+    pub fn clear_constraint(&mut self) {
+        self.check_reentrancy("clear_constraint");
+        unsafe { ccadical_reset_constraint(self.ptr) };
+        if let Some(check) = self.unsat_check.as_mut() {
+            check.set_constraint(None);
+        }
+        if let Some(log) = self.proof.as_mut() {
+            log.set_constraint(None);
+        }
+    }
+
+    /// Checks if the constraint clause was used in the proof of the
+    /// unsatisfiability of the formula. The state of the solver must be
+    /// `Some(false)`, which is checked only in debug builds, see
+    /// `try_constraint_failed` for a checked variant.
+    #[inline]
+    pub fn constraint_failed(&self) -> bool {
+        debug_assert!(self.status() == Some(false));
+        unsafe { ccadical_constraint_failed(self.ptr) != 0 }
+    }
+
+    /// Checks if the constraint clause was used in the proof of the
+    /// unsatisfiability of the formula, or returns an error if the state of
+    /// the solver is not `Some(false)`.
+    pub fn try_constraint_failed(&self) -> Result<bool, Error> {
+        if self.status() != Some(false) {
+            return Err(Error::new("invalid state"));
+        }
+        Ok(self.constraint_failed())
+    }
+
+    /// Returns the status of the solver as returned by the last call to
+    /// `solve` or `solve_with`. The state becomes `None` if a new clause
+    /// is added.
+    #[inline]
+    pub fn status(&self) -> Option<bool> {
+        let r = unsafe { ccadical_status(self.ptr) };
+        if r == 10 {
+            Some(true)
+        } else if r == 20 {
+            Some(false)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the current state of the internal state machine of the
+    /// solver, which is finer than `status`. Options can only be set and
+    /// DIMACS files can only be read in the `Configuring` state.
+    /// # Examples
+    /// ```
+    /// use cadical::State;
+    /// let mut sat: cadical::Solver = Default::default();
+    /// assert_eq!(sat.state(), State::Configuring);
+    /// sat.add_clause([1, 2]);
+    /// assert_eq!(sat.state(), State::Steady);
+    /// sat.solve();
+    /// assert_eq!(sat.state(), State::Satisfied);
+    /// ```
+    pub fn state(&self) -> State {
+        match unsafe { ccadical_state(self.ptr) } {
+            1 => State::Initializing,
+            2 => State::Configuring,
+            4 => State::Steady,
+            8 => State::Adding,
+            16 => State::Solving,
+            32 => State::Satisfied,
+            64 => State::Unsatisfied,
+            _ => State::Deleting,
+        }
+    }
+
+    /// Checks if the formula is already known to be unsatisfiable at the root
+    /// level, for example because the empty clause was added or derived by
+    /// an earlier `solve` call. This is a cheap check that does not search,
+    /// so `false` does not imply that the formula is satisfiable.
+    /// # Examples
+    /// ```
+    /// let mut sat: cadical::Solver = Default::default();
+    /// sat.add_clause([1, 2]);
+    /// assert!(!sat.is_inconsistent());
+    /// sat.add_clause([]);
+    /// assert!(sat.is_inconsistent());
+    /// ```
+    #[inline]
+    pub fn is_inconsistent(&self) -> bool {
+        unsafe { ccadical_is_inconsistent(self.ptr) != 0 }
+    }
+
+    /// Returns the value of the given literal in the last solution. The
+    /// state of the solver must be `Some(true)`. The returned value is
+    /// `None` if the formula is satisfied regardless of the value of the
+    /// literal. The state and the literal are checked only in debug builds,
+    /// see `try_value` for a checked variant.
+    #[inline]
+    pub fn value(&self, lit: i32) -> Option<bool> {
+        debug_assert!(self.status() == Some(true));
+        debug_assert!(lit != 0 && lit != i32::MIN);
+        let val = unsafe { ccadical_val(self.ptr, lit) };
+        if val == lit.abs() {
+            Some(true)
+        } else if val == -lit.abs() {
+            Some(false)
+        } else {
+            None
+        }
+    }
+
+    /// Checks if the given assumed literal (passed to `solve_with`) was used
+    /// in the proof of the unsatisfiability of the formula. The state of the
+    /// solver must be `Some(false)`. The state and the literal are checked
+    /// only in debug builds, see `try_failed` for a checked variant.
+    #[inline]
+    pub fn failed(&self, lit: i32) -> bool {
+        debug_assert!(self.status() == Some(false));
+        debug_assert!(lit != 0 && lit != i32::MIN);
+        let val = unsafe { ccadical_failed(self.ptr, lit) };
+        val == 1
+    }
+
+    /// Returns the value of the given literal in the last solution like
+    /// `value`, or an error if the state of the solver is not `Some(true)`
+    /// or the literal is invalid.
+    /// # Examples
+    /// ```
+    /// let mut sat: cadical::Solver = Default::default();
+    /// sat.add_clause([1]);
+    /// assert!(sat.try_value(1).is_err());
+    /// assert_eq!(sat.solve(), Some(true));
+    /// assert_eq!(sat.try_value(1).unwrap(), Some(true));
+    /// assert!(sat.try_value(0).is_err());
+    /// ```
+    pub fn try_value(&self, lit: i32) -> Result<Option<bool>, Error> {
+        if self.status() != Some(true) {
+            Err(Error::new("invalid state"))
+        } else if lit == 0 || lit == i32::MIN {
+            Err(Error::new("invalid literal"))
+        } else {
+            Ok(self.value(lit))
+        }
+    }
+
+    /// Checks if the given assumed literal was used in the proof of the
+    /// unsatisfiability of the formula like `failed`, or returns an error if
+    /// the state of the solver is not `Some(false)` or the literal is
+    /// invalid.
+    pub fn try_failed(&self, lit: i32) -> Result<bool, Error> {
+        if self.status() != Some(false) {
+            Err(Error::new("invalid state"))
+        } else if lit == 0 || lit == i32::MIN {
+            Err(Error::new("invalid literal"))
+        } else {
+            Ok(self.failed(lit))
+        }
+    }
+
+    /// Shrinks a set of failed assumptions (typically the ones for which
+    /// `failed` returned `true`) by iteratively trying to drop one literal at
+    /// a time and re-solving with the remaining ones. Each of these calls is
+    /// limited to `budget` conflicts, a negative value disables the limit. A
+    /// literal is kept if the formula becomes satisfiable without it or the
+    /// budget is exhausted, so the returned core is only guaranteed to be
+    /// minimal if no budget limit was hit. The state of the solver afterwards
+    /// reflects the last re-solve call.
+    /// # Examples
+    /// ```
+    /// let mut sat: cadical::Solver = Default::default();
+    /// sat.add_clause([-1, -2]);
+    /// assert_eq!(sat.shrink_core(&[1, 2, 3], 100), vec![1, 2]);
+    /// ```
+    pub fn shrink_core(&mut self, core: &[i32], budget: i32) -> Vec<i32> {
+        let limit = CString::new("conflicts").unwrap();
+        let mut core = core.to_vec();
+        let mut pos = 0;
+        while pos < core.len() {
+            let mut candidate = core.clone();
+            candidate.remove(pos);
+            unsafe { ccadical_limit2(self.ptr, limit.as_ptr(), budget) };
+            if self.solve_with(candidate.iter().copied()) == Some(false) {
+                // the new core can be even smaller than the candidate
+                core = candidate
+                    .into_iter()
+                    .filter(|&lit| self.failed(lit))
+                    .collect();
+            } else {
+                pos += 1;
+            }
+        }
+        core
+    }
+
+    /// Returns the maximum variable index in the problem as maintained by
+    /// the solver.
+    /// # Examples
+    /// ```
+    /// let mut sat: cadical::Solver = Default::default();
+    /// sat.add_clause([1, -3]);
+    /// assert_eq!(sat.max_variable(), 3);
+    /// assert_eq!(sat.num_variables(), 2);
+    /// assert_eq!(sat.num_clauses(), 1);
+    /// ```
+    #[inline]
+    pub fn max_variable(&self) -> i32 {
+        unsafe { ccadical_vars(self.ptr) }
+    }
+
+    /// Returns the number of active variables in the problem. Variables become
+    /// active if a clause is added with it. They become inactive, if they
+    /// are eliminated or become fixed at the root level.
+    #[inline]
+    pub fn num_variables(&self) -> i32 {
+        // cannot fail, there are at most `max_variable` active variables
+        narrow(self.num_variables_u64()).expect("variable count overflow")
+    }
+
+    /// Returns the number of active variables as an `u64`.
+    #[inline]
+    pub fn num_variables_u64(&self) -> u64 {
+        unsafe { ccadical_active(self.ptr) as u64 }
+    }
+
+    /// Returns the number of active irredundant clauses. Clauses become
+    /// inactive if they are satisfied, subsumed or eliminated.
+    /// # Panics
+    /// If the count does not fit into `usize` on 32-bit targets, use
+    /// `num_clauses_u64` for huge instances there.
+    #[inline]
+    pub fn num_clauses(&self) -> usize {
+        self.try_num_clauses().expect("clause count overflow")
+    }
+
+    /// Returns the number of active irredundant clauses, or an error if it
+    /// does not fit into `usize`.
+    #[inline]
+    pub fn try_num_clauses(&self) -> Result<usize, Error> {
+        narrow(self.num_clauses_u64())
+    }
+
+    /// Returns the number of active irredundant clauses as an `u64`.
+    #[inline]
+    pub fn num_clauses_u64(&self) -> u64 {
+        unsafe { ccadical_irredundant(self.ptr) as u64 }
+    }
+
+    /// Sets a solver limit with the corresponding name to the given value.
+    /// These limits are only valid for the next `solve` or `solve_with` call
+    /// and reset to their default values, which disables them.
+    /// The following limits are supported:
+    /// * `preprocessing`: the number of preprocessing rounds to be performed
+    ///   during the search (defaults to `0`).
+    /// * `localsearch`: the number of local search rounds to be performed
+    ///   during the search (defaults to `0`).
+    /// * `terminate`: this value is regularly decremented and aborts the
+    ///   solver when it reaches zero (defaults to `0`).
+    /// * `conflicts`: decremented when a conflict is detected
+    ///   and aborts the solver when it becomes negative (defaults to `-1`).
+    /// * `decisions`: decremented when a decision is made
+    ///   and aborts the solver when it becomes negative (defaults to `-1`).
+    /// * `propagations`: the maximum number of literals propagated during
+    ///   search, checked when the solver polls for termination, where
+    ///   negative values disable it (defaults to `-1`).
+    pub fn set_limit(&mut self, name: &str, limit: i32) -> Result<(), Error> {
+        if name == "propagations" {
+            self.propagation_limit = if limit >= 0 { Some(limit) } else { None };
+            self.limits.insert(name.to_string(), limit);
+            return Ok(());
+        }
+        let key = CString::new(name).map_err(|_| Error::new("invalid string"))?;
+        let valid = unsafe { ccadical_limit2(self.ptr, key.as_ptr(), limit) };
+        if valid != 0 {
+            self.limits.insert(name.to_string(), limit);
+            Ok(())
+        } else {
+            Err(Error::new("unknown limit"))
+        }
+    }
+
+    /// Sets the internal option with the given name to the given value.
+    /// Options can only be set right after the construction of the solver,
+    /// before any clauses are added. An unknown name and a value outside of
+    /// the range of the option are reported as different errors, the range
+    /// can also be queried with `option_range`.
+    /// # Examples
+    /// ```
+    /// use cadical::OptionError;
+    /// let mut sat: cadical::Solver = Default::default();
+    /// assert_eq!(sat.set_option("phase", 0), Ok(()));
+    /// assert_eq!(sat.set_option("phas", 0), Err(OptionError::UnknownOption));
+    /// assert_eq!(
+    ///     sat.set_option("phase", 2),
+    ///     Err(OptionError::ValueOutOfRange { min: 0, max: 1 })
+    /// );
+    /// ```
+    pub fn set_option(&mut self, name: &str, value: i32) -> Result<(), OptionError> {
+        if self.state() != State::Configuring {
+            return Err(OptionError::InvalidState);
+        }
+        let key = CString::new(name).map_err(|_| OptionError::InvalidString)?;
+        let (min, max) = self.option_range(name).ok_or(OptionError::UnknownOption)?;
+        if value < min || value > max {
+            return Err(OptionError::ValueOutOfRange { min, max });
+        }
+        let valid = unsafe { ccadical_set_option2(self.ptr, key.as_ptr(), value) };
+        if valid != 0 {
+            let value = unsafe { ccadical_get_option(self.ptr, key.as_ptr()) };
+            self.options.insert(name.to_string(), value);
+            Ok(())
+        } else {
+            Err(OptionError::UnknownOption)
+        }
+    }
+
+    /// Returns the current value of the internal option with the given name,
+    /// or zero if there is no such option.
+    pub fn get_option(&self, name: &str) -> i32 {
+        match CString::new(name) {
+            Ok(name) => unsafe { ccadical_get_option(self.ptr, name.as_ptr()) },
+            Err(_) => 0,
+        }
+    }
+
+    /// Sets the seed of the internal random number generator of the solver.
+    /// The seed is reduced modulo the range of the `seed` option.
+    pub fn set_seed(&mut self, seed: u64) -> Result<(), Error> {
+        Ok(self.set_option("seed", (seed % 2_000_000_001) as i32)?)
+    }
+
+    /// Sets the random seed and enables the random shuffling of the decision
+    /// queue and the variable scores. Solvers that are shuffled with
+    /// different seeds explore the search space differently, which is useful
+    /// for portfolios, while the same seed reproduces the same run.
+    /// # Examples
+    /// ```
+    /// let mut sat: cadical::Solver = Default::default();
+    /// sat.shuffle(42).unwrap();
+    /// assert_eq!(sat.get_option("shuffle"), 1);
+    /// assert_eq!(sat.get_option("seed"), 42);
+    /// ```
+    pub fn shuffle(&mut self, seed: u64) -> Result<(), Error> {
+        self.set_seed(seed)?;
+        self.set_option("shuffle", 1)?;
+        self.set_option("shufflequeue", 1)?;
+        self.set_option("shufflescores", 1)?;
+        Ok(self.set_option("shufflerandom", 1)?)
+    }
+
+    /// Enables or disables the deterministic mode, where repeated runs on the
+    /// same input produce identical results and statistics. The search of
+    /// CaDiCaL does not depend on the clock, so enabling this pins the random
+    /// seed to zero (which requires the same state as `set_option`) and makes
+    /// `solve_limited` ignore the wall clock limit of its budget. Disabling it
+    /// restores the previous seed, which again requires that state if the
+    /// previous seed was not zero. Installed callbacks, like `Timeout`, can
+    /// still make the runs differ.
+    /// # Examples
+    /// ```
+    /// let mut sat: cadical::Solver = Default::default();
+    /// sat.set_seed(42).unwrap();
+    /// sat.set_deterministic(true).unwrap();
+    /// assert!(sat.is_deterministic());
+    /// assert_eq!(sat.get_option("seed"), 0);
+    /// sat.set_deterministic(false).unwrap();
+    /// assert_eq!(sat.get_option("seed"), 42);
+    /// ```
+    pub fn set_deterministic(&mut self, enable: bool) -> Result<(), Error> {
+        match (enable, self.deterministic) {
+            (true, None) => {
+                let seed = self.get_option("seed");
+                self.set_option("seed", 0)?;
+                self.deterministic = Some(seed);
+            }
+            (false, Some(seed)) => {
+                if self.get_option("seed") != seed {
+                    self.set_option("seed", seed)?;
+                }
+                self.deterministic = None;
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Returns whether the deterministic mode is enabled.
+    #[inline]
+    pub fn is_deterministic(&self) -> bool {
+        self.deterministic.is_some()
+    }
+
+    /// Sets the callbacks to be called while the solver is running.
+    /// # Examples
+    /// ```
+    /// let mut sat: cadical::Solver = Default::default();
+    /// sat.add_clause([1, 2]);
+    /// sat.set_callbacks(Some(cadical::Timeout::new(0.0)));
+    /// assert_eq!(sat.solve(), None);
+    /// ```
+    pub fn set_callbacks(&mut self, cbs: Option<C>) {
+        if let Some(cbs) = cbs {
+            self.cbs = Some(Box::new(cbs));
+        } else {
+            self.cbs = None;
+        }
+    }
+
+    extern "C" fn terminate_cb(data: *const c_void) -> c_int {
+        debug_assert!(!data.is_null());
+        let data = unsafe { &*(data as *const CallbackData<C>) };
+
+        if let Some(payload) = data.panic.take() {
+            data.panic.set(Some(payload));
+            return terminated("panic");
+        }
+        if let Some(deadline) = data.deadline {
+            if Instant::now() >= deadline {
+                return terminated("deadline");
+            }
+        }
+        if let Some(limit) = data.propagations {
+            if unsafe { ccadical_propagations(data.ptr) } >= limit {
+                return terminated("propagations");
+            }
+        }
+        if let Some(flag) = &data.interrupt {
+            if flag.load(Ordering::Relaxed) {
+                return terminated("interrupt");
+            }
+        }
+        if !data.stats.is_null() {
+            unsafe { ccadical_publish_stats(data.ptr, data.stats) };
+        }
+        if unsafe { &*data.account }.exceeds(data.memory_limit) {
+            return terminated("memory");
+        }
+        if let Some(mut cbs) = data.cbs.replace(None) {
+            // panics must not unwind through the native frames
+            let ret = panic::catch_unwind(AssertUnwindSafe(|| {
+                if let Some(last) = data.restarts.get() {
+                    let restarts = unsafe { ccadical_restarts(data.ptr) };
+                    if restarts != last {
+                        data.restarts.set(Some(restarts));
+                        if data.restart_events {
+                            cbs.restart(restarts as u64, unsafe { ccadical_level(data.ptr) });
+                        }
+                        if let Some(started) = data.reports {
+                            cbs.report(&ReportEvent::read(data.ptr, started));
+                        }
+                    }
+                }
+                #[cfg(feature = "debug-hooks")]
+                if let Some((last, interval)) = data.decisions.get() {
+                    let decisions = unsafe { ccadical_decisions(data.ptr) };
+                    if (decisions - last) as u64 >= interval {
+                        data.decisions.set(Some((decisions, interval)));
+                        let lit = unsafe { ccadical_decision(data.ptr) };
+                        if lit != 0 {
+                            cbs.decision(lit, unsafe { ccadical_level(data.ptr) });
+                        }
+                    }
+                }
+                cbs.terminate()
+            }));
+            data.cbs.set(Some(cbs));
+            match ret {
+                Ok(true) => terminated("callbacks"),
+                Ok(false) => 0,
+                Err(payload) => {
+                    data.panic.set(Some(payload));
+                    terminated("panic")
+                }
+            }
+        } else {
+            0
+        }
+    }
+
+    extern "C" fn learn_cb(data: *const c_void, clause: *const c_int, len: usize) {
+        let clause = if len == 0 {
+            &[]
+        } else {
+            debug_assert!(!clause.is_null());
+            unsafe { slice::from_raw_parts(clause, len) }
+        };
+
+        debug_assert!(!data.is_null());
+        let data = unsafe { &*(data as *const CallbackData<C>) };
+
+        if let Some(mut cbs) = data.cbs.replace(None) {
+            // after a panic the callbacks are not called until the search
+            // stops at the next termination check
+            let panicked = data.panic.take();
+            let ret = if panicked.is_some() {
+                Ok(())
+            } else {
+                panic::catch_unwind(AssertUnwindSafe(|| {
+                    let mut glue = 0;
+                    if data.conflicts {
+                        let mut jump: c_int = 0;
+                        glue = unsafe {
+                            ccadical_learned_levels(
+                                data.ptr,
+                                clause.as_ptr(),
+                                clause.len(),
+                                &mut jump,
+                            )
+                        };
+                        cbs.conflict(clause.len(), glue, jump);
+                    }
+                    if clause.len() <= data.max_length.max(0) as usize && glue <= data.max_glue {
+                        #[cfg(feature = "tracing")]
+                        tracing::trace!(size = clause.len(), "learned");
+                        #[cfg(feature = "smallvec")]
+                        if data.owned {
+                            cbs.learn_owned(data.pool.take(clause));
+                            return;
+                        }
+                        cbs.learn(clause);
+                    }
+                }))
+            };
+            data.cbs.set(Some(cbs));
+            data.panic.set(ret.err().or(panicked));
+        }
+    }
+
+    /// Returns a mutable reference to the callbacks.
+    pub fn get_callbacks(&mut self) -> Option<&mut C> {
+        self.cbs.as_deref_mut()
+    }
+
+    /// Writes the problem in DIMACS format to the given file.
+    pub fn write_dimacs(&mut self, path: &Path) -> Result<(), Error> {
+        let path = dimacs_path(path)?;
+        let err = unsafe { ccadical_write_dimacs(self.ptr, path.as_ptr(), 0) };
+        if err.is_null() {
+            Ok(())
+        } else {
+            Err(dimacs_error(err))
+        }
+    }
+
+    /// Reads a problem in DIMACS format from the given file. You must call
+    /// this function during configuration time, before adding any clauses.
+    /// Returns the number of variables as reported by the loader.
+    pub fn read_dimacs(&mut self, path: &Path) -> Result<i32, Error> {
+        if self.state() != State::Configuring {
+            return Err(Error::new("invalid state"));
+        }
+        if self.unsat_check.is_some() {
+            return Err(Error::new("unsupported with unsat check"));
+        }
+        if self.proof.is_some() {
+            return Err(Error::new("unsupported with proof logging"));
+        }
+        #[cfg(feature = "tracing")]
+        let _span = tracing::debug_span!("read_dimacs", path = %path.display()).entered();
+        let path = dimacs_path(path)?;
+        let mut vars: c_int = 0;
+        let _account = self.account.enter();
+        let err =
+            unsafe { ccadical_read_dimacs(self.ptr, path.as_ptr(), &mut vars as *mut c_int, 0) };
+        if err.is_null() {
+            #[cfg(feature = "tracing")]
+            tracing::debug!(variables = vars, clauses = self.num_clauses(), "parsed");
+            Ok(vars)
+        } else {
+            let err = dimacs_error(err);
+            self.out_of_memory |= err.msg == "out of memory";
+            Err(err)
+        }
+    }
+
+    /// Raises the decision priority of the given variables by bumping them
+    /// both in the decision queue and in the variable score heap, as if they
+    /// had occurred in a learned clause. The first variable gets the highest
+    /// priority. The effect wears off as the search progresses. Variables
+    /// above `max_variable` and those that are already fixed or removed by
+    /// preprocessing are skipped.
+    /// # Panics
+    /// If one of the variables is not positive.
+    pub fn prioritize<I>(&mut self, vars: I)
+    where
+        I: IntoIterator<Item = i32>,
+    {
+        self.check_reentrancy("prioritize");
+        let vars: Vec<i32> = vars.into_iter().collect();
+        let max_var = self.max_variable();
+        for &var in vars.iter().rev() {
+            assert!(var > 0, "invalid variable {}", var);
+            if var <= max_var {
+                unsafe { ccadical_prioritize(self.ptr, var) };
+            }
+        }
+    }
+
+    /// Freezes the variable of the given literal, so it is not eliminated
+    /// by preprocessing and its value is kept by `simplified_formula`.
+    /// Freezing is counted, the variable stays frozen until it is melted as
+    /// many times as it was frozen.
+    /// # Examples
+    /// ```
+    /// let mut sat: cadical::Solver = Default::default();
+    /// sat.freeze(3);
+    /// assert!(sat.frozen(-3));
+    /// sat.melt(3).unwrap();
+    /// assert!(!sat.frozen(3));
+    /// assert!(sat.melt(3).is_err());
+    /// ```
+    pub fn freeze(&mut self, lit: i32) {
+        self.check_reentrancy("freeze");
+        debug_assert!(lit != 0 && lit != i32::MIN);
+        unsafe { ccadical_freeze(self.ptr, lit) };
+    }
+
+    /// Melts the variable of the given literal, undoing one `freeze` call.
+    /// Returns an error if the variable is not frozen.
+    pub fn melt(&mut self, lit: i32) -> Result<(), Error> {
+        if !self.frozen(lit) {
+            return Err(Error::new("not frozen"));
+        }
+        unsafe { ccadical_melt(self.ptr, lit) };
+        Ok(())
+    }
+
+    /// Checks if the variable of the given literal is frozen.
+    pub fn frozen(&self, lit: i32) -> bool {
+        debug_assert!(lit != 0 && lit != i32::MIN);
+        unsafe { ccadical_frozen(self.ptr, lit) != 0 }
+    }
+
+    /// Calls the given function with each irredundant clause of the current
+    /// formula, until it returns `false`. The clauses are reported as seen
+    /// by the solver, so they might be simplified, and fixed literals are
+    /// only reported as unit clauses for frozen variables. Returns `false`
+    /// if the traversal was stopped by the function.
+    /// # Examples
+    /// ```
+    /// let mut sat: cadical::Solver = Default::default();
+    /// sat.add_clause([1, 2]);
+    /// let mut clauses = Vec::new();
+    /// sat.traverse_clauses(|clause| {
+    ///     clauses.push(clause.to_vec());
+    ///     true
+    /// });
+    /// assert_eq!(clauses, vec![vec![1, 2]]);
+    /// ```
+    pub fn traverse_clauses<F>(&self, mut visit: F) -> bool
+    where
+        F: FnMut(&[i32]) -> bool,
+    {
+        let data = &mut visit as *mut F as *mut c_void;
+        let res = unsafe { ccadical_traverse_clauses(self.ptr, data, Self::clause_cb::<F>) };
+        res != 0
+    }
+
+    extern "C" fn clause_cb<F>(data: *mut c_void, clause: *const c_int, len: usize) -> c_int
+    where
+        F: FnMut(&[i32]) -> bool,
+    {
+        debug_assert!(!data.is_null());
+        let visit = unsafe { &mut *(data as *mut F) };
+        let clause = if len == 0 {
+            &[]
+        } else {
+            debug_assert!(!clause.is_null());
+            unsafe { slice::from_raw_parts(clause, len) }
+        };
+        visit(clause) as c_int
+    }
+
+    /// Increase the maximum variable index explicitly.
+    ///
+    /// This function makes sure that at least 'min_max_var' variables are initialized.
+    /// A call to this function sets the solver status to `None`.
+    pub fn reserve(&mut self, min_max_var: i32) {
+        let _account = self.account.enter();
+        let ret = unsafe { ccadical_reserve(self.ptr, min_max_var) };
+        self.allocated(ret);
+    }
+
+    /// Hints that the given number of additional clauses with at least two
+    /// literals will be added, so the internal clause list can be allocated
+    /// at once instead of growing repeatedly during bulk loading. The
+    /// clauses themselves are still allocated one by one by CaDiCaL.
+    /// # Examples
+    /// ```
+    /// let mut sat: cadical::Solver = Default::default();
+    /// sat.reserve(100);
+    /// sat.reserve_clauses(99);
+    /// for var in 1..100 {
+    ///     sat.add_clause([-var, var + 1]);
+    /// }
+    /// assert_eq!(sat.num_clauses(), 99);
+    /// ```
+    pub fn reserve_clauses(&mut self, count: usize) {
+        let _account = self.account.enter();
+        let ret = unsafe { ccadical_reserve_clauses(self.ptr, count) };
+        self.allocated(ret);
+    }
+
+    /// Records the failure of a native allocation reported by a shim.
+    #[inline]
+    fn allocated(&mut self, ret: c_int) {
+        if ret < 0 {
+            self.out_of_memory = true;
+        }
+    }
+
+    /// Returns `true` if a native allocation of CaDiCaL failed. C++
+    /// allocation failures are caught at the language boundary instead of
+    /// aborting the process, but the internal state of the solver might be
+    /// inconsistent afterwards, so solve calls return `None` until the
+    /// solver is `reset`. Failures detected by CaDiCaL itself still abort.
+    pub fn out_of_memory(&self) -> bool {
+        self.out_of_memory
+    }
+
+    /// Adds the given clause like `add_clause`, but returns an error if a
+    /// native allocation failed now or earlier, or if the clause contains a
+    /// zero under `ZeroLiteralPolicy::Reject`, in which case it is not added.
+    pub fn try_add_clause<I>(&mut self, clause: I) -> Result<(), Error>
+    where
+        I: IntoIterator<Item = i32>,
+    {
+        if !self.out_of_memory {
+            self.check_reentrancy("add_clause");
+            self.clause.clear();
+            self.clause.extend(clause);
+            if self.zero_policy == ZeroLiteralPolicy::Reject && self.clause.contains(&0) {
+                return Err(Error::new("zero literal in clause"));
+            }
+            self.check_selectors(&self.clause);
+            self.add_zero_separated();
+        }
+        self.memory_result(())
+    }
+
+    /// Solves the formula like `solve`, but returns an error if a native
+    /// allocation failed now or earlier.
+    /// # Examples
+    /// ```
+    /// let mut sat: cadical::Solver = Default::default();
+    /// sat.try_add_clause([1, 2]).unwrap();
+    /// assert_eq!(sat.try_solve().unwrap(), Some(true));
+    /// ```
+    pub fn try_solve(&mut self) -> Result<Option<bool>, Error> {
+        let ret = self.solve();
+        self.memory_result(ret)
+    }
+
+    fn memory_result<T>(&self, value: T) -> Result<T, Error> {
+        if self.out_of_memory {
+            Err(Error::new("out of memory"))
+        } else {
+            Ok(value)
+        }
+    }
+}
+
+/// Converts a count to a narrower integer type, returning an error instead
+/// of silently truncating it.
+pub(crate) fn narrow<S, T>(value: S) -> Result<T, Error>
+where
+    S: Copy + fmt::Display,
+    T: TryFrom<S>,
+{
+    T::try_from(value).map_err(|_| Error::new(&format!("count {} out of range", value)))
+}
+
+/// Returns the path in the encoding expected by `fopen`. On Unix paths are
+/// arbitrary bytes, while elsewhere only Unicode paths are supported.
+#[cfg(unix)]
+fn dimacs_path(path: &Path) -> Result<CString, Error> {
+    use std::os::unix::ffi::OsStrExt;
+    CString::new(path.as_os_str().as_bytes()).map_err(|_| Error::new("invalid path"))
+}
+
+#[cfg(not(unix))]
+fn dimacs_path(path: &Path) -> Result<CString, Error> {
+    let path = path.to_str().ok_or_else(|| Error::new("invalid path"))?;
+    CString::new(path).map_err(|_| Error::new("invalid path"))
+}
+
+/// Returns the largest variable of the literals, or zero if there are none.
+fn max_var(lits: &[i32]) -> i32 {
+    lits.iter().map(|lit| lit.wrapping_abs()).max().unwrap_or(0)
+}
+
+/// Returns the value of the terminate callback when the solver is stopped for
+/// the given reason, which is also reported as a `tracing` event.
+#[cfg_attr(not(feature = "tracing"), allow(unused_variables))]
+fn terminated(reason: &str) -> c_int {
+    #[cfg(feature = "tracing")]
+    tracing::debug!(reason, "terminated");
+    1
+}
+
+fn dimacs_error(err: *const c_char) -> Error {
+    let err = unsafe { CStr::from_ptr(err) };
+    Error::from_bytes(err.to_bytes())
+}
+
+/// Returns the signature of the library, with invalid UTF-8 sequences
+/// replaced. It is converted only once, so it can be borrowed statically.
+pub(crate) fn signature() -> &'static str {
+    static SIGNATURE: OnceLock<String> = OnceLock::new();
+    SIGNATURE.get_or_init(|| {
+        let sig = unsafe { CStr::from_ptr(ccadical_signature()) };
+        sig.to_string_lossy().into_owned()
+    })
+}
+
+impl<C: Callbacks> Default for Solver<C> {
+    fn default() -> Self {
         Solver::new()
     }
-}
+}
+
+impl<C: Callbacks> Drop for Solver<C> {
+    fn drop(&mut self) {
+        let _account = self.account.enter();
+        unsafe { ccadical_release(self.ptr) };
+        // the solver writes its last calls when it is released
+        #[cfg(feature = "cpp-tracing")]
+        if !self.api_trace_file.is_null() {
+            unsafe { ccadical_trace_close(self.api_trace_file) };
+        }
+    }
+}
+
+/// CaDiCaL does not use thread local variables, so it is possible to
+/// move it between threads. However it cannot be used concurrently
+/// (for example getting the value from multiple threads at once), so we
+/// do not implement `Sync`.
+unsafe impl<C: Callbacks + Send> Send for Solver<C> {}
+
+/// The states of the internal state machine of the solver.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum State {
+    /// During the construction of the solver.
+    Initializing,
+    /// Before any clauses or assumptions are added, options can be set.
+    Configuring,
+    /// Ready to add clauses and assumptions, or to solve.
+    Steady,
+    /// A clause has been started but not yet terminated.
+    Adding,
+    /// During solving, for example within the callbacks.
+    Solving,
+    /// The last call to solve found a solution.
+    Satisfied,
+    /// The last call to solve proved unsatisfiability.
+    Unsatisfied,
+    /// During the destruction of the solver.
+    Deleting,
+}
+
+/// Handle of a clause added by `Solver::add_removable_clause`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ClauseHandle(i32);
+
+/// Callbacks trait for finer control. A panic in a callback stops the search
+/// and is resumed when the native solver has returned from `solve`.
+///
+/// The solver is borrowed by `solve` while the callbacks run, so they cannot
+/// call it in safe code. Reentering it through raw pointers is not allowed
+/// from any callback: adding clauses, assumptions or constraints, solving,
+/// simplifying and resetting panic with a clear message instead of
+/// corrupting the solver, and the rest is undefined behavior. To stop the
+/// search, return `true` from `terminate` or set the flag returned by
+/// `Solver::interrupt_flag`, which is allowed from any thread.
+pub trait Callbacks {
+    /// Called when the `solve` method is called.
+    #[inline(always)]
+    fn started(&mut self) {}
+
+    /// Called by the solver periodically to check if it should terminate.
+    #[inline(always)]
+    fn terminate(&mut self) -> bool {
+        false
+    }
+
+    /// Returns the maximum length of clauses to be passed to `learn`. This
+    /// method will be called once at the start of each `solve` call.
+    #[inline(always)]
+    fn max_length(&self) -> i32 {
+        0
+    }
+
+    /// Returns the maximum glue (the number of different decision levels of
+    /// the literals) of clauses to be passed to `learn`. Clauses above this
+    /// and the `max_length` threshold are dropped by the native solver
+    /// without calling into Rust, unless `observe_conflicts` is enabled.
+    /// This method will be called once at the start of each `solve` call.
+    #[inline(always)]
+    fn max_glue(&self) -> i32 {
+        i32::MAX
+    }
+
+    /// Called by the solver when a new derived clause is learnt.
+    #[allow(unused_variables)]
+    #[inline(always)]
+    fn learn(&mut self, clause: &[i32]) {}
+
+    /// Returns whether the learned clauses should be passed to
+    /// `learn_owned` instead of `learn`. This method will be called once at
+    /// the start of each `solve` call, and is only available with the
+    /// `smallvec` feature.
+    #[cfg(feature = "smallvec")]
+    #[inline(always)]
+    fn learn_owned_clauses(&self) -> bool {
+        false
+    }
+
+    /// Called by the solver when a new derived clause is learnt, with an
+    /// owned copy allocated from the `ClausePool` of the solver, which the
+    /// callback can keep without copying. This is only available with the
+    /// `smallvec` feature.
+    #[cfg(feature = "smallvec")]
+    #[inline(always)]
+    fn learn_owned(&mut self, clause: LearnedClause) {
+        self.learn(&clause);
+    }
+
+    /// Returns whether `conflict` should be called. This method will be
+    /// called once at the start of each `solve` call.
+    #[inline(always)]
+    fn observe_conflicts(&self) -> bool {
+        false
+    }
+
+    /// Called for each clause learned from a conflict with its length, its
+    /// glue (the number of different decision levels of its literals, also
+    /// known as LBD) and the decision level the solver backjumps to. This
+    /// requires passing every learned clause through the learn callback, so
+    /// it has a noticeable overhead.
+    #[allow(unused_variables)]
+    #[inline(always)]
+    fn conflict(&mut self, size: usize, glue: i32, jump: i32) {}
+
+    /// Returns whether `restart` should be called. This method will be
+    /// called once at the start of each `solve` call.
+    #[inline(always)]
+    fn observe_restarts(&self) -> bool {
+        false
+    }
+
+    /// Called after the solver has restarted with the total number of
+    /// restarts so far and the current decision level. Restarts are detected
+    /// when the solver checks for termination, which happens frequently but
+    /// not immediately, so several restarts might be reported at once and
+    /// the level might already include new decisions.
+    #[allow(unused_variables)]
+    #[inline(always)]
+    fn restart(&mut self, restarts: u64, level: i32) {}
+
+    /// Returns whether `report` should be called. This method will be
+    /// called once at the start of each `solve` call.
+    #[inline(always)]
+    fn observe_reports(&self) -> bool {
+        false
+    }
+
+    /// Called after the solver has restarted with the columns of the
+    /// progress report line of CaDiCaL, such as the number of conflicts,
+    /// the trail size and the numbers of clauses and active variables. The
+    /// reports are detected like the restart events, so they are delivered
+    /// even if the verbose output is disabled, but several restarts might
+    /// be reported at once.
+    #[allow(unused_variables)]
+    #[inline(always)]
+    fn report(&mut self, event: &ReportEvent) {}
+
+    /// Returns the sampling interval of `decision`, where zero disables it
+    /// and `n` reports at most one out of every `n` decisions. This method
+    /// will be called once at the start of each `solve` call. Only available
+    /// with the `debug-hooks` feature.
+    #[cfg(feature = "debug-hooks")]
+    #[inline(always)]
+    fn decision_interval(&self) -> u64 {
+        0
+    }
+
+    /// Called with the most recent decision literal and its decision level.
+    /// Decisions are sampled when the solver checks for termination, so
+    /// even with an interval of one some decisions are not reported (for
+    /// example those immediately followed by a conflict). This is meant for
+    /// teaching and debugging, and it slows down the solver considerably.
+    /// Only available with the `debug-hooks` feature.
+    #[cfg(feature = "debug-hooks")]
+    #[allow(unused_variables)]
+    #[inline(always)]
+    fn decision(&mut self, lit: i32, level: i32) {}
+}
+
+/// Callbacks implementing a simple timeout.
+pub struct Timeout {
+    pub started: Instant,
+    pub timeout: f32,
+}
+
+impl Timeout {
+    /// Creates a new timeout structure with the given timeout value.
+    pub fn new(timeout: f32) -> Self {
+        Timeout {
+            started: Instant::now(),
+            timeout,
+        }
+    }
+}
+
+impl Callbacks for Timeout {
+    #[inline(always)]
+    fn started(&mut self) {
+        self.started = Instant::now();
+    }
+
+    #[inline(always)]
+    fn terminate(&mut self) -> bool {
+        self.started.elapsed().as_secs_f32() >= self.timeout
+    }
+}
+
+/// Resource limits for a single `solve_limited` or `solve_budgeted` call.
+/// Missing limits are not enforced, so the default budget is unlimited. The
+/// propagation and wall clock limits are checked when the solver polls for
+/// termination, so they can be slightly exceeded.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Budget {
+    /// The maximum number of conflicts.
+    pub conflicts: Option<u64>,
+    /// The maximum number of decisions.
+    pub decisions: Option<u64>,
+    /// The maximum number of propagated literals during search.
+    pub propagations: Option<u64>,
+    /// The maximum wall clock time.
+    pub time: Option<Duration>,
+}
+
+/// Error type for configuration and DIMACS reading and writing errors. Use
+/// `Error::new` to construct one, more fields might be added later.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub struct Error {
+    pub msg: String,
+    /// The original bytes of a native message that is not valid UTF-8, in
+    /// which case `msg` contains its lossy conversion.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub raw: Option<Vec<u8>>,
+}
+
+impl Error {
+    pub fn new(msg: &str) -> Self {
+        Error {
+            msg: msg.to_string(),
+            raw: None,
+        }
+    }
+
+    /// Converts a message of the native code, where invalid UTF-8 sequences
+    /// are replaced with `U+FFFD` in `msg` but the original bytes are kept.
+    pub(crate) fn from_bytes(bytes: &[u8]) -> Self {
+        match std::str::from_utf8(bytes) {
+            Ok(msg) => Error::new(msg),
+            Err(_) => Error {
+                msg: String::from_utf8_lossy(bytes).into_owned(),
+                raw: Some(bytes.to_vec()),
+            },
+        }
+    }
+
+    /// Returns the message as raw bytes, which are the original bytes of the
+    /// native message if it was not valid UTF-8.
+    pub fn as_bytes(&self) -> &[u8] {
+        self.raw.as_deref().unwrap_or(self.msg.as_bytes())
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.msg.fmt(f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+
+    #[test]
+    #[cfg(not(miri))]
+    fn solver() {
+        let mut sat: Solver = Solver::new();
+        assert!(sat.signature().starts_with("cadical-"));
+        assert_eq!(sat.status(), None);
+        sat.add_clause([1, 2]);
+        assert_eq!(sat.max_variable(), 2);
+        assert_eq!(sat.num_variables(), 2);
+        assert_eq!(sat.num_clauses(), 1);
+        assert_eq!(sat.solve(), Some(true));
+        assert_eq!(sat.solve_with([-1].iter().copied()), Some(true));
+        assert_eq!(sat.value(1), Some(false));
+        assert_eq!(sat.value(-1), Some(true));
+        assert_eq!(sat.value(2), Some(true));
+        assert_eq!(sat.value(-2), Some(false));
+        assert_eq!(sat.solve_with([-2].iter().copied()), Some(true));
+        assert_eq!(sat.value(1), Some(true));
+        assert_eq!(sat.value(2), Some(false));
+        assert_eq!(sat.solve_with([-1, -2].iter().copied()), Some(false));
+        assert_eq!(sat.failed(-1), true);
+        assert_eq!(sat.failed(-2), true);
+        assert_eq!(sat.status(), Some(false));
+        sat.add_clause([4, 5]);
+        assert_eq!(sat.status(), None);
+        assert_eq!(sat.max_variable(), 5);
+        assert_eq!(sat.num_variables(), 4);
+        assert_eq!(sat.num_clauses(), 2);
+        assert_eq!(sat.solve_with([-1, -2, -4].iter().copied()), Some(false));
+        assert_eq!(sat.failed(-1), true);
+        assert_eq!(sat.failed(-2), true);
+        assert_eq!(sat.failed(-4), false);
+    }
+
+    fn pigeon_hole(num: i32) -> Solver {
+        let mut sat: Solver = Solver::new();
+        for i in 0..(num + 1) {
+            sat.add_clause((0..num).map(|j| 1 + i * num + j));
+        }
+        for i1 in 0..(num + 1) {
+            for i2 in 0..(num + 1) {
+                if i1 == i2 {
+                    continue;
+                }
+                for j in 0..num {
+                    let l1 = 1 + i1 * num + j;
+                    let l2 = 1 + i2 * num + j;
+                    sat.add_clause([-l1, -l2])
+                }
+            }
+        }
+        sat
+    }
+
+    #[test]
+    fn timeout() {
+        // the naive mockup and the pure Rust solver are slower on the large instance
+        let mut sat = pigeon_hole(if cfg!(miri) {
+            5
+        } else if cfg!(feature = "pure-rust") {
+            8
+        } else {
+            9
+        });
+        let started = Instant::now();
+        sat.set_callbacks(Some(Timeout::new(0.2)));
+        let result = sat.solve();
+        assert_eq!(sat.get_callbacks().unwrap().timeout, 0.2);
+        let elapsed = started.elapsed().as_secs_f32();
+        if result == None {
+            assert!(0.1 < elapsed && elapsed < 0.3);
+        } else {
+            assert!(result == Some(false) && elapsed <= 0.3);
+        }
+
+        let started = Instant::now();
+        sat.set_callbacks(Some(Timeout::new(0.5)));
+        let result = sat.solve();
+        let elapsed = started.elapsed().as_secs_f32();
+        if result == None {
+            assert!(0.4 < elapsed && elapsed < 0.6);
+        } else {
+            assert!(result == Some(false) && elapsed <= 0.6);
+        }
+
+        sat.set_callbacks(None);
+        assert_eq!(sat.solve(), Some(false));
+    }
+
+    #[test]
+    #[cfg(not(miri))]
+    fn restart_events() {
+        #[derive(Default)]
+        struct Restarts {
+            events: Vec<(u64, i32)>,
+        }
+
+        impl Callbacks for Restarts {
+            fn observe_restarts(&self) -> bool {
+                true
+            }
+
+            fn restart(&mut self, restarts: u64, level: i32) {
+                self.events.push((restarts, level));
+            }
+        }
+
+        let mut sat: Solver<Restarts> = Solver::new();
+        for i in 0..8 {
+            sat.add_clause((0..7).map(|j| 1 + i * 7 + j));
+            for k in 0..i {
+                for j in 0..7 {
+                    sat.add_clause([-(1 + i * 7 + j), -(1 + k * 7 + j)]);
+                }
+            }
+        }
+        sat.set_callbacks(Some(Restarts::default()));
+        assert_eq!(sat.solve(), Some(false));
+        let events = &sat.get_callbacks().unwrap().events;
+        assert!(!events.is_empty());
+        assert!(events.windows(2).all(|pair| pair[0].0 < pair[1].0));
+        assert!(events.iter().all(|&(_, level)| level >= 0));
+    }
+
+    #[test]
+    #[cfg(not(miri))]
+    fn report_events() {
+        #[derive(Default)]
+        struct Reports {
+            events: Vec<ReportEvent>,
+        }
+
+        impl Callbacks for Reports {
+            fn observe_reports(&self) -> bool {
+                true
+            }
+
+            fn report(&mut self, event: &ReportEvent) {
+                self.events.push(*event);
+            }
+        }
+
+        let mut sat: Solver<Reports> = Solver::new();
+        sat.add_formula(&generators::pigeon_hole(7));
+        sat.set_callbacks(Some(Reports::default()));
+        assert_eq!(sat.solve(), Some(false));
+        let events = &sat.get_callbacks().unwrap().events;
+        assert!(!events.is_empty());
+        assert!(events
+            .windows(2)
+            .all(|pair| pair[0].restarts < pair[1].restarts));
+        assert!(events
+            .windows(2)
+            .all(|pair| pair[0].conflicts <= pair[1].conflicts));
+        assert!(events
+            .windows(2)
+            .all(|pair| pair[0].seconds <= pair[1].seconds));
+        for event in events.iter() {
+            assert!(event.active <= event.variables);
+            assert!((0.0..=100.0).contains(&event.remaining()));
+        }
+    }
+
+    #[test]
+    #[cfg(all(feature = "debug-hooks", not(miri)))]
+    fn decision_events() {
+        #[derive(Default)]
+        struct Decisions {
+            events: Vec<(i32, i32)>,
+        }
+
+        impl Callbacks for Decisions {
+            fn decision_interval(&self) -> u64 {
+                1
+            }
+
+            fn decision(&mut self, lit: i32, level: i32) {
+                self.events.push((lit, level));
+            }
+        }
+
+        let mut sat: Solver<Decisions> = Solver::new();
+        for i in 0..6 {
+            sat.add_clause((0..5).map(|j| 1 + i * 5 + j));
+            for k in 0..i {
+                for j in 0..5 {
+                    sat.add_clause([-(1 + i * 5 + j), -(1 + k * 5 + j)]);
+                }
+            }
+        }
+        sat.set_callbacks(Some(Decisions::default()));
+        assert_eq!(sat.solve(), Some(false));
+        let events = &sat.get_callbacks().unwrap().events;
+        assert!(!events.is_empty());
+        assert!(events
+            .iter()
+            .all(|&(lit, level)| lit.abs() <= 30 && level > 0));
+    }
+
+    #[test]
+    #[cfg(not(miri))]
+    fn learn_glue_filter() {
+        struct Learned {
+            max_glue: i32,
+            learned: usize,
+        }
+
+        impl Callbacks for Learned {
+            fn max_length(&self) -> i32 {
+                100
+            }
+
+            fn max_glue(&self) -> i32 {
+                self.max_glue
+            }
+
+            fn learn(&mut self, _clause: &[i32]) {
+                self.learned += 1;
+            }
+        }
+
+        let mut counts = Vec::new();
+        for &max_glue in [1, 3, i32::MAX].iter() {
+            let mut sat: Solver<Learned> = Solver::new();
+            sat.add_formula(&generators::pigeon_hole(5));
+            sat.set_callbacks(Some(Learned {
+                max_glue,
+                learned: 0,
+            }));
+            assert_eq!(sat.solve(), Some(false));
+            counts.push(sat.get_callbacks().unwrap().learned);
+        }
+        assert!(counts[0] <= counts[1] && counts[1] <= counts[2]);
+        assert!(counts[2] > 0);
+    }
+
+    #[test]
+    #[cfg(not(miri))]
+    fn conflict_events() {
+        #[derive(Default)]
+        struct Conflicts {
+            events: Vec<(usize, i32, i32)>,
+            learned: usize,
+        }
+
+        impl Callbacks for Conflicts {
+            fn max_length(&self) -> i32 {
+                2
+            }
+
+            fn learn(&mut self, clause: &[i32]) {
+                assert!(clause.len() <= 2);
+                self.learned += 1;
+            }
+
+            fn observe_conflicts(&self) -> bool {
+                true
+            }
+
+            fn conflict(&mut self, size: usize, glue: i32, jump: i32) {
+                self.events.push((size, glue, jump));
+            }
+        }
+
+        let mut sat: Solver<Conflicts> = Solver::new();
+        for i in 0..6 {
+            sat.add_clause((0..5).map(|j| 1 + i * 5 + j));
+            for k in 0..i {
+                for j in 0..5 {
+                    sat.add_clause([-(1 + i * 5 + j), -(1 + k * 5 + j)]);
+                }
+            }
+        }
+        sat.set_callbacks(Some(Conflicts::default()));
+        assert_eq!(sat.solve(), Some(false));
+        let cbs = sat.get_callbacks().unwrap();
+        assert!(!cbs.events.is_empty());
+        let short = cbs.events.iter().filter(|event| event.0 <= 2).count();
+        assert_eq!(cbs.learned, short);
+        for &(size, glue, jump) in cbs.events.iter() {
+            assert!(size >= 1 && glue >= 1 && glue as usize <= size);
+            assert!(jump >= 0 && (size > 1 || jump == 0));
+        }
+    }
+
+    #[test]
+    #[cfg(all(feature = "trace-viz", not(miri)))]
+    fn trace_viz() {
+        let mut sat: Solver<Trace> = Solver::new();
+        for i in 0..6 {
+            sat.add_clause((0..5).map(|j| 1 + i * 5 + j));
+            for k in 0..i {
+                for j in 0..5 {
+                    sat.add_clause([-(1 + i * 5 + j), -(1 + k * 5 + j)]);
+                }
+            }
+        }
+        sat.set_callbacks(Some(Trace::new(1, 100)));
+        assert_eq!(sat.solve(), Some(false));
+        let trace = sat.get_callbacks().unwrap();
+        let events = trace.events();
+        assert_eq!(events.len(), 100);
+        assert!(events.windows(2).all(|pair| pair[0].0 <= pair[1].0));
+        let conflicts = events
+            .iter()
+            .filter(|(_, event)| matches!(event, TraceEvent::Conflict { .. }));
+        assert!(conflicts.count() > 0);
+        let dot = trace.to_dot();
+        assert_eq!(dot.matches("->").count(), 100);
+        let json = trace.to_json();
+        assert_eq!(json.matches("\"event\"").count(), 100);
+        trace.clear();
+        assert!(trace.events().is_empty());
+    }
+
+    #[test]
+    #[cfg(not(miri))]
+    fn propagation_limit() {
+        let mut sat = pigeon_hole(5);
+        sat.set_limit("propagations", 500).unwrap();
+        let propagations = unsafe { ccadical_propagations(sat.ptr) };
+        assert_eq!(sat.solve(), None);
+        let spent = unsafe { ccadical_propagations(sat.ptr) } - propagations;
+        assert!(spent >= 500);
+        sat.set_limit("propagations", 500).unwrap();
+        sat.set_limit("propagations", -1).unwrap();
+        assert_eq!(sat.solve(), Some(false));
+    }
+
+    #[test]
+    #[cfg(all(feature = "rustsat", not(miri)))]
+    fn rustsat() {
+        use ::rustsat::solvers::{
+            Interrupt, InterruptSolver, Solve, SolveIncremental, SolverResult,
+        };
+        use ::rustsat::types::{Lit, TernaryVal};
+
+        let mut sat: Solver = Solver::new();
+        let (a, b, c) = (Lit::positive(0), Lit::positive(1), Lit::positive(2));
+        sat.add_binary(a, b).unwrap();
+        sat.add_binary(!a, c).unwrap();
+        assert_eq!(Solve::solve(&mut sat).unwrap(), SolverResult::Sat);
+        assert_eq!(sat.solve_assumps(&[!b, !c]).unwrap(), SolverResult::Unsat);
+        let mut core = sat.core().unwrap();
+        core.sort();
+        assert_eq!(core, vec![b, c]);
+        assert_eq!(sat.solve_assumps(&[!b]).unwrap(), SolverResult::Sat);
+        assert_eq!(sat.lit_val(c).unwrap(), TernaryVal::True);
+
+        let mut sat = pigeon_hole(9);
+        let interrupter = sat.interrupter();
+        let handle = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(100));
+            interrupter.interrupt();
+        });
+        assert_eq!(Solve::solve(&mut sat).unwrap(), SolverResult::Interrupted);
+        handle.join().unwrap();
+    }
+
+    #[test]
+    #[cfg(all(feature = "varisat", not(miri)))]
+    fn varisat() {
+        use varisat_formula::{CnfFormula, ExtendFormula, Lit};
+
+        let lit = Lit::from_dimacs;
+        let mut formula = CnfFormula::new();
+        formula.add_clause(&[lit(1), lit(2)]);
+        formula.add_clause(&[lit(-1), lit(3)]);
+        formula.add_clause(&[lit(-3)]);
+        let mut sat: Solver = Solver::from(&formula);
+        assert_eq!(sat.max_variable(), 3);
+        assert_eq!(sat.solve(), Some(true));
+        assert_eq!(sat.value(2), Some(true));
+        sat.add_varisat_formula(&formula);
+        assert_eq!(sat.solve_with([-2]), Some(false));
+    }
+
+    #[test]
+    #[cfg(all(feature = "dimacs", not(miri)))]
+    fn dimacs_instance() {
+        let text = "c example\np cnf 4 3\n1 2 0\n-1 2 0\n-2 3 0\n";
+        let instance = ::dimacs::parse_dimacs(text).unwrap();
+        let mut sat: Solver = Solver::new();
+        sat.add_cnf(&instance).unwrap();
+        assert_eq!(sat.max_variable(), 4);
+        assert_eq!(sat.solve(), Some(true));
+        assert_eq!(sat.value(3), Some(true));
+        assert_eq!(sat.solve_with([-3]), Some(false));
+
+        let instance = ::dimacs::parse_dimacs("p sat 2\n(*(1 -2))\n").unwrap();
+        let result = sat.add_cnf(&instance);
+        assert_eq!(result, Err(Error::new("unsupported instance")));
+    }
+
+    #[test]
+    #[cfg(all(feature = "memory-accounting", not(miri)))]
+    fn memory_accounting() {
+        let mut sat: Solver = Solver::new();
+        let empty = sat.memory_usage();
+        assert!(empty > 0);
+        sat.add_formula(&generators::pigeon_hole(8));
+        let loaded = sat.memory_usage();
+        assert!(loaded > empty);
+        assert!(sat.peak_memory_usage() >= loaded);
+
+        let other: Solver = Solver::new();
+        assert!(other.memory_usage() > 0);
+        assert_eq!(sat.memory_usage(), loaded);
+
+        sat.set_memory_limit(Some(loaded));
+        assert_eq!(sat.solve(), None);
+        sat.reset();
+        assert!(sat.memory_usage() < loaded);
+    }
+
+    #[test]
+    #[cfg(all(feature = "cdylib", not(miri)))]
+    fn ipasir() {
+        use super::ipasir::*;
+        use std::ptr::null_mut;
+
+        extern "C" fn stop(_data: *mut c_void) -> c_int {
+            1
+        }
+
+        unsafe {
+            let solver = ipasir_init();
+            assert!(!ipasir_signature().is_null());
+            for &lit in [1, 2, 0, -1, 2, 0].iter() {
+                ipasir_add(solver, lit);
+            }
+            assert_eq!(ipasir_solve(solver), 10);
+            assert_eq!(ipasir_val(solver, 2), 2);
+            assert_eq!(ipasir_val(solver, -2), 2);
+
+            ipasir_assume(solver, -2);
+            assert_eq!(ipasir_solve(solver), 20);
+            assert_eq!(ipasir_failed(solver, -2), 1);
+            assert_eq!(ipasir_solve(solver), 10);
+
+            ipasir_set_terminate(solver, null_mut(), Some(stop));
+            assert_eq!(ipasir_solve(solver), 0);
+            ipasir_set_terminate(solver, null_mut(), None);
+            assert_eq!(ipasir_solve(solver), 10);
+            ipasir_release(solver);
+        }
+    }
+
+    #[test]
+    #[cfg(all(feature = "cdylib", not(miri)))]
+    fn ipasir2() {
+        use super::ipasir2::*;
+        use std::ffi::CStr;
+        use std::ptr::{null, null_mut};
+
+        extern "C" fn stop(_data: *mut c_void) -> c_int {
+            1
+        }
+
+        unsafe {
+            let mut solver = null_mut();
+            assert_eq!(ipasir2_init(&mut solver), Ipasir2ErrorCode::Ok);
+            let mut options = null();
+            let mut count = 0;
+            ipasir2_options(solver, &mut options, &mut count);
+            let options = std::slice::from_raw_parts(options, count as usize);
+            let seed = options
+                .iter()
+                .find(|option| CStr::from_ptr(option.name).to_bytes() == b"cadical.seed")
+                .unwrap();
+            assert_eq!(ipasir2_set_option(solver, seed, 7, 0), Ipasir2ErrorCode::Ok);
+            assert_eq!(
+                ipasir2_set_option(solver, seed, -1, 0),
+                Ipasir2ErrorCode::InvalidOptionValue
+            );
+
+            let none = Ipasir2Redundancy::None as c_int;
+            assert_eq!(
+                ipasir2_add(solver, [1, 2].as_ptr(), 2, none),
+                Ipasir2ErrorCode::Ok
+            );
+            assert_eq!(
+                ipasir2_add(solver, [1, 3].as_ptr(), 2, 4),
+                Ipasir2ErrorCode::InvalidArgument
+            );
+            assert_eq!(
+                ipasir2_add(solver, [-1, 2].as_ptr(), 2, none),
+                Ipasir2ErrorCode::Ok
+            );
+            assert_eq!(
+                ipasir2_set_option(solver, seed, 8, 0),
+                Ipasir2ErrorCode::InvalidState
+            );
+
+            let mut result = 0;
+            ipasir2_solve(solver, &mut result, null(), 0);
+            assert_eq!(result, 10);
+            let mut value = 0;
+            assert_eq!(ipasir2_val(solver, -2, &mut value), Ipasir2ErrorCode::Ok);
+            assert_eq!(value, 2);
+            assert_eq!(
+                ipasir2_failed(solver, 2, &mut result),
+                Ipasir2ErrorCode::InvalidState
+            );
+
+            ipasir2_solve(solver, &mut result, [-2].as_ptr(), 1);
+            assert_eq!(result, 20);
+            assert_eq!(
+                ipasir2_failed(solver, -2, &mut result),
+                Ipasir2ErrorCode::Ok
+            );
+            assert_eq!(result, 1);
+
+            ipasir2_set_terminate(solver, null_mut(), Some(stop));
+            ipasir2_solve(solver, &mut result, null(), 0);
+            assert_eq!(result, 0);
+            ipasir2_set_terminate(solver, null_mut(), None);
+            ipasir2_solve(solver, &mut result, null(), 0);
+            assert_eq!(result, 10);
+            assert_eq!(ipasir2_release(solver), Ipasir2ErrorCode::Ok);
+        }
+    }
+
+    #[test]
+    #[cfg(all(feature = "testing", not(miri)))]
+    fn cross_check() {
+        use testing::{Assumptions, Formula};
+        fn prop(formula: Formula, assumptions: Assumptions) -> bool {
+            testing::cross_check(&formula, &assumptions.0).is_ok()
+        }
+        quickcheck::quickcheck(prop as fn(Formula, Assumptions) -> bool);
+
+        let formula = Formula {
+            variables: 2,
+            clauses: vec![vec![1, 2], vec![-1, 2], vec![1, -2]],
+        };
+        assert_eq!(
+            testing::brute_force(2, &formula.clauses, &[]),
+            Some(vec![1, 2])
+        );
+        assert_eq!(testing::brute_force(2, &formula.clauses, &[-2]), None);
+        assert!(testing::cross_check(&formula, &[-2, 1]).is_ok());
+    }
+
+    #[test]
+    fn random_ksat() {
+        let formula = generators::random_ksat(20, 100, 3, 42);
+        assert_eq!(formula.len(), 100);
+        assert_eq!(formula.num_literals(), 300);
+        assert_eq!(formula.max_variable(), 20);
+        for clause in formula.iter() {
+            assert!(clause.iter().all(|lit| (1..=20).contains(&lit.abs())));
+            assert!(clause[0].abs() != clause[1].abs());
+            assert!(clause[0].abs() != clause[2].abs());
+            assert!(clause[1].abs() != clause[2].abs());
+        }
+        assert_eq!(formula, generators::random_ksat(20, 100, 3, 42));
+        assert_ne!(formula, generators::random_ksat(20, 100, 3, 43));
+
+        let clauses: Vec<Vec<i32>> = generators::random_ksat_clauses(20, 100, 3, 42).collect();
+        assert!(formula.iter().eq(clauses.iter().map(|c| c.as_slice())));
+    }
+
+    #[test]
+    #[cfg(all(feature = "pure-rust", not(miri)))]
+    fn pure_rust() {
+        let satisfies = |formula: &CnfFormula, model: u32| {
+            formula.iter().all(|clause| {
+                let value = |lit: i32| (model >> (lit.abs() - 1)) & 1 == (lit > 0) as u32;
+                clause.iter().any(|&lit| value(lit))
+            })
+        };
+        for seed in 0..50 {
+            let formula = generators::random_ksat(12, 52, 3, seed);
+            let expected = (0..1 << 12).any(|model| satisfies(&formula, model));
+            let mut sat: Solver = Solver::new();
+            sat.add_formula(&formula);
+            assert_eq!(sat.solve(), Some(expected));
+            if expected {
+                let model = (1..=12).filter(|&var| sat.value(var) == Some(true));
+                assert!(satisfies(&formula, model.map(|var| 1 << (var - 1)).sum()));
+            }
+
+            let assumptions = [1, -2, 3, -4];
+            let result = sat.solve_with(assumptions.iter().copied());
+            if result == Some(false) {
+                let mut core: Solver = Solver::new();
+                core.add_formula(&formula);
+                let failed = assumptions.iter().copied().filter(|&lit| sat.failed(lit));
+                assert_eq!(core.solve_with(failed), Some(false));
+            }
+        }
+    }
+
+    #[test]
+    fn crafted_families() {
+        let formula = generators::pigeon_hole(4);
+        assert_eq!((formula.max_variable(), formula.len()), (20, 5 + 4 * 10));
+        let formula = generators::parity(6, 3);
+        assert_eq!((formula.max_variable(), formula.len()), (16, 2 * 5 * 4 + 2));
+        let formula = generators::tseitin(8, 4, 5);
+        assert_eq!((formula.max_variable(), formula.len()), (16, 8 * 8));
+        assert_eq!(formula, generators::tseitin(8, 4, 5));
+        let formula = generators::counting(7, 3);
+        assert_eq!(formula.max_variable(), 35);
+        assert_eq!(formula.len(), 7 + 7 * (15 * 14 / 2));
+    }
+
+    #[test]
+    #[cfg(not(miri))]
+    fn crafted_unsat() {
+        let formulas = [
+            generators::pigeon_hole(5),
+            generators::parity(12, 1),
+            generators::tseitin(12, 3, 2),
+            generators::counting(7, 2),
+            generators::counting(7, 3),
+        ];
+        for formula in formulas.iter() {
+            let mut sat: Solver = Solver::new();
+            sat.add_formula(formula);
+            assert_eq!(sat.solve(), Some(false));
+        }
+        let mut sat: Solver = Solver::new();
+        sat.add_formula(&generators::counting(6, 3));
+        assert_eq!(sat.solve(), Some(true));
+    }
+
+    #[test]
+    #[cfg(not(miri))]
+    fn add_formula() {
+        let mut sat: Solver = Solver::new();
+        sat.add_formula(&generators::random_ksat(50, 100, 3, 1));
+        assert_eq!(sat.max_variable(), 50);
+        assert_eq!(sat.solve(), Some(true));
+
+        let formula: CnfFormula = vec![vec![1], vec![-1, 2], vec![-2]].into_iter().collect();
+        let mut sat: Solver = Solver::new();
+        sat.add_formula(&formula);
+        assert_eq!(sat.solve(), Some(false));
+    }
+
+    #[test]
+    #[cfg(not(miri))]
+    fn clause_builder() {
+        let mut sat: Solver = Solver::new();
+        let mut clause = ClauseBuilder::with_capacity(3);
+        clause.extend([1, 2, 3].iter().copied());
+        assert_eq!(clause.literals(), &[1, 2, 3]);
+        clause.commit(&mut sat);
+        assert!(clause.literals().is_empty());
+        for &lit in [-1, -2, -3].iter() {
+            clause.push(lit);
+            clause.commit(&mut sat);
+        }
+        clause.push(4);
+        clause.clear();
+        assert_eq!(sat.solve(), Some(false));
+    }
+
+    #[test]
+    #[cfg(not(miri))]
+    fn add_clause_slice() {
+        let mut sat: Solver = Solver::new();
+        let clause: Vec<i32> = vec![1, 2, 3];
+        sat.add_clause_slice(&clause);
+        sat.add_clause_slice(&[-1]);
+        sat.add_clause_slice(&[-1, -2][1..]);
+        sat.push();
+        sat.add_clause_slice([-3].as_ref());
+        assert_eq!(sat.solve(), Some(false));
+        sat.pop().unwrap();
+        assert_eq!(sat.solve(), Some(true));
+        assert_eq!(sat.value(3), Some(true));
+
+        let mut builder = ClauseBuilder::new();
+        builder.extend([-3, 5]);
+        assert_eq!(builder.as_clause(), &[-3, 5]);
+        sat.add_clause_slice(&builder);
+        assert_eq!(sat.solve_with([-5]), Some(false));
+    }
+
+    #[test]
+    #[cfg(not(miri))]
+    fn components() {
+        let mut formula = generators::pigeon_hole(2);
+        let offset = formula.max_variable();
+        for clause in generators::random_ksat(20, 40, 3, 5).iter() {
+            formula.add_clause(clause.iter().map(|&lit| lit + offset * lit.signum()));
+        }
+        formula.add_clause([40, 41]);
+        assert!(formula.components().len() >= 3);
+        match formula.solve_components(4) {
+            ComponentResult::Unsatisfiable(component) => {
+                assert_eq!(component, generators::pigeon_hole(2));
+            }
+            result => panic!("unexpected result {:?}", result),
+        }
+
+        let formula = generators::random_ksat(30, 60, 3, 9);
+        let mut sat: Solver = Solver::new();
+        sat.add_formula(&formula);
+        assert_eq!(sat.solve(), Some(true));
+        match formula.solve_components(0) {
+            ComponentResult::Satisfiable(model) => {
+                assert_eq!(model.len(), 30);
+                assert!(formula
+                    .iter()
+                    .all(|clause| clause.iter().any(|lit| model.contains(lit))));
+            }
+            result => panic!("unexpected result {:?}", result),
+        }
+    }
+
+    #[test]
+    fn remove_redundant() {
+        let mut formula = CnfFormula::new();
+        formula.add_clause([1, 2, 3]);
+        formula.add_clause([3, 2, 1, 2]);
+        formula.add_clause([-2, 4]);
+        formula.add_clause([4, -2]);
+        formula.add_clause([5, -2, 6, 4]);
+        formula.add_clause([7]);
+        formula.add_clause([-7, 1]);
+        formula.add_clause([7, 8]);
+        formula.add_clause([1, -1, 2]);
+        formula.add_clause([9, 3, -9]);
+        formula.add_clause([]);
+        formula.add_clause([]);
+        formula.reserve(10);
+        assert_eq!(formula.remove_redundant(), 7);
+        assert_eq!(formula.max_variable(), 10);
+        let clauses: Vec<&[i32]> = formula.iter().collect();
+        assert_eq!(clauses, vec![&[1, 2, 3][..], &[-2, 4], &[7], &[-7, 1], &[]]);
+        assert_eq!(formula.remove_redundant(), 0);
+    }
+
+    #[test]
+    #[cfg(all(target_has_atomic = "64", not(miri)))]
+    fn live_stats() {
+        use std::sync::atomic::AtomicBool;
+        let mut sat: Solver = Solver::new();
+        let stats = sat.live_stats();
+        assert_eq!(stats.snapshot(), StatsSnapshot::default());
+        let done = Arc::new(AtomicBool::new(false));
+        let monitor = {
+            let done = done.clone();
+            std::thread::spawn(move || {
+                let mut last = StatsSnapshot::default();
+                while !done.load(Ordering::Relaxed) {
+                    let snapshot = stats.snapshot();
+                    assert!(snapshot.conflicts >= last.conflicts);
+                    assert!(snapshot.decisions >= last.decisions);
+                    last = snapshot;
+                }
+            })
+        };
+        sat.add_formula(&generators::pigeon_hole(6));
+        assert_eq!(sat.solve(), Some(false));
+        done.store(true, Ordering::Relaxed);
+        monitor.join().unwrap();
+        let snapshot = sat.live_stats().snapshot();
+        assert!(snapshot.conflicts > 0 && snapshot.decisions > 0);
+    }
+
+    #[test]
+    #[cfg(not(miri))]
+    fn fork() {
+        let mut base: Solver = Solver::new();
+        base.add_formula(&generators::pigeon_hole(3));
+        base.add_clause([13, 14]);
+        base.push();
+        base.add_clause([-13]);
+        base.assume_sticky([16]);
+        base.simplify(1);
+
+        let mut sat: Solver = base.fork();
+        assert_eq!(sat.scope_depth(), 1);
+        assert_eq!(sat.sticky_assumptions(), &[16]);
+        assert_eq!(sat.solve(), Some(false));
+
+        let mut base: Solver = Solver::new();
+        base.add_clause([1, 2]);
+        base.add_clause([-1, 3]);
+        base.push();
+        base.add_clause([-3]);
+        let mut sat: Solver = base.fork();
+        assert_eq!(sat.solve(), Some(true));
+        assert_eq!(sat.value(2), Some(true));
+        sat.pop().unwrap();
+        assert_eq!(sat.solve_with([1]), Some(true));
+        assert_eq!(base.solve_with([1]), Some(false));
+    }
+
+    #[test]
+    #[cfg(not(miri))]
+    fn solve_in_background() {
+        let mut sat: Solver = Solver::new();
+        sat.add_formula(&generators::pigeon_hole(12));
+        let handle = sat.solve_in_background(None);
+        let dropper = thread::spawn(move || drop(handle));
+        dropper.join().unwrap();
+
+        let mut sat: Solver = Solver::new();
+        sat.add_clause([1, 2]);
+        sat.add_clause([3]);
+        let handle = sat.solve_in_background([-3]);
+        let (sat, result) = handle.join();
+        assert_eq!(result, Some(false));
+        assert!(sat.failed(-3));
+
+        let handle = sat.solve_in_background(None);
+        while !handle.is_finished() {
+            thread::yield_now();
+        }
+        handle.terminate();
+        let (mut sat, result) = handle.join();
+        assert_eq!(result, Some(true));
+        assert_eq!(sat.solve(), Some(true));
+    }
+
+    #[test]
+    #[cfg(all(feature = "diagnostics", not(miri)))]
+    fn diagnostics() {
+        let mut sat: Solver = Solver::new();
+        let id = sat.diagnostics_id();
+        assert!(diagnostics::live_solver_count() >= 1);
+        sat.add_clause([1, 2]);
+        sat.add_clause([-1, 3]);
+        assert_eq!(sat.solve(), Some(true));
+        let info = diagnostics::live_solvers()
+            .into_iter()
+            .find(|info| info.id == id);
+        let info = info.unwrap();
+        assert_eq!((info.variables, info.clauses), (3, 2));
+        assert!(info.memory > 0 && info.age() < Duration::from_secs(60));
+
+        let other: Solver = Solver::new();
+        assert_ne!(other.diagnostics_id(), id);
+        drop(sat);
+        let solvers = diagnostics::live_solvers();
+        assert!(solvers.iter().all(|info| info.id != id));
+        assert!(solvers.iter().any(|info| info.id == other.diagnostics_id()));
+    }
+
+    #[test]
+    fn native_strings() {
+        let sat: Solver = Solver::new();
+        assert_eq!(sat.signature().as_bytes(), sat.signature_bytes());
+
+        let err = Error::from_bytes(b"line 3: bad \xff byte");
+        assert_eq!(err.msg, "line 3: bad \u{fffd} byte");
+        assert_eq!(err.as_bytes(), b"line 3: bad \xff byte");
+        assert_eq!(err.raw.as_deref(), Some(err.as_bytes()));
+        assert_eq!(Error::from_bytes(b"bad byte"), Error::new("bad byte"));
+        assert_eq!(Error::new("bad byte").as_bytes(), b"bad byte");
+    }
+
+    #[test]
+    #[cfg(not(miri))]
+    fn wide_counts() {
+        let mut sat: Solver = Solver::new();
+        sat.add_clause([1, -3]);
+        sat.add_clause([2, 3]);
+        assert_eq!(sat.num_variables_u64(), 3);
+        assert_eq!(sat.num_clauses_u64(), 2);
+        assert_eq!(sat.try_num_clauses(), Ok(2));
+        assert_eq!(sat.try_new_variable().unwrap(), 4);
+        assert_eq!(sat.new_variable(), 5);
+        assert_eq!(sat.max_variable(), 5);
+
+        assert_eq!(
+            narrow::<u64, i32>(1 << 31),
+            Err(Error::new("count 2147483648 out of range"))
+        );
+        assert_eq!(
+            narrow::<i64, u8>(-1),
+            Err(Error::new("count -1 out of range"))
+        );
+        let budget = Budget {
+            conflicts: Some(u64::MAX),
+            decisions: Some(u64::MAX),
+            propagations: Some(u64::MAX),
+            ..Default::default()
+        };
+        assert_eq!(sat.solve_budgeted(&budget), Some(true));
+    }
+
+    #[test]
+    #[cfg(all(feature = "verify", not(miri)))]
+    fn verify_model() {
+        let mut sat: Solver = Solver::new();
+        sat.add_clause([1, 2, 3]);
+        sat.push();
+        sat.add_clause([-1]);
+        sat.add_clause_slice(&[-2]);
+        assert_eq!(sat.solve(), Some(true));
+        assert_eq!(sat.verify_model(), Ok(()));
+        sat.pop().unwrap();
+        assert_eq!(sat.solve_with([-3]), Some(true));
+        assert_eq!(sat.verify_model(), Ok(()));
+        assert!(sat.num_recorded_clauses() >= 4);
+
+        // a clause that CaDiCaL never saw is reported as violated
+        let blocking: Vec<i32> = (1..=3)
+            .map(|var| {
+                if sat.value(var) == Some(true) {
+                    -var
+                } else {
+                    var
+                }
+            })
+            .collect();
+        sat.shadow.push(&[1, 2]);
+        sat.shadow.push(&blocking);
+        assert_eq!(sat.verify_model(), Err(blocking));
+        sat.reset();
+        assert_eq!(sat.num_recorded_clauses(), 0);
+    }
+
+    #[test]
+    #[cfg(not(miri))]
+    fn unsat_check() {
+        let mut sat: Solver = Solver::new();
+        sat.set_unsat_check(true).unwrap();
+        assert!(sat.unsat_check());
+        sat.add_formula(&generators::pigeon_hole(4));
+        assert_eq!(sat.solve(), Some(false));
+
+        let mut sat: Solver = Solver::new();
+        sat.set_unsat_check(true).unwrap();
+        sat.add_clause([1, 2]);
+        sat.push();
+        sat.add_clause([-1]);
+        assert_eq!(sat.solve_with([-2]), Some(false));
+        sat.constrain([-2]);
+        assert_eq!(sat.solve(), Some(false));
+        sat.pop().unwrap();
+        assert_eq!(sat.solve_with([-2]), Some(true));
+        assert!(sat.read_dimacs(Path::new("unused.cnf")).is_err());
+
+        // the check fails if the recorded clauses are satisfiable
+        sat.add_clause([-1]);
+        sat.add_clause([-2]);
+        sat.unsat_check = Some(Default::default());
+        assert_eq!(sat.solve(), None);
+        assert_eq!(sat.status(), Some(false));
+    }
+
+    #[test]
+    fn proof_checker() {
+        // the clauses of x1 = x2 = x3 with x1 and -x3, and a padding clause
+        let originals = [vec![-1, 2], vec![-2, 3], vec![1], vec![-3], vec![4, 5]];
+        let proof = [b'a', 4, 0, b'd', 10, 13, 0, b'a', 0];
+        let steps = proof::parse_binary_drat(&proof).unwrap();
+        assert_eq!(
+            steps,
+            vec![
+                (0, true, vec![2]),
+                (3, false, vec![5, -6]),
+                (7, true, vec![])
+            ]
+        );
+        assert!(proof::parse_binary_drat(&[b'a', 4]).is_err());
+        assert!(proof::parse_binary_drat(&[b'x', 0]).is_err());
+
+        let mut checker = proof::Checker::new();
+        for (index, clause) in originals.iter().enumerate() {
+            checker.add_original(clause, index);
+        }
+        for (_, added, clause) in steps.iter() {
+            checker.add_step(*added, clause);
+        }
+        checker.verify(&[], None).unwrap();
+        assert_eq!(checker.core(), vec![0, 1, 2, 3]);
+
+        // the refutation can use assumptions and the constraint
+        let mut checker = proof::Checker::new();
+        for (index, clause) in originals.iter().enumerate().skip(1) {
+            checker.add_original(clause, index);
+        }
+        checker.verify(&[-5], Some(&[2])).unwrap();
+        assert_eq!(checker.core(), vec![1, 3]);
+
+        // a lemma that does not follow by unit propagation is rejected
+        let mut checker = proof::Checker::new();
+        for (index, clause) in originals[..2].iter().enumerate() {
+            checker.add_original(clause, index);
+        }
+        checker.add_step(true, &[3]);
+        checker.add_step(true, &[-3]);
+        assert!(checker.verify(&[], None).is_err());
+
+        // a lemma is checked without the original clauses added after it
+        let mut checker = proof::Checker::new();
+        checker.add_original(&[1], 0);
+        checker.add_step(true, &[3]);
+        checker.add_original(&[-1, 3], 1);
+        checker.add_original(&[-3], 2);
+        checker.add_step(true, &[]);
+        assert!(checker.verify(&[], None).is_err());
+        let mut checker = proof::Checker::new();
+        checker.add_original(&[1], 0);
+        checker.add_original(&[-1, 3], 1);
+        checker.add_step(true, &[3]);
+        checker.add_original(&[-3], 2);
+        checker.add_step(true, &[]);
+        checker.verify(&[], None).unwrap();
+        assert_eq!(checker.core(), vec![0, 1, 2]);
+
+        // an original clause added again after its deletion is used instead
+        let mut checker = proof::Checker::new();
+        checker.add_original(&[1, 2, 3], 0);
+        checker.add_original(&[-2], 1);
+        checker.add_step(false, &[1, 2, 3]);
+        checker.add_original(&[1, 2, 3], 2);
+        checker.add_original(&[-1], 3);
+        checker.add_original(&[-3], 4);
+        checker.add_step(true, &[]);
+        checker.verify(&[], None).unwrap();
+        assert_eq!(checker.core(), vec![1, 2, 3, 4]);
+    }
+
+    #[test]
+    #[cfg(not(miri))]
+    fn unsat_core_cnf() {
+        let mut sat: Solver = Solver::new();
+        assert!(sat.unsat_core_cnf().is_err());
+        sat.enable_proof_logging().unwrap();
+        assert!(sat.proof_logging());
+        let formula = generators::pigeon_hole(5);
+        sat.add_formula(&formula);
+        let extra = generators::random_ksat(10, 30, 3, 7);
+        for clause in extra.iter() {
+            sat.add_clause(clause.iter().map(|&lit| lit.signum() * (lit.abs() + 100)));
+        }
+        assert_eq!(sat.solve(), Some(false));
+        let core = sat.unsat_core_cnf().unwrap();
+        assert!(core.len() <= formula.len());
+        assert!(core
+            .iter()
+            .all(|clause| clause.iter().all(|&lit| lit.abs() <= 100)));
+        let mut check: Solver = Solver::new();
+        check.add_formula(&core);
+        assert_eq!(check.solve(), Some(false));
+        assert!(sat.read_dimacs(Path::new("unused.cnf")).is_err());
+
+        // the core is relative to the failed assumptions and the constraint,
+        // and it does not contain activation literals
+        sat.reset();
+        assert!(sat.proof_logging());
+        sat.add_clause([1, 2, 3]);
+        sat.add_clause([4, 5]);
+        sat.push();
+        sat.add_clause([-1]);
+        sat.add_clause([-4]);
+        sat.constrain([2, 4]);
+        assert_eq!(sat.solve_with([-2]), Some(false));
+        let core = sat.unsat_core_cnf().unwrap();
+        assert_eq!(core.iter().collect::<Vec<_>>(), vec![&[-4][..]]);
+        assert_eq!(sat.solve_with([-3]), Some(true));
+        assert!(sat.unsat_core_cnf().is_err());
+        assert_eq!(sat.solve_with([-2, -3]), Some(false));
+        let core = sat.unsat_core_cnf().unwrap();
+        assert_eq!(core.len(), 2);
+        assert!(core.iter().all(|clause| !sat.is_selector(clause[0])));
+
+        let mut sat: Solver = Solver::new();
+        sat.add_clause([1]);
+        assert!(sat.enable_proof_logging().is_err());
+    }
+
+    #[test]
+    #[cfg(not(miri))]
+    fn unsat_core_cnf_incremental() {
+        // the clauses are added in batches between solve calls, so the
+        // proof of each call is checked against the clauses added before it
+        let mut count = 0;
+        for seed in 0..10 {
+            let formula = generators::random_ksat(30, 160, 3, seed);
+            let mut sat: Solver = Solver::new();
+            sat.enable_proof_logging().unwrap();
+            let mut added = CnfFormula::new();
+            for (index, clause) in formula.iter().enumerate() {
+                sat.add_clause(clause.iter().copied());
+                added.add_clause(clause.to_vec());
+                if index % 20 != 19 {
+                    continue;
+                }
+                let result = sat.solve_with([1 + (index as i32 / 20)]);
+                if result != Some(false) {
+                    continue;
+                }
+                count += 1;
+                let core = sat.unsat_core_cnf().unwrap();
+                assert!(core
+                    .iter()
+                    .all(|clause| added.iter().any(|other| other == clause)));
+                let mut check: Solver = Solver::new();
+                check.add_formula(&core);
+                let failed: Vec<i32> = (1..=8).filter(|&lit| sat.failed(lit)).collect();
+                assert_eq!(check.solve_with(failed), Some(false));
+            }
+        }
+        assert!(count >= 10);
+    }
+
+    #[test]
+    #[cfg(not(miri))]
+    fn typestate() {
+        use typestate::Outcome;
+        let mut sat: typestate::Solver = typestate::Solver::new();
+        sat.add_clause([1, 2]);
+        sat.add_clause([-1, 2]);
+        let mut sat = match sat.solve_with([-2]) {
+            Outcome::Unsat(sat) => {
+                assert!(sat.failed(-2));
+                sat.resume()
+            }
+            _ => panic!(),
+        };
+        sat.constrain([-2]);
+        let sat = match sat.solve() {
+            Outcome::Unsat(sat) => {
+                assert!(sat.constraint_failed());
+                sat.resume()
+            }
+            _ => panic!(),
+        };
+        let sat = match sat.solve() {
+            Outcome::Sat(sat) => {
+                assert_eq!(sat.value(2), Some(true));
+                assert_eq!(sat.model()[1], 2);
+                sat
+            }
+            _ => panic!(),
+        };
+
+        let mut sat: typestate::Solver = sat.into_inner().fork::<Timeout>().into();
+        sat.add_clause([-2]);
+        assert!(matches!(sat.solve(), Outcome::Unsat(_)));
+    }
 
-impl<C: Callbacks> Drop for Solver<C> {
-    fn drop(&mut self) {
-        unsafe { ccadical_release(self.ptr) };
+    #[test]
+    #[cfg(not(miri))]
+    fn checked_queries() {
+        let mut sat: Solver = Solver::new();
+        sat.add_clause([1, 2]);
+        assert_eq!(sat.try_value(1).unwrap_err().msg, "invalid state");
+        assert!(sat.try_failed(1).is_err());
+        assert_eq!(sat.solve_with([-1]), Some(true));
+        assert_eq!(sat.try_value(2).unwrap(), Some(true));
+        assert_eq!(sat.try_value(i32::MIN).unwrap_err().msg, "invalid literal");
+        assert!(sat.try_constraint_failed().is_err());
+        sat.constrain([-2]);
+        assert_eq!(sat.solve_with([-1]), Some(false));
+        assert!(sat.try_value(2).is_err());
+        assert!(sat.try_failed(-1).unwrap());
+        assert!(sat.try_constraint_failed().unwrap());
+        assert!(sat.try_failed(0).is_err());
     }
-}
 
-/// CaDiCaL does not use thread local variables, so it is possible to
-/// move it between threads. However it cannot be used concurrently
-/// (for example getting the value from multiple threads at once), so we
-/// do not implement `Sync`.
-unsafe impl<C: Callbacks + Send> Send for Solver<C> {}
+    #[test]
+    #[cfg(not(miri))]
+    fn shared_solver() {
+        let shared: Arc<SharedSolver> = Arc::new(SharedSolver::new(Solver::new()));
+        shared.with(|sat| sat.add_formula(&generators::pigeon_hole(10)));
+        let solver = Arc::clone(&shared);
+        let handle = thread::spawn(move || solver.solve());
+        thread::sleep(Duration::from_millis(50));
+        shared.terminate();
+        assert_eq!(handle.join().unwrap(), None);
+        assert_eq!(shared.model(), None);
 
-/// Callbacks trait for finer control.
-pub trait Callbacks {
-    /// Called when the `solve` method is called.
-    #[inline(always)]
-    fn started(&mut self) {}
+        let shared: Arc<SharedSolver> = Arc::new(SharedSolver::new(Solver::new()));
+        shared.add_clause([1, -2]);
+        shared.add_clause([2, 3]);
+        assert_eq!(shared.solve_with([-1]), Some(true));
+        let readers: Vec<_> = (0..4)
+            .map(|_| {
+                let shared = Arc::clone(&shared);
+                thread::spawn(move || (shared.value(-2), shared.value(3), shared.value(4)))
+            })
+            .collect();
+        for reader in readers {
+            assert_eq!(reader.join().unwrap(), (Some(true), Some(true), None));
+        }
+        shared.add_clause([-3]);
+        assert_eq!(shared.value(3), None);
+        assert_eq!(shared.solve_with([-1]), Some(false));
+        assert!(shared.with(|sat| sat.failed(-1)));
+        let shared = Arc::try_unwrap(shared).ok().unwrap();
+        assert_eq!(shared.into_inner().max_variable(), 3);
+    }
 
-    /// Called by the solver periodically to check if it should terminate.
-    #[inline(always)]
-    fn terminate(&mut self) -> bool {
-        false
+    #[test]
+    #[cfg(all(feature = "isolated", not(miri)))]
+    fn isolated() {
+        let input = "o seed 5\no seed x\nc 1 2\nc -1 2\ns -2\ns\n";
+        let mut output = Vec::new();
+        isolated::serve(input.as_bytes(), &mut output).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines[..3], ["ok", "err invalid value", "r 20 -2"]);
+        let mut words = lines[3].split_whitespace();
+        assert_eq!((words.next(), words.next()), (Some("r"), Some("10")));
+        let model: Vec<i32> = words.map(|word| word.parse().unwrap()).collect();
+        assert!([[1, 2], [-1, 2]]
+            .iter()
+            .all(|clause| clause.iter().any(|lit| model.contains(lit))));
+        assert!(isolated::serve("c 1 0\n".as_bytes(), Vec::new()).is_err());
+
+        let mut sat = IsolatedSolver::new("/nonexistent/cadical-worker", &[]);
+        sat.add_clause([1]);
+        assert!(sat.solve().is_err());
+        assert_eq!(sat.status(), None);
+        assert_eq!(sat.max_variable(), 1);
+
+        // a worker that exits immediately is reported as crashed
+        #[cfg(unix)]
+        {
+            let mut sat = IsolatedSolver::new("true", &[]);
+            sat.add_clause([1, 2]);
+            assert_eq!(sat.solve().unwrap_err().msg, "worker crashed");
+            assert_eq!(sat.restarts(), 1);
+            assert!(sat.solve().is_err());
+            assert_eq!(sat.restarts(), 2);
+        }
     }
 
-    /// Returns the maximum length of clauses to be passed to `learn`. This
-    /// method will be called once at the start of each `solve` call.
-    #[inline(always)]
-    fn max_length(&self) -> i32 {
-        0
+    #[test]
+    #[cfg(not(miri))]
+    fn out_of_memory() {
+        let mut sat: Solver = Solver::new();
+        sat.try_add_clause([1, 2]).unwrap();
+        assert_eq!(sat.try_solve().unwrap(), Some(true));
+        assert!(!sat.out_of_memory());
+
+        sat.out_of_memory = true;
+        assert_eq!(sat.try_add_clause([-1]).unwrap_err().msg, "out of memory");
+        assert_eq!(sat.solve(), None);
+        assert!(sat.try_solve().is_err());
+        sat.reset();
+        assert_eq!(sat.try_solve().unwrap(), Some(true));
     }
 
-    /// Called by the solver when a new derived clause is learnt.
-    #[allow(unused_variables)]
-    #[inline(always)]
-    fn learn(&mut self, clause: &[i32]) {}
-}
+    #[test]
+    #[cfg(not(miri))]
+    fn callback_panic() {
+        struct Panics {
+            armed: bool,
+        }
 
-/// Callbacks implementing a simple timeout.
-pub struct Timeout {
-    pub started: Instant,
-    pub timeout: f32,
-}
+        impl Callbacks for Panics {
+            fn terminate(&mut self) -> bool {
+                if self.armed {
+                    panic!("stop here");
+                }
+                false
+            }
+        }
 
-impl Timeout {
-    /// Creates a new timeout structure with the given timeout value.
-    pub fn new(timeout: f32) -> Self {
-        Timeout {
-            started: Instant::now(),
-            timeout,
+        let mut sat: Solver<Panics> = Solver::new();
+        sat.add_formula(&generators::pigeon_hole(5));
+        sat.set_callbacks(Some(Panics { armed: true }));
+        let result = panic::catch_unwind(AssertUnwindSafe(|| sat.solve()));
+        let payload = result.unwrap_err();
+        assert_eq!(payload.downcast_ref::<&str>(), Some(&"stop here"));
+
+        let cbs = sat.get_callbacks().unwrap();
+        cbs.armed = false;
+        assert_eq!(sat.solve(), Some(false));
+    }
+
+    #[test]
+    #[cfg(not(miri))]
+    fn diversified_configs() {
+        let list = configs::diversified(20, 7);
+        assert_eq!(list.len(), 20);
+        for (i, config) in list.iter().enumerate() {
+            assert!(list[..i].iter().all(|other| other != config));
+            let mut sat: Solver = config.build().unwrap();
+            assert_eq!(sat.get_option("seed"), 7 + i as i32);
+            sat.add_formula(&generators::pigeon_hole(3));
+            assert_eq!(sat.solve(), Some(false));
         }
     }
-}
 
-impl Callbacks for Timeout {
-    #[inline(always)]
-    fn started(&mut self) {
-        self.started = Instant::now();
+    #[test]
+    #[cfg(not(miri))]
+    fn simplified_formula() {
+        let formulas = [
+            generators::random_ksat(40, 120, 3, 2),
+            generators::pigeon_hole(4),
+        ];
+        for formula in formulas.iter() {
+            let mut sat: Solver = Solver::new();
+            sat.add_formula(formula);
+            let simplified = sat.simplified_formula();
+            assert!(simplified.max_variable() >= formula.max_variable());
+            let mut other: Solver = Solver::new();
+            other.add_formula(&simplified);
+            assert_eq!(other.solve(), sat.solve());
+        }
+
+        // the clauses of active scopes are returned without their
+        // activation literals, and those of closed scopes are left out
+        let mut sat: Solver = Solver::new();
+        sat.add_clause([1, 2, 3]);
+        sat.push();
+        sat.add_clause([-1, 2]);
+        sat.push();
+        sat.add_clause([-2, 3]);
+        sat.pop().unwrap();
+        let simplified = sat.simplified_formula();
+        assert!(simplified
+            .iter()
+            .all(|clause| clause.iter().all(|&lit| !sat.is_selector(lit))));
+        assert!(simplified.iter().any(|clause| clause == [-1, 2]));
+        assert!(simplified.iter().all(|clause| clause != [-2, 3]));
+        assert_eq!(sat.scope_depth(), 1);
+        assert_eq!(sat.solve_with([1, -2]), Some(false));
     }
 
-    #[inline(always)]
-    fn terminate(&mut self) -> bool {
-        self.started.elapsed().as_secs_f32() >= self.timeout
+    #[test]
+    #[cfg(not(miri))]
+    fn interrupt_flag() {
+        let mut sat = pigeon_hole(9);
+        let flag = sat.interrupt_flag();
+        let handle = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(100));
+            flag.store(true, Ordering::Relaxed);
+        });
+        assert_eq!(sat.solve(), None);
+        handle.join().unwrap();
+        assert!(!sat.interrupt_flag().load(Ordering::Relaxed));
     }
-}
 
-/// Error type for configuration and DIMACS reading and writing errors.
-#[derive(Clone, Debug, PartialEq, Eq)]
-pub struct Error {
-    pub msg: String,
-}
+    #[test]
+    #[cfg(not(miri))]
+    fn dimacs_log() {
+        let path = std::env::temp_dir().join(format!("cadical-log-{}.icnf", std::process::id()));
+        std::fs::remove_file(&path).ok();
+        let mut sat: Solver = Solver::new();
+        sat.add_clause([1, 2]);
+        sat.set_dimacs_log(&path).unwrap();
+        sat.add_clause([-1, 3]);
+        sat.push();
+        sat.add_clause([-3]);
+        assert_eq!(sat.solve_with([2]), Some(true));
+        sat.flush_dimacs_log().unwrap();
+        sat.pop().unwrap();
+        sat.assume(-2);
+        sat.clear_assumptions();
+        assert_eq!(sat.solve(), Some(true));
+        drop(sat);
+        let text = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(text, "p inccnf\n-1 3 0\n-3 -4 0\na 2 4 0\n-4 0\na 0\n");
 
-impl Error {
-    pub fn new(msg: &str) -> Self {
-        Error {
-            msg: msg.to_string(),
+        let mut sat: Solver = Solver::new();
+        sat.set_dimacs_log(&path).unwrap();
+        sat.add_clause([5]);
+        sat.close_dimacs_log().unwrap();
+        sat.add_clause([6]);
+        drop(sat);
+        let text = std::fs::read_to_string(&path).unwrap();
+        assert!(text.ends_with("a 0\n5 0\n"));
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    #[cfg(not(miri))]
+    fn assume_all() {
+        let mut sat: Solver = Solver::new();
+        sat.add_clause(1..=200);
+        let lits: Vec<i32> = (1..=200).map(|var| -var).collect();
+        sat.assume_all(&lits);
+        assert_eq!(sat.solve(), Some(false));
+        assert!(sat.failed(-200));
+        assert_eq!(sat.solve_with(lits[1..].iter().copied()), Some(true));
+        assert_eq!(sat.value(1), Some(true));
+    }
+
+    #[test]
+    #[cfg(not(miri))]
+    fn add_clause_unchecked() {
+        let mut sat: Solver = Solver::new();
+        unsafe { sat.add_clause_unchecked(&[1, 2]) };
+        sat.push();
+        unsafe { sat.add_clause_unchecked(&[-1]) };
+        unsafe { sat.add_clause_unchecked(&[-2]) };
+        assert_eq!(sat.solve(), Some(false));
+        sat.pop().unwrap();
+        assert_eq!(sat.solve(), Some(true));
+        unsafe { sat.add_clause_unchecked(&[]) };
+        assert_eq!(sat.solve(), Some(false));
+    }
+
+    #[test]
+    fn clause_store() {
+        let mut store = ClauseStore::new();
+        store.push(&[1, -2, i32::MAX]);
+        store.push(&[]);
+        store.push(&[-i32::MAX, 3]);
+        assert_eq!(store.len(), 3);
+        assert_eq!(store.num_literals(), 5);
+        assert_eq!(store.clause(0).collect::<Vec<_>>(), vec![1, -2, i32::MAX]);
+        assert_eq!(store.clause(1).count(), 0);
+        assert_eq!(store.clause(2).collect::<Vec<_>>(), vec![-i32::MAX, 3]);
+        assert_eq!(store.find_violated(|lit| lit > 0), Some(1));
+        store.clear();
+        assert!(store.is_empty());
+        assert_eq!(store.find_violated(|_| false), None);
+    }
+
+    #[test]
+    fn parse_dimacs() {
+        let text = "c comment\np cnf 5 3\n1 -2 0 3\n4 0\n\n-5 0\n%\n0\n";
+        let formula = CnfFormula::parse_dimacs(text).unwrap();
+        assert_eq!(formula.max_variable(), 5);
+        assert_eq!(formula.len(), 3);
+        assert_eq!(formula.clause(1), &[3, 4]);
+
+        assert!(CnfFormula::parse_dimacs("1 2").is_err());
+        assert!(CnfFormula::parse_dimacs("1 x 0").is_err());
+        assert!(CnfFormula::parse_dimacs("p dnf 1 1\n").is_err());
+    }
+
+    #[test]
+    fn dimacs_diagnostics() {
+        let err = CnfFormula::parse_dimacs_detailed("c x\n  p dnf 1 1\n").unwrap_err();
+        assert_eq!(err.kind, DimacsErrorKind::InvalidHeader);
+        assert_eq!((err.line, err.column), (2, 3));
+
+        let err = CnfFormula::parse_dimacs_detailed("1 2 0\n-1  2.5 0\n").unwrap_err();
+        assert_eq!(err.kind, DimacsErrorKind::InvalidLiteral);
+        assert_eq!((err.line, err.column), (2, 5));
+        assert_eq!(err.token.as_deref(), Some("2.5"));
+        assert_eq!(
+            err.to_string(),
+            "line 2, column 5: invalid literal `2.5` \
+             (literals must be integers separated by whitespace)"
+        );
+
+        let err = CnfFormula::parse_dimacs_detailed("1 -2147483648 0\n").unwrap_err();
+        assert_eq!(err.kind, DimacsErrorKind::LiteralOutOfRange);
+        let err = CnfFormula::parse_dimacs_detailed("99999999999 0\n").unwrap_err();
+        assert_eq!(err.kind, DimacsErrorKind::LiteralOutOfRange);
+
+        let err = CnfFormula::parse_dimacs_detailed("p cnf 3 2\n1 2 0\n-3\n\n").unwrap_err();
+        assert_eq!(err.kind, DimacsErrorKind::UnterminatedClause);
+        assert_eq!(err.line, 3);
+        assert!(err.suggestion.unwrap().contains("claims 2 clauses"));
+        let err = CnfFormula::parse_dimacs("1 2").unwrap_err();
+        assert!(err.msg.contains("unterminated clause"));
+    }
+
+    #[test]
+    #[cfg(not(miri))]
+    fn read_dimacs_detailed() {
+        let path = std::env::temp_dir().join(format!("cadical-diag-{}.cnf", std::process::id()));
+        std::fs::write(&path, "p cnf 2 2\n1 2 0\n-1 y 0\n").unwrap();
+        let mut sat: Solver = Solver::new();
+        let err = match sat.read_dimacs_detailed(&path) {
+            Err(ReadDimacsError::Parse(err)) => err,
+            result => panic!("unexpected result {:?}", result),
+        };
+        assert_eq!(err.kind, DimacsErrorKind::InvalidLiteral);
+        assert_eq!((err.line, err.column), (3, 4));
+        assert_eq!(err.token.as_deref(), Some("y"));
+
+        std::fs::write(&path, "p cnf 2 2\n1 2 0\n-1 0\n").unwrap();
+        let mut sat: Solver = Solver::new();
+        assert_eq!(sat.read_dimacs_detailed(&path).unwrap(), 2);
+        std::fs::remove_file(&path).unwrap();
+
+        let mut sat: Solver = Solver::new();
+        match sat.read_dimacs_detailed(&path) {
+            Err(ReadDimacsError::Io(err)) => assert_eq!(err.kind(), std::io::ErrorKind::NotFound),
+            result => panic!("unexpected result {:?}", result),
         }
+        assert_eq!(sat.max_variable(), 0);
     }
-}
 
-impl fmt::Display for Error {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        self.msg.fmt(f)
+    #[test]
+    #[cfg(not(miri))]
+    fn dimacs_shards() {
+        let dir = std::env::temp_dir();
+        let formula = generators::pigeon_hole(5);
+        let mut paths = Vec::new();
+        for (index, chunk) in formula.iter().collect::<Vec<_>>().chunks(10).enumerate() {
+            let path = dir.join(format!(
+                "cadical-shard-{}-{}.cnf",
+                std::process::id(),
+                index
+            ));
+            let mut text = String::new();
+            for clause in chunk {
+                for lit in clause.iter() {
+                    text.push_str(&format!("{} ", lit));
+                }
+                text.push_str("0\n");
+            }
+            std::fs::write(&path, text).unwrap();
+            paths.push(path);
+        }
+        assert!(paths.len() > 2);
+
+        let mut sat: Solver = Solver::new();
+        sat.read_dimacs_shards(&paths, 2).unwrap();
+        assert_eq!(sat.max_variable(), 30);
+        assert_eq!(sat.solve(), Some(false));
+        let mut sat: Solver = Solver::new();
+        sat.read_dimacs_shards(&paths, 1).unwrap();
+        assert_eq!(sat.max_variable(), 30);
+
+        let mut sat: Solver = Solver::new();
+        paths.push(dir.join("cadical-missing-shard.cnf"));
+        assert!(sat.read_dimacs_shards(&paths, 0).is_err());
+        for path in paths.iter() {
+            std::fs::remove_file(path).ok();
+        }
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use std::thread;
+    #[test]
+    #[cfg(not(miri))]
+    fn pb_constraints() {
+        use encode::{PbConstraint, Relation};
+        let terms = vec![(3, 1), (-2, 2), (2, -3), (5, 4)];
+        for &relation in [Relation::AtMost, Relation::AtLeast, Relation::Equal].iter() {
+            for bound in -3..=11 {
+                let constraint = PbConstraint {
+                    terms: terms.clone(),
+                    relation,
+                    bound,
+                };
+                let mut sat: Solver = Solver::new();
+                sat.add_pb_constraint(&constraint);
+                for bits in 0..16 {
+                    let lits: Vec<i32> = (1..=4)
+                        .map(|var| {
+                            if bits & (1 << (var - 1)) != 0 {
+                                var
+                            } else {
+                                -var
+                            }
+                        })
+                        .collect();
+                    let expected = constraint.holds(|lit| lits.contains(&lit));
+                    assert_eq!(sat.solve_with(lits.iter().copied()), Some(expected));
+                }
+            }
+        }
+    }
 
     #[test]
     #[cfg(not(miri))]
-    fn solver() {
+    fn gates() {
         let mut sat: Solver = Solver::new();
-        assert!(sat.signature().starts_with("cadical-"));
-        assert_eq!(sat.status(), None);
-        sat.add_clause([1, 2]);
-        assert_eq!(sat.max_variable(), 2);
-        assert_eq!(sat.num_variables(), 2);
-        assert_eq!(sat.num_clauses(), 1);
+        let and = sat.and_gate([1, 2]);
+        let or = sat.or_gate([1, 2]);
+        let xor = sat.xor_gate(1, 2);
+        let vars = sat.max_variable();
+        assert_eq!(vars, 5);
+        for bits in 0..4 {
+            let a = if bits & 1 != 0 { 1 } else { -1 };
+            let b = if bits & 2 != 0 { 2 } else { -2 };
+            assert_eq!(sat.solve_with([a, b]), Some(true));
+            assert_eq!(sat.value(and), Some(a > 0 && b > 0));
+            assert_eq!(sat.value(or), Some(a > 0 || b > 0));
+            assert_eq!(sat.value(xor), Some((a > 0) != (b > 0)));
+        }
+        let t = sat.and_gate([]);
+        let f = sat.or_gate([]);
         assert_eq!(sat.solve(), Some(true));
-        assert_eq!(sat.solve_with([-1].iter().copied()), Some(true));
-        assert_eq!(sat.value(1), Some(false));
-        assert_eq!(sat.value(-1), Some(true));
-        assert_eq!(sat.value(2), Some(true));
-        assert_eq!(sat.value(-2), Some(false));
-        assert_eq!(sat.solve_with([-2].iter().copied()), Some(true));
-        assert_eq!(sat.value(1), Some(true));
-        assert_eq!(sat.value(2), Some(false));
-        assert_eq!(sat.solve_with([-1, -2].iter().copied()), Some(false));
-        assert_eq!(sat.failed(-1), true);
-        assert_eq!(sat.failed(-2), true);
-        assert_eq!(sat.status(), Some(false));
-        sat.add_clause([4, 5]);
-        assert_eq!(sat.status(), None);
-        assert_eq!(sat.max_variable(), 5);
-        assert_eq!(sat.num_variables(), 4);
-        assert_eq!(sat.num_clauses(), 2);
-        assert_eq!(sat.solve_with([-1, -2, -4].iter().copied()), Some(false));
-        assert_eq!(sat.failed(-1), true);
-        assert_eq!(sat.failed(-2), true);
-        assert_eq!(sat.failed(-4), false);
+        assert_eq!(sat.value(t), Some(true));
+        assert_eq!(sat.value(f), Some(false));
     }
 
-    fn pigeon_hole(num: i32) -> Solver {
+    #[test]
+    #[cfg(feature = "cpp-tracing")]
+    fn trace_api_calls() {
+        let path = std::env::temp_dir().join("cadical-test-api-trace.txt");
         let mut sat: Solver = Solver::new();
-        for i in 0..(num + 1) {
-            sat.add_clause((0..num).map(|j| 1 + i * num + j));
+        sat.trace_api_calls(&path).unwrap();
+        assert!(sat.is_tracing_api_calls());
+        assert!(sat.trace_api_calls(&path).is_err());
+        sat.add_clause([1, -2]);
+        assert_eq!(sat.solve_with([2]), Some(true));
+        sat.reset();
+        sat.add_clause([1]);
+        sat.add_clause([-1]);
+        assert_eq!(sat.solve(), Some(false));
+        assert!(sat
+            .fork::<Timeout>()
+            .trace_api_calls(&path.join("missing"))
+            .is_err());
+        drop(sat);
+        let trace = ApiTrace::parse(&std::fs::read_to_string(&path).unwrap());
+        assert_eq!(trace.calls[0], "init");
+        assert_eq!(trace.calls.iter().filter(|call| *call == "init").count(), 2);
+        std::fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    #[cfg(not(miri))]
+    fn graph_encoders() {
+        use encode::graph::Graph;
+        let mut petersen = Graph::new(10);
+        for i in 0..5 {
+            petersen.add_edge(i, (i + 1) % 5);
+            petersen.add_edge(i, i + 5);
+            petersen.add_edge(i + 5, (i + 2) % 5 + 5);
         }
-        for i1 in 0..(num + 1) {
-            for i2 in 0..(num + 1) {
-                if i1 == i2 {
-                    continue;
+        let matrix = petersen.adjacency();
+
+        for &(colors, result) in [(2, false), (3, true)].iter() {
+            let mut sat: Solver = Solver::new();
+            let coloring = sat.add_coloring(&petersen, colors);
+            assert_eq!(sat.solve(), Some(result));
+            if result {
+                let colors = coloring.decode(&sat);
+                for &(u, v) in petersen.edges.iter() {
+                    assert_ne!(colors[u], colors[v]);
                 }
-                for j in 0..num {
-                    let l1 = 1 + i1 * num + j;
-                    let l2 = 1 + i2 * num + j;
-                    sat.add_clause([-l1, -l2])
+            }
+        }
+
+        for &(size, result) in [(2, true), (3, false)].iter() {
+            let mut sat: Solver = Solver::new();
+            let clique = sat.add_clique(&petersen, size);
+            assert_eq!(sat.solve(), Some(result));
+            if result {
+                let vertices = clique.decode(&sat);
+                assert!(vertices.len() >= size);
+                for &u in vertices.iter() {
+                    for &v in vertices.iter() {
+                        assert!(u == v || matrix[u][v]);
+                    }
+                }
+            }
+        }
+
+        for &(size, result) in [(4, true), (5, false)].iter() {
+            let mut sat: Solver = Solver::new();
+            let set = sat.add_independent_set(&petersen, size);
+            assert_eq!(sat.solve(), Some(result));
+            if result {
+                let vertices = set.decode(&sat);
+                assert!(vertices.len() >= size);
+                for &u in vertices.iter() {
+                    for &v in vertices.iter() {
+                        assert!(!matrix[u][v]);
+                    }
+                }
+            }
+        }
+
+        for &(size, result) in [(6, true), (5, false)].iter() {
+            let mut sat: Solver = Solver::new();
+            let cover = sat.add_vertex_cover(&petersen, size);
+            assert_eq!(sat.solve(), Some(result));
+            if result {
+                let vertices = cover.decode(&sat);
+                assert!(vertices.len() <= size);
+                for &(u, v) in petersen.edges.iter() {
+                    assert!(vertices.contains(&u) || vertices.contains(&v));
+                }
+            }
+        }
+    }
+
+    #[test]
+    #[cfg(not(miri))]
+    fn algebra_encoders() {
+        let count = |size: usize, commutative: bool, iso: bool| {
+            let mut sat: Solver = Solver::new();
+            let op = sat.new_operation(size, 2);
+            sat.add_functional(&op);
+            sat.add_total(&op);
+            sat.add_associative(&op);
+            if commutative {
+                sat.add_commutative(&op);
+            }
+            let mut count = 0;
+            while sat.solve() == Some(true) {
+                let table = op.decode(&sat);
+                for a in 0..size {
+                    for b in 0..size {
+                        for c in 0..size {
+                            let ab = table[a * size + b];
+                            let bc = table[b * size + c];
+                            assert_eq!(table[ab * size + c], table[a * size + bc]);
+                        }
+                        if commutative {
+                            assert_eq!(table[a * size + b], table[b * size + a]);
+                        }
+                    }
+                }
+                if iso {
+                    sat.block_isomorphic(&op, &table, |_| true);
+                } else {
+                    sat.block_table(&op, &table);
+                }
+                count += 1;
+            }
+            count
+        };
+        assert_eq!(count(2, false, false), 8);
+        assert_eq!(count(3, false, true), 24);
+        assert_eq!(count(3, true, true), 12);
+
+        let mut sat: Solver = Solver::new();
+        let op = sat.new_operation(3, 1);
+        assert_eq!(op.tuples(), 3);
+        assert_eq!(op.permute_table(&[1, 2, 0], &[0, 2, 1]), vec![2, 0, 1]);
+        let rel = sat.new_relation(3, 2);
+        sat.add_clause([rel.var(&[1, 2])]);
+        sat.add_clause([op.var(&[0], 2)]);
+        sat.add_functional(&op);
+        assert_eq!(sat.solve(), Some(true));
+        assert!(rel.decode(&sat)[5]);
+        assert_eq!(op.decode(&sat)[0], 2);
+        assert_eq!(sat.value(op.values(&[0])[1]), Some(false));
+    }
+
+    #[test]
+    #[cfg(not(miri))]
+    fn latin_squares() {
+        use encode::latin::{LatinEncoding, PartialSquare};
+        let encodings = [
+            LatinEncoding::Minimal,
+            LatinEncoding::Extended,
+            LatinEncoding::Sequential,
+        ];
+        for &encoding in encodings.iter() {
+            let mut sat: Solver = Solver::new();
+            let op = sat.new_quasigroup(3, encoding);
+            let mut count = 0;
+            while sat.solve() == Some(true) {
+                let table = op.decode(&sat);
+                for a in 0..3 {
+                    let mut row: Vec<usize> = (0..3).map(|b| table[a * 3 + b]).collect();
+                    let mut column: Vec<usize> = (0..3).map(|b| table[b * 3 + a]).collect();
+                    row.sort_unstable();
+                    column.sort_unstable();
+                    assert_eq!(row, vec![0, 1, 2]);
+                    assert_eq!(column, vec![0, 1, 2]);
+                }
+                sat.block_table(&op, &table);
+                count += 1;
+            }
+            assert_eq!(count, 12);
+
+            let square = PartialSquare::parse("0 .\n. 1\n").unwrap();
+            let mut sat: Solver = Solver::new();
+            let op = sat.new_quasigroup(2, encoding);
+            sat.add_partial_square(&op, &square);
+            assert_eq!(sat.solve(), Some(false));
+        }
+
+        let square = PartialSquare::parse("0 . 2\n\n. . .\n. 1 .\n").unwrap();
+        assert_eq!(square.size, 3);
+        assert_eq!(square.cells[2], Some(2));
+        assert_eq!(square.cells[3], None);
+        assert!(PartialSquare::parse("0 .\n. 1 0\n").is_err());
+        assert!(PartialSquare::parse("0 . .\n.\n").is_err());
+        assert!(PartialSquare::parse("0 2\n. .\n").is_err());
+        assert!(PartialSquare::parse("0 x\n. .\n").is_err());
+    }
+
+    #[test]
+    #[cfg(not(miri))]
+    fn hamiltonian_encoders() {
+        use encode::graph::{Graph, HamiltonianEncoding};
+        let mut petersen = Graph::new(10);
+        let mut cube = Graph::new(8);
+        for i in 0..5 {
+            petersen.add_edge(i, (i + 1) % 5);
+            petersen.add_edge(i, i + 5);
+            petersen.add_edge(i + 5, (i + 2) % 5 + 5);
+        }
+        for u in 0..8 {
+            for &bit in [1, 2, 4].iter() {
+                if u & bit == 0 {
+                    cube.add_edge(u, u | bit);
+                }
+            }
+        }
+
+        let encodings = [HamiltonianEncoding::Position, HamiltonianEncoding::Ordering];
+        for &encoding in encodings.iter() {
+            for &symmetry_breaking in [false, true].iter() {
+                for &(graph, cycle, result) in [
+                    (&petersen, false, true),
+                    (&petersen, true, false),
+                    (&cube, true, true),
+                ]
+                .iter()
+                {
+                    let mut sat: Solver = Solver::new();
+                    let path = if cycle {
+                        sat.add_hamiltonian_cycle(graph, encoding, symmetry_breaking)
+                    } else {
+                        sat.add_hamiltonian_path(graph, encoding, symmetry_breaking)
+                    };
+                    assert_eq!(sat.solve(), Some(result));
+                    if !result {
+                        continue;
+                    }
+                    let matrix = graph.adjacency();
+                    let order = path.decode(&sat);
+                    let mut sorted = order.clone();
+                    sorted.sort_unstable();
+                    assert_eq!(sorted, (0..graph.vertices).collect::<Vec<_>>());
+                    for pair in order.windows(2) {
+                        assert!(matrix[pair[0]][pair[1]]);
+                    }
+                    let last = order[order.len() - 1];
+                    if cycle {
+                        assert!(matrix[last][order[0]]);
+                    }
+                    if symmetry_breaking && cycle {
+                        assert_eq!(order[0], 0);
+                        assert!(order[1] < last);
+                    } else if symmetry_breaking {
+                        assert!(order[0] < last);
+                    }
                 }
             }
         }
-        sat
+
+        let mut sat: Solver = Solver::new();
+        sat.add_hamiltonian_cycle(&Graph::new(2), HamiltonianEncoding::Position, false);
+        assert_eq!(sat.solve(), Some(false));
+        let mut sat: Solver = Solver::new();
+        let path = sat.add_hamiltonian_path(&Graph::new(1), HamiltonianEncoding::Ordering, true);
+        assert_eq!(sat.solve(), Some(true));
+        assert_eq!(path.decode(&sat), vec![0]);
     }
 
     #[test]
-    fn timeout() {
-        let mut sat = pigeon_hole(9);
-        let started = Instant::now();
-        sat.set_callbacks(Some(Timeout::new(0.2)));
-        let result = sat.solve();
-        assert_eq!(sat.get_callbacks().unwrap().timeout, 0.2);
-        let elapsed = started.elapsed().as_secs_f32();
-        if result == None {
-            assert!(0.1 < elapsed && elapsed < 0.3);
-        } else {
-            assert!(result == Some(false) && elapsed <= 0.3);
-        }
+    fn blif_parse() {
+        let text = ".model adder # half adder\n\
+                    .inputs a \\\n b\n\
+                    .outputs s c\n\
+                    .names a b s\n\
+                    11 0\n\
+                    00 0\n\
+                    .names a b c\n\
+                    11 1\n\
+                    .end\n";
+        let network = BlifNetwork::parse(text).unwrap();
+        assert_eq!(network.model, "adder");
+        assert_eq!(network.inputs, vec!["a", "b"]);
+        assert_eq!(network.outputs, vec!["s", "c"]);
+        assert_eq!(network.tables.len(), 2);
+        assert!(!network.tables[0].on_set);
+        assert_eq!(network.tables[0].cubes, vec!["11", "00"]);
 
-        let started = Instant::now();
-        sat.set_callbacks(Some(Timeout::new(0.5)));
-        let result = sat.solve();
-        let elapsed = started.elapsed().as_secs_f32();
-        if result == None {
-            assert!(0.4 < elapsed && elapsed < 0.6);
-        } else {
-            assert!(result == Some(false) && elapsed <= 0.6);
+        assert!(BlifNetwork::parse(".names a b\n1 1\n0 0\n").is_err());
+        assert!(BlifNetwork::parse(".names a b\n11 1\n").is_err());
+        assert!(BlifNetwork::parse(".subckt adder a=x\n").is_err());
+        assert!(BlifNetwork::parse("11 1\n").is_err());
+    }
+
+    #[test]
+    #[cfg(not(miri))]
+    fn blif_encode() {
+        let text = ".model adder\n\
+                    .inputs a b\n\
+                    .outputs s c\n\
+                    .names a b s\n\
+                    11 0\n\
+                    00 0\n\
+                    .names a b c\n\
+                    11 1\n\
+                    .names one\n\
+                    1\n\
+                    .end\n";
+        let network = BlifNetwork::parse(text).unwrap();
+        let mut sat: Solver = Solver::new();
+        let nets = sat.add_blif(&network).unwrap();
+        for bits in 0..4 {
+            let a = if bits & 1 != 0 { nets["a"] } else { -nets["a"] };
+            let b = if bits & 2 != 0 { nets["b"] } else { -nets["b"] };
+            assert_eq!(sat.solve_with([a, b]), Some(true));
+            assert_eq!(sat.value(nets["s"]), Some((a > 0) != (b > 0)));
+            assert_eq!(sat.value(nets["c"]), Some(a > 0 && b > 0));
+            assert_eq!(sat.value(nets["one"]), Some(true));
         }
 
-        sat.set_callbacks(None);
-        assert_eq!(sat.solve(), Some(false));
+        let mut sat: Solver = Solver::new();
+        let network = BlifNetwork::parse(".names a b\n1 1\n.names c b\n1 1\n").unwrap();
+        assert!(sat.add_blif(&network).is_err());
+        let network = BlifNetwork::parse(".outputs b\n.names a b\n1 1\n").unwrap();
+        assert!(sat.add_blif(&network).is_err());
+    }
+
+    #[test]
+    fn opb_parse() {
+        let text = "* #variable= 4 #constraint= 2\n\
+                    min: +2 x1 -1 x3 ;\n\
+                    +1 x1 +1 x2 +1 ~x3 >= 2 ;\n\
+                    -3 x2 +1 x1 = -2 ;\n";
+        let instance = OpbInstance::parse(text).unwrap();
+        assert_eq!(instance.variables, 4);
+        assert_eq!(instance.objective, Some(vec![(2, 1), (-1, 3)]));
+        assert_eq!(instance.constraints.len(), 2);
+        assert_eq!(instance.constraints[1].relation, encode::Relation::Equal);
+        assert_eq!(instance.constraints[1].bound, -2);
+
+        assert!(OpbInstance::parse("+1 x1 >= 1").is_err());
+        assert!(OpbInstance::parse("+1 x1 x2 >= 1 ;").is_err());
+        assert!(OpbInstance::parse("+1 y1 >= 1 ;").is_err());
+        assert!(OpbInstance::parse("+1 x1 > 1 ;").is_err());
+    }
+
+    #[test]
+    #[cfg(not(miri))]
+    fn minimize() {
+        let text = "min: +3 x1 +2 x2 +4 x3 +1 x4 ;\n\
+                    +1 x1 +1 x2 +1 x3 +1 x4 >= 2 ;\n\
+                    +1 x2 +1 x4 <= 1 ;\n";
+        let instance = OpbInstance::parse(text).unwrap();
+        let mut sat: Solver = Solver::new();
+        sat.add_opb(&instance);
+        let optimum = sat.minimize(instance.objective.as_ref().unwrap()).unwrap();
+        assert_eq!(optimum.cost, 4);
+        assert!(optimum.optimal);
+        assert!(instance
+            .constraints
+            .iter()
+            .all(|c| c.holds(|lit| optimum.model.contains(&lit))));
+        assert_eq!(sat.scope_depth(), 0);
+
+        sat.add_clause([-1]);
+        sat.add_clause([-3]);
+        assert_eq!(sat.minimize(&[(1, 2)]), None);
     }
 
     #[test]
@@ -624,6 +4469,625 @@ mod tests {
         println!("reading DIMACS error: {}", res.err().unwrap());
     }
 
+    #[test]
+    #[cfg(all(unix, not(miri)))]
+    fn non_unicode_path() {
+        use std::ffi::OsStr;
+        use std::os::unix::ffi::OsStrExt;
+        let mut path = std::env::temp_dir();
+        path.push(OsStr::from_bytes(b"pigeon\xff.cnf"));
+
+        let mut sat = pigeon_hole(3);
+        assert!(sat.write_dimacs(&path).is_ok());
+        let mut sat: Solver = Default::default();
+        assert_eq!(sat.read_dimacs(&path), Ok(12));
+        assert_eq!(sat.solve(), Some(false));
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    #[cfg(not(miri))]
+    fn core_shrinking() {
+        let mut sat: Solver = Solver::new();
+        sat.add_clause([-1, -2]);
+        sat.add_clause([-2, -3, -4]);
+        assert_eq!(sat.solve_with([1, 2, 3, 4].iter().copied()), Some(false));
+        assert_eq!(sat.shrink_core(&[4, 3, 2, 1], 100), vec![2, 1]);
+        assert_eq!(sat.shrink_core(&[2, 3, 4], -1), vec![2, 3, 4]);
+        assert_eq!(sat.shrink_core(&[5, 6], 100), vec![5, 6]);
+    }
+
+    #[test]
+    fn equivalences() {
+        let mut sat: Solver = Solver::new();
+        sat.add_clause([-1, 2]);
+        sat.add_clause([-2, 1]);
+        sat.add_clause([3, 4]);
+        sat.add_clause([-3, -4]);
+        sat.add_clause([1, 5, 6]);
+        sat.add_clause([-6, 5]);
+        assert_eq!(sat.binary_clauses().len(), 5);
+        assert_eq!(sat.equivalences(), vec![vec![1, 2], vec![3, -4]]);
+    }
+
+    #[test]
+    #[cfg(not(miri))]
+    fn scopes() {
+        let mut sat: Solver = Solver::new();
+        sat.add_clause([1, 2, 3]);
+        assert_eq!(sat.pop(), Err(Error::new("no open scope")));
+        sat.push();
+        sat.add_clause([-1]);
+        assert_eq!(sat.max_variable(), 4);
+        sat.push();
+        sat.add_clause([-2]);
+        assert_eq!(sat.scope_depth(), 2);
+        assert_eq!(sat.solve_with([-3].iter().copied()), Some(false));
+        assert_eq!(sat.solve(), Some(true));
+        assert_eq!(sat.value(3), Some(true));
+        sat.pop().unwrap();
+        assert_eq!(sat.solve_with([-3].iter().copied()), Some(true));
+        assert_eq!(sat.value(2), Some(true));
+        sat.pop().unwrap();
+        assert_eq!(sat.scope_depth(), 0);
+        assert_eq!(sat.solve_with([-2, -3].iter().copied()), Some(true));
+        assert_eq!(sat.value(1), Some(true));
+
+        assert!(sat.is_selector(-4));
+        let result = panic::catch_unwind(AssertUnwindSafe(|| sat.add_clause([4])));
+        assert!(result.is_err());
+        let var = sat.new_variable();
+        assert_eq!(var, 6);
+        sat.add_clause([var]);
+        assert_eq!(sat.solve(), Some(true));
+    }
+
+    #[test]
+    #[cfg(not(miri))]
+    fn removable_clauses() {
+        let mut sat: Solver = Solver::new();
+        sat.add_clause([1, 2]);
+        let first = sat.add_removable_clause([-1]);
+        let second = sat.add_removable_clause([-2]);
+        assert_ne!(first, second);
+        assert_eq!(sat.solve(), Some(false));
+        sat.remove_clause(first).unwrap();
+        assert_eq!(sat.solve(), Some(true));
+        assert_eq!(sat.value(1), Some(true));
+        assert_eq!(sat.remove_clause(first), Err(Error::new("unknown clause")));
+        sat.push();
+        let third = sat.add_removable_clause([-1]);
+        assert_eq!(sat.solve(), Some(false));
+        sat.pop().unwrap();
+        assert_eq!(sat.solve(), Some(true));
+        assert_eq!(sat.value(1), Some(true));
+        sat.remove_clause(second).unwrap();
+        sat.remove_clause(third).unwrap();
+        assert_eq!(sat.solve(), Some(true));
+
+        let mut sat: Solver = Solver::new();
+        sat.add_clause([1, 2]);
+        let handle = sat.add_removable_clause([-1]);
+        sat.remove_clause(handle).unwrap();
+        assert_eq!(sat.solve(), Some(true));
+        let result = panic::catch_unwind(AssertUnwindSafe(|| sat.add_clause([3])));
+        assert!(result.is_err());
+        let var = sat.new_variable();
+        sat.add_clause([var]);
+        assert_eq!(sat.solve(), Some(true));
+        assert_eq!(sat.value(var), Some(true));
+    }
+
+    #[test]
+    #[cfg(not(miri))]
+    fn assumption_groups() {
+        let mut sat: Solver = Solver::new();
+        sat.add_clause([-1, -2]);
+        sat.add_clause([-3, -4]);
+        sat.assumption_group("a", [1, 3]);
+        sat.assumption_group("b", [2]);
+        sat.assumption_group("c", [5]);
+        assert_eq!(sat.solve(), Some(false));
+        assert_eq!(sat.failed_groups(), vec!["a", "b"]);
+        sat.set_group_active("b", false).unwrap();
+        assert_eq!(sat.solve_with([4].iter().copied()), Some(false));
+        assert_eq!(sat.failed_groups(), vec!["a"]);
+        sat.remove_group("a").unwrap();
+        assert_eq!(sat.solve_with([4].iter().copied()), Some(true));
+        assert_eq!(sat.value(5), Some(true));
+        assert_eq!(sat.remove_group("a"), Err(Error::new("unknown group")));
+        assert!(sat.set_group_active("a", true).is_err());
+    }
+
+    #[test]
+    #[cfg(not(miri))]
+    fn sticky_assumptions() {
+        let mut sat: Solver = Solver::new();
+        sat.add_clause([1, 2, 3]);
+        sat.assume_sticky([-1]);
+        sat.assume_sticky([-2]);
+        assert_eq!(sat.sticky_assumptions(), &[-1, -2]);
+        assert_eq!(sat.solve_with([-3].iter().copied()), Some(false));
+        assert!(sat.failed(-1) && sat.failed(-2) && sat.failed(-3));
+        assert_eq!(sat.solve(), Some(true));
+        assert_eq!(sat.value(3), Some(true));
+        sat.clear_sticky_assumptions();
+        assert_eq!(sat.solve_with([-3].iter().copied()), Some(true));
+    }
+
+    #[test]
+    #[cfg(not(miri))]
+    fn prioritize() {
+        let mut sat = pigeon_hole(4);
+        sat.prioritize([20, 1, 7]);
+        assert_eq!(sat.solve(), Some(false));
+
+        // fixed variables and those above the maximum are skipped
+        let mut sat: Solver = Solver::new();
+        sat.add_clause([1]);
+        sat.add_clause([-1, 2]);
+        assert_eq!(sat.solve(), Some(true));
+        sat.prioritize([1, 2, 1000]);
+        assert_eq!(sat.max_variable(), 2);
+        assert_eq!(sat.solve(), Some(true));
+    }
+
+    #[test]
+    fn options() {
+        let mut sat: Solver = Solver::new();
+        assert_eq!(sat.set_option("bad", 1), Err(OptionError::UnknownOption));
+        assert_eq!(sat.set_option("\0", 1), Err(OptionError::InvalidString));
+        assert_eq!(sat.option_range("verbose"), Some((0, 3)));
+        assert_eq!(
+            sat.set_option("verbose", 4),
+            Err(OptionError::ValueOutOfRange { min: 0, max: 3 })
+        );
+        assert_eq!(
+            Error::from(OptionError::ValueOutOfRange { min: 0, max: 3 }),
+            Error::new("value out of range 0..=3")
+        );
+        assert_eq!(sat.set_seed(7), Ok(()));
+        assert_eq!(sat.get_option("seed"), 7);
+        sat.add_clause([1, 2]);
+        assert_eq!(sat.set_seed(8), Err(Error::new("invalid state")));
+        assert_eq!(sat.get_option("seed"), 7);
+    }
+
+    #[test]
+    #[cfg(not(miri))]
+    fn inconsistent() {
+        let mut sat: Solver = Solver::new();
+        assert!(!sat.is_inconsistent());
+        sat.add_clause([1, 2]);
+        sat.add_clause([-1, 2]);
+        sat.add_clause([1, -2]);
+        assert!(!sat.is_inconsistent());
+        assert_eq!(sat.solve_with([-2]), Some(false));
+        assert!(!sat.is_inconsistent());
+        sat.add_clause([-1, -2]);
+        assert_eq!(sat.solve(), Some(false));
+        assert!(sat.is_inconsistent());
+    }
+
+    #[test]
+    #[cfg(all(feature = "profiling", not(miri)))]
+    fn profile() {
+        let mut sat = pigeon_hole(6);
+        assert_eq!(sat.profile().search, 0.0);
+        assert_eq!(sat.solve(), Some(false));
+        let profile = sat.profile();
+        assert!(profile.search > 0.0);
+        assert!(profile.solve >= profile.search);
+    }
+
+    #[test]
+    #[cfg(not(miri))]
+    fn state() {
+        let mut sat: Solver = Solver::new();
+        assert_eq!(sat.state(), State::Configuring);
+        sat.reserve(3);
+        assert_eq!(sat.state(), State::Steady);
+        assert_eq!(sat.set_option("seed", 1), Err(OptionError::InvalidState));
+        sat.add_clause([1, 2]);
+        assert_eq!(sat.solve(), Some(true));
+        assert_eq!(sat.state(), State::Satisfied);
+        assert_eq!(sat.solve_with([-1, -2]), Some(false));
+        assert_eq!(sat.state(), State::Unsatisfied);
+        sat.add_clause([3]);
+        assert_eq!(sat.state(), State::Steady);
+        sat.reset();
+        assert_eq!(sat.state(), State::Configuring);
+    }
+
+    #[test]
+    #[cfg(not(miri))]
+    fn deterministic() {
+        let run = || {
+            let mut sat: Solver = Solver::new();
+            sat.set_deterministic(true).unwrap();
+            for i in 0..7 {
+                sat.add_clause((0..6).map(|j| 1 + i * 6 + j));
+                for k in 0..i {
+                    for j in 0..6 {
+                        sat.add_clause([-(1 + i * 6 + j), -(1 + k * 6 + j)]);
+                    }
+                }
+            }
+            let budget = Budget {
+                conflicts: Some(500),
+                time: Some(Duration::from_millis(0)),
+                ..Default::default()
+            };
+            let (result, effort) = sat.solve_limited(&budget);
+            (result, effort.conflicts, effort.decisions)
+        };
+        let first = run();
+        assert_eq!(first.0, None);
+        assert!(first.1 >= 500);
+        assert_eq!(run(), first);
+
+        let mut sat: Solver = Solver::new();
+        sat.add_clause([1]);
+        assert_eq!(
+            sat.set_deterministic(true),
+            Err(Error::new("invalid state"))
+        );
+        assert!(!sat.is_deterministic());
+
+        let mut sat: Solver = Solver::new();
+        sat.set_seed(7).unwrap();
+        sat.set_deterministic(true).unwrap();
+        sat.set_deterministic(true).unwrap();
+        assert_eq!(sat.get_option("seed"), 0);
+        sat.set_deterministic(false).unwrap();
+        assert!(!sat.is_deterministic());
+        assert_eq!(sat.get_option("seed"), 7);
+        assert_eq!(sat.manifest().options.get("seed"), Some(&7));
+
+        let mut sat: Solver = Solver::new();
+        sat.set_deterministic(true).unwrap();
+        sat.add_clause([1]);
+        sat.set_deterministic(false).unwrap();
+        assert!(!sat.is_deterministic());
+    }
+
+    #[test]
+    fn manifest() {
+        let mut sat: Solver = Solver::new();
+        sat.shuffle(5).unwrap();
+        sat.set_limit("conflicts", 100).unwrap();
+        let manifest = sat.manifest();
+        assert_eq!(manifest.config, None);
+        assert_eq!(manifest.options.len(), 5);
+        assert_eq!(manifest.options.get("seed"), Some(&5));
+        assert_eq!(manifest.limits.get("conflicts"), Some(&100));
+        let text = manifest.to_string();
+        assert!(text.contains("option seed=5\n"));
+        assert!(text.contains("limit conflicts=100\n"));
+        assert!(text.ends_with("deterministic false\n"));
+        sat.add_clause([1]);
+        sat.solve();
+        assert!(sat.manifest().limits.is_empty());
+        sat.reset();
+        assert!(sat.manifest().options.is_empty());
+    }
+
+    #[test]
+    #[cfg(not(miri))]
+    fn reentrancy() {
+        struct Reenter(*mut Solver<Reenter>);
+
+        impl Callbacks for Reenter {
+            fn terminate(&mut self) -> bool {
+                unsafe { (*self.0).add_clause([1]) };
+                false
+            }
+        }
+
+        let mut sat: Box<Solver<Reenter>> = Box::new(pigeon_hole(5).fork());
+        let ptr: *mut Solver<Reenter> = &mut *sat;
+        sat.set_callbacks(Some(Reenter(ptr)));
+        assert!(!sat.is_solving());
+        let result = panic::catch_unwind(panic::AssertUnwindSafe(|| sat.solve()));
+        let payload = result.unwrap_err();
+        let msg = payload.downcast_ref::<String>().unwrap();
+        assert_eq!(
+            msg,
+            "Solver::add_clause called from a callback during solving"
+        );
+        assert!(!sat.is_solving());
+        sat.set_callbacks(None);
+        assert_eq!(sat.solve(), Some(false));
+    }
+
+    #[test]
+    #[cfg(feature = "smallvec")]
+    fn learn_owned() {
+        struct Keep(Vec<LearnedClause>);
+
+        impl Callbacks for Keep {
+            fn max_length(&self) -> i32 {
+                20
+            }
+
+            fn learn_owned_clauses(&self) -> bool {
+                true
+            }
+
+            fn learn_owned(&mut self, clause: LearnedClause) {
+                self.0.push(clause);
+            }
+        }
+
+        let mut sat: Solver<Keep> = pigeon_hole(5).fork();
+        sat.set_callbacks(Some(Keep(Vec::new())));
+        assert_eq!(sat.solve(), Some(false));
+        let clauses = std::mem::take(&mut sat.get_callbacks().unwrap().0);
+        assert!(!clauses.is_empty());
+        assert!(clauses.iter().all(|clause| clause.len() <= 20));
+
+        let pool = sat.clause_pool();
+        let long: Vec<i32> = (1..=12).collect();
+        let clause = pool.take(&long);
+        assert_eq!(&clause[..], &long[..]);
+        assert!(pool.is_empty());
+        pool.recycle(clause);
+        pool.recycle(pool.take(&[1, 2]));
+        assert_eq!(pool.len(), 1);
+        assert_eq!(&pool.take(&long[2..])[..], &long[2..]);
+        assert!(pool.is_empty());
+        for clause in clauses {
+            pool.recycle(clause);
+        }
+    }
+
+    #[test]
+    fn zero_literal_policy() {
+        let mut sat: Solver = Solver::new();
+        assert_eq!(sat.zero_literal_policy(), ZeroLiteralPolicy::Unchecked);
+        sat.set_zero_literal_policy(ZeroLiteralPolicy::Skip);
+        sat.add_clause([0, 1, 0, 2, 0]);
+        sat.add_clause_slice(&[-1, 0]);
+        assert_eq!(sat.num_clauses(), 2);
+        sat.set_zero_literal_policy(ZeroLiteralPolicy::Split);
+        sat.add_clause([3, 0, 4, 5]);
+        sat.add_clause_slice(&[-4, 0]);
+        assert_eq!(sat.num_clauses(), 5);
+        assert_eq!(sat.solve(), Some(true));
+        assert_eq!(sat.value(5), Some(true));
+        sat.set_zero_literal_policy(ZeroLiteralPolicy::Reject);
+        assert_eq!(
+            sat.try_add_clause([-5, 0]),
+            Err(Error::new("zero literal in clause"))
+        );
+        assert_eq!(sat.try_add_clause([-3]), Ok(()));
+        let result = panic::catch_unwind(AssertUnwindSafe(|| sat.add_clause([0])));
+        assert!(result.is_err());
+        assert_eq!(sat.solve(), Some(false));
+        sat.set_zero_literal_policy(ZeroLiteralPolicy::Split);
+        sat.reset();
+        sat.add_clause([1, 0, 0]);
+        assert_eq!(sat.solve(), Some(false));
+    }
+
+    #[test]
+    fn sanitize() {
+        let mut sat: Solver = Solver::new();
+        assert!(!sat.sanitize());
+        sat.set_sanitize(true);
+        assert!(sat.sanitize());
+        sat.add_clause([1, 2, 1, 2, 1]);
+        sat.add_clause_slice(&[-1, 3, 1]);
+        sat.add_clause([-2, -2]);
+        sat.push();
+        sat.add_clause([4, -4, 4]);
+        sat.add_clause([-1, -1]);
+        let report = sat.sanitize_report();
+        assert_eq!(report.clauses, 5);
+        assert_eq!(report.clauses_with_duplicates, 3);
+        assert_eq!(report.duplicate_literals, 5);
+        assert_eq!(report.tautologies, 2);
+        assert_eq!(sat.solve(), Some(false));
+        sat.pop().unwrap();
+        assert_eq!(sat.solve(), Some(true));
+        assert_eq!(sat.value(1), Some(true));
+        sat.set_sanitize(false);
+        assert_eq!(sat.sanitize_report(), SanitizeReport::default());
+    }
+
+    #[test]
+    fn api_trace_shrink() {
+        let text = "init\nset seed 3\nadd 1\nadd 2\nadd 0\nadd -1\nadd 0\nassume -2\n\
+                    solve\nadd 3\nadd 0\nsolve\nval 3\nadd -2\nadd 0\nsolve\nrelease\n";
+        let trace = ApiTrace::parse(text);
+        assert_eq!(trace.to_string(), text);
+        let mut sat: Solver = Solver::new();
+        let results = trace.replay(&mut sat);
+        assert_eq!(results, [Some(false), Some(true), Some(false)]);
+        assert_eq!(sat.get_option("seed"), 3);
+        let mut calls = 0;
+        let small = trace.shrink(|trace| {
+            calls += 1;
+            let mut sat: Solver = Solver::new();
+            let results = trace.replay(&mut sat);
+            results.windows(2).any(|w| w == [Some(true), Some(false)])
+        });
+        assert_eq!(small.calls, ["init", "solve", "add 0", "solve", "release"]);
+        assert!(calls < 100);
+    }
+
+    #[test]
+    fn options_snapshot() {
+        let mut sat: Solver = Solver::new();
+        assert_eq!(sat.options_snapshot(), OptionsSnapshot::default());
+        sat.set_option("phase", 0).unwrap();
+        sat.set_seed(7).unwrap();
+        let tuned = sat.options_snapshot();
+        assert_eq!(tuned.values.get("phase"), Some(&0));
+        assert_eq!(tuned.values.get("seed"), Some(&7));
+        sat.reset_options().unwrap();
+        assert_eq!(sat.options_snapshot(), OptionsSnapshot::default());
+        assert!(sat.manifest().options.is_empty());
+        sat.apply(&tuned).unwrap();
+        assert_eq!(sat.get_option("seed"), 7);
+        assert_eq!(sat.options_snapshot(), tuned);
+        assert_eq!(sat.manifest().options, tuned.values);
+        let mut bad = tuned.clone();
+        bad.values.insert("phase".to_string(), 2);
+        assert_eq!(
+            sat.apply(&bad),
+            Err(OptionError::ValueOutOfRange { min: 0, max: 1 })
+        );
+        bad.values.insert("bad".to_string(), 1);
+        assert_eq!(sat.apply(&bad), Err(OptionError::UnknownOption));
+        assert_eq!(sat.options_snapshot(), tuned);
+
+        sat.add_clause([1, 2]);
+        assert_eq!(sat.solve(), Some(true));
+        assert_eq!(sat.reset_options(), Err(OptionError::InvalidState));
+        assert_eq!(sat.options_snapshot(), tuned);
+        let mut quiet = tuned.clone();
+        quiet.values.insert("quiet".to_string(), 1);
+        sat.apply(&quiet).unwrap();
+        assert_eq!(sat.get_option("quiet"), 1);
+        assert_eq!(sat.solve(), Some(true));
+    }
+
+    #[test]
+    #[cfg(not(miri))]
+    fn reset() {
+        let mut sat: Solver = Solver::new();
+        sat.set_seed(3).unwrap();
+        sat.add_clause([1, 2]);
+        sat.push();
+        sat.add_clause([-1]);
+        sat.assume_sticky([-2]);
+        sat.assumption_group("a", [4]);
+        sat.set_callbacks(Some(Timeout::new(10.0)));
+        assert_eq!(sat.solve(), Some(false));
+        assert!(sat.last_solve_time().is_some());
+        sat.memory_limit = Some(1 << 30);
+        let flag = sat.interrupt_flag();
+        flag.store(true, Ordering::Relaxed);
+        sat.reset();
+        assert_eq!(sat.memory_limit, None);
+        assert!(!flag.load(Ordering::Relaxed));
+        assert_eq!(sat.status(), None);
+        assert_eq!(sat.last_solve_time(), None);
+        assert_eq!(sat.max_variable(), 0);
+        assert_eq!(sat.scope_depth(), 0);
+        assert!(sat.sticky_assumptions().is_empty());
+        assert_eq!(sat.get_option("seed"), 0);
+        assert!(sat.get_callbacks().is_some());
+        sat.add_clause([-1]);
+        assert_eq!(sat.solve_with([-2].iter().copied()), Some(true));
+    }
+
+    #[test]
+    #[cfg(not(miri))]
+    fn clearing() {
+        let mut sat: Solver = Solver::new();
+        sat.add_clause([1, 2]);
+        sat.assume(-1);
+        sat.assume(-2);
+        sat.clear_assumptions();
+        assert_eq!(sat.solve(), Some(true));
+        sat.constrain([-1]);
+        assert_eq!(sat.solve_with([-2].iter().copied()), Some(false));
+        assert!(sat.constraint_failed());
+        sat.constrain([-1]);
+        sat.clear_constraint();
+        assert_eq!(sat.solve_with([-2].iter().copied()), Some(true));
+    }
+
+    #[test]
+    fn formula_stats() {
+        let mut sat: Solver = Solver::new();
+        assert_eq!(sat.formula_stats(), FormulaStats::default());
+        sat.add_clause([1, 2]);
+        sat.add_clause([-1, 2, 3]);
+        sat.add_clause([-2, 3, 4, 5]);
+        sat.add_clause([5, 6, 7]);
+        let stats = sat.formula_stats();
+        assert_eq!(stats.clauses, 4);
+        assert_eq!(stats.literals, 12);
+        assert_eq!(stats.length_histogram, vec![0, 0, 1, 2, 1]);
+        assert_eq!((stats.units, stats.binaries, stats.ternaries), (0, 1, 2));
+        assert_eq!(stats.variables, 7);
+        assert_eq!(stats.pure_variables, 5);
+        assert_eq!(stats.max_occurrences, 3);
+        assert!((stats.mean_occurrences - 12.0 / 7.0).abs() < 1e-9);
+    }
+
+    #[cfg(not(miri))]
+    #[test]
+    fn temporary_clauses() {
+        let mut sat: Solver = Solver::new();
+        sat.add_clause([1, 2, 3]);
+        let clauses = vec![vec![-1], vec![-2], vec![-3]];
+        assert_eq!(sat.solve_with_clauses([], clauses), Some(false));
+        assert_eq!(sat.solve_with_clauses([-1], [[-2]]), Some(true));
+        assert_eq!(sat.value(3), Some(true));
+        assert_eq!(sat.solve_with([-1, -2]), Some(true));
+        let empty: [[i32; 0]; 0] = [];
+        assert_eq!(sat.solve_with_clauses([-3], empty), Some(true));
+        sat.push();
+        assert_eq!(sat.solve_with_clauses([], [[-1], [-2]]), Some(true));
+        assert_eq!(sat.value(3), Some(true));
+        sat.pop().unwrap();
+        assert_eq!(sat.solve_with([-1, -2, -3]), Some(false));
+
+        let mut sat: Solver = Solver::new();
+        sat.add_clause([1, 2]);
+        assert_eq!(sat.solve_with_clauses([-1], [[-2, 4]]), Some(true));
+        assert_eq!(sat.value(4), Some(true));
+        assert!(sat.is_selector(5));
+        let result = panic::catch_unwind(AssertUnwindSafe(|| sat.add_clause([-5])));
+        assert!(result.is_err());
+        let var = sat.new_variable();
+        sat.add_clause([var]);
+        assert_eq!(sat.solve_with([-1]), Some(true));
+        assert_eq!(sat.value(var), Some(true));
+    }
+
+    #[cfg(not(miri))]
+    #[test]
+    fn solve_limited() {
+        let mut sat = pigeon_hole(if cfg!(feature = "pure-rust") { 7 } else { 5 });
+        let budget = Budget {
+            conflicts: Some(10),
+            ..Default::default()
+        };
+        let (result, effort) = sat.solve_limited(&budget);
+        assert_eq!(result, None);
+        assert!(effort.conflicts >= 10);
+        assert!(effort.decisions > 0);
+
+        let budget = Budget {
+            time: Some(Duration::from_millis(0)),
+            ..Default::default()
+        };
+        assert_eq!(sat.solve_limited(&budget).0, None);
+
+        let budget = Budget {
+            propagations: Some(1000),
+            ..Default::default()
+        };
+        let (result, effort) = sat.solve_limited(&budget);
+        assert_eq!(result, None);
+        assert!(effort.propagations >= 1000);
+        assert_eq!(sat.solve_budgeted(&budget), None);
+
+        let budget = Budget {
+            conflicts: Some(u64::MAX),
+            propagations: Some(u64::MAX),
+            ..Default::default()
+        };
+        let (result, effort) = sat.solve_limited(&budget);
+        assert_eq!(result, Some(false));
+        assert!(effort.conflicts > 0);
+    }
+
     #[test]
     fn test_reserve() {
         let mut s: Solver = Default::default();
@@ -633,4 +5097,38 @@ mod tests {
         assert_eq!(s.solve(), Some(true));
         assert_eq!(s.max_variable(), 2);
     }
+
+    #[test]
+    fn freeze() {
+        let mut sat: Solver = Solver::new();
+        sat.add_clause([1, 2]);
+        sat.freeze(1);
+        sat.freeze(-1);
+        assert!(sat.frozen(1));
+        assert!(!sat.frozen(2));
+        assert_eq!(sat.melt(1), Ok(()));
+        assert!(sat.frozen(-1));
+        assert_eq!(sat.melt(-1), Ok(()));
+        assert!(!sat.frozen(1));
+        assert_eq!(sat.melt(2), Err(Error::new("not frozen")));
+        sat.freeze(5);
+        assert_eq!(sat.max_variable(), 5);
+    }
+
+    #[test]
+    fn failed_and_constraint() {
+        let mut sat: Solver = Solver::new();
+        sat.add_clause([1, 2]);
+        sat.add_clause([-1, 3]);
+        assert_eq!(sat.solve_with([-2, -3, 4]), Some(false));
+        assert!(sat.failed(-2) && sat.failed(-3));
+        assert!(!sat.failed(4));
+        sat.constrain([-2]);
+        assert_eq!(sat.solve_with([-3]), Some(false));
+        assert!(sat.failed(-3));
+        assert!(sat.constraint_failed());
+        assert!(!sat.is_inconsistent());
+        assert_eq!(sat.solve_with([-3]), Some(true));
+        assert_eq!(sat.value(2), Some(true));
+    }
 }
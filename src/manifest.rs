@@ -0,0 +1,62 @@
+//! A record of the settings of the solver for reproducing experiments.
+
+use super::{Callbacks, Solver};
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// The settings of a solver that affect its search: the version of CaDiCaL,
+/// the configuration preset, the options and limits set through this crate
+/// (including the random seed) and the deterministic mode. A solver that is
+/// constructed with the same preset, options and limits behaves the same on
+/// the same formula.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Manifest {
+    /// The signature of the CaDiCaL library.
+    pub version: String,
+    /// The configuration preset passed to `Solver::with_config`.
+    pub config: Option<String>,
+    /// The options set by `set_option` with their current values.
+    pub options: BTreeMap<String, i32>,
+    /// The limits set by `set_limit` for the next solve call.
+    pub limits: BTreeMap<String, i32>,
+    /// Whether the deterministic mode is enabled.
+    pub deterministic: bool,
+}
+
+impl<C: Callbacks> Solver<C> {
+    /// Returns the manifest of the current settings of the solver.
+    /// # Examples
+    /// ```
+    /// let mut sat = cadical::Solver::<cadical::Timeout>::with_config("sat").unwrap();
+    /// sat.set_seed(42).unwrap();
+    /// let manifest = sat.manifest();
+    /// assert_eq!(manifest.config.as_deref(), Some("sat"));
+    /// assert_eq!(manifest.options.get("seed"), Some(&42));
+    /// ```
+    pub fn manifest(&self) -> Manifest {
+        Manifest {
+            version: self.signature().to_string(),
+            config: self.config.clone(),
+            options: self.options.clone(),
+            limits: self.limits.clone(),
+            deterministic: self.deterministic.is_some(),
+        }
+    }
+}
+
+impl fmt::Display for Manifest {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "version {}", self.version)?;
+        if let Some(config) = &self.config {
+            writeln!(f, "config {}", config)?;
+        }
+        for (name, value) in self.options.iter() {
+            writeln!(f, "option {}={}", name, value)?;
+        }
+        for (name, value) in self.limits.iter() {
+            writeln!(f, "limit {}={}", name, value)?;
+        }
+        writeln!(f, "deterministic {}", self.deterministic)
+    }
+}
@@ -0,0 +1,81 @@
+//! Accounting of the native memory allocated by a solver. With the
+//! `memory-accounting` feature the global C++ allocation functions are
+//! replaced, and the allocations made while an account is entered on the
+//! current thread are charged to it. Without the feature the accounts are
+//! empty and entering them does nothing.
+
+#[cfg(feature = "memory-accounting")]
+use super::ffi::*;
+#[cfg(feature = "memory-accounting")]
+use std::os::raw::c_void;
+
+/// The native memory account of a solver.
+pub(crate) struct Account {
+    #[cfg(feature = "memory-accounting")]
+    ptr: *mut c_void,
+}
+
+/// Charges the native allocations of the current thread to an account until
+/// it is dropped.
+pub(crate) struct AccountScope {
+    #[cfg(feature = "memory-accounting")]
+    previous: *mut c_void,
+}
+
+impl Account {
+    pub(crate) fn new() -> Self {
+        Account {
+            #[cfg(feature = "memory-accounting")]
+            ptr: unsafe { ccadical_account_new() },
+        }
+    }
+
+    /// Makes this the active account of the current thread.
+    #[inline]
+    pub(crate) fn enter(&self) -> AccountScope {
+        AccountScope {
+            #[cfg(feature = "memory-accounting")]
+            previous: unsafe { ccadical_account_enter(self.ptr) },
+        }
+    }
+
+    /// Returns the number of bytes currently allocated.
+    #[cfg(feature = "memory-accounting")]
+    pub(crate) fn current(&self) -> u64 {
+        unsafe { ccadical_account_current(self.ptr) as u64 }
+    }
+
+    /// Returns the maximum number of bytes allocated at any time.
+    #[cfg(feature = "memory-accounting")]
+    pub(crate) fn peak(&self) -> u64 {
+        unsafe { ccadical_account_peak(self.ptr) as u64 }
+    }
+
+    /// Returns whether more than the given number of bytes are allocated.
+    #[inline]
+    pub(crate) fn exceeds(&self, limit: Option<u64>) -> bool {
+        #[cfg(feature = "memory-accounting")]
+        if let Some(limit) = limit {
+            return self.current() > limit;
+        }
+        let _ = limit;
+        false
+    }
+}
+
+impl Drop for AccountScope {
+    #[inline]
+    fn drop(&mut self) {
+        #[cfg(feature = "memory-accounting")]
+        unsafe {
+            ccadical_account_enter(self.previous);
+        }
+    }
+}
+
+#[cfg(feature = "memory-accounting")]
+impl Drop for Account {
+    fn drop(&mut self) {
+        unsafe { ccadical_account_release(self.ptr) };
+    }
+}
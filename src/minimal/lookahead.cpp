@@ -0,0 +1,16 @@
+// Replaces the lookahead and cube generation of CaDiCaL in minimal builds.
+// These are only reachable through the lookahead and cubing API of the C++
+// solver, which the Rust wrapper does not use.
+#include "../../cadical/src/internal.hpp"
+
+namespace CaDiCaL
+{
+  int Internal::lookahead() { return 0; }
+
+  Internal::CubesWithStatus Internal::generate_cubes(int, int)
+  {
+    CubesWithStatus result;
+    result.status = 0;
+    return result;
+  }
+}
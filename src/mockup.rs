@@ -1,35 +1,401 @@
 //! This is a mockup implementation of the solver to allow testing the memory
-//! safety of the crate with `cargo +nightly miri test`.
+//! safety of the crate with `cargo +nightly miri test`. It is a naive DPLL
+//! solver without clause learning, which is slow but gives correct answers
+//! on small formulas: assumptions, failed literals, constraints, frozen
+//! variables, limits, and the terminate and learn callbacks all follow the
+//! semantics of CaDiCaL, so the test suites of downstream crates can run
+//! against it.
 
-#![allow(unused_variables)]
+#![allow(unused_variables, clippy::missing_safety_doc)]
 
-use std::ffi::CStr;
+use std::ffi::{CStr, CString, OsStr};
 use std::os::raw::{c_char, c_int, c_void};
+use std::path::PathBuf;
 use std::ptr::{null, null_mut};
+use std::sync::atomic::{AtomicI64, AtomicU8, Ordering};
+use std::time::Instant;
+
+/// The options known to the mockup with their default, minimum and maximum
+/// values.
+const OPTIONS: [(&str, i32, i32, i32); 17] = [
+    ("chrono", 1, 0, 2),
+    ("elim", 1, 0, 1),
+    ("lucky", 1, 0, 1),
+    ("phase", 1, 0, 1),
+    ("probe", 1, 0, 1),
+    ("quiet", 0, 0, 1),
+    ("restart", 1, 0, 1),
+    ("seed", 0, 0, 2_000_000_000),
+    ("shuffle", 0, 0, 1),
+    ("shufflequeue", 1, 0, 1),
+    ("shufflerandom", 0, 0, 1),
+    ("shufflescores", 1, 0, 1),
+    ("stabilize", 1, 0, 1),
+    ("stabilizeonly", 0, 0, 1),
+    ("subsume", 1, 0, 1),
+    ("verbose", 0, 0, 3),
+    ("walk", 1, 0, 1),
+];
+
+/// The names of the options in the same order, terminated by a zero byte.
+const OPTION_NAMES: [&str; 17] = [
+    "chrono\0",
+    "elim\0",
+    "lucky\0",
+    "phase\0",
+    "probe\0",
+    "quiet\0",
+    "restart\0",
+    "seed\0",
+    "shuffle\0",
+    "shufflequeue\0",
+    "shufflerandom\0",
+    "shufflescores\0",
+    "stabilize\0",
+    "stabilizeonly\0",
+    "subsume\0",
+    "verbose\0",
+    "walk\0",
+];
+
+/// The answer of a search.
+enum Answer {
+    Sat,
+    Unsat,
+    Unknown,
+}
 
 pub struct Mockup {
-    vars: Vec<bool>,
-    clauses: i32,
-    conflicts: i32,
-    decisions: i32,
+    max_var: i32,
+    formula: Vec<Vec<i32>>,
+    clause: Vec<i32>,
+    assumptions: Vec<i32>,
+    constraint: Vec<i32>,
+    constraint_clause: Option<Vec<i32>>,
+    use_constraint: bool,
+    frozen: Vec<u32>,
+    options: Vec<(String, i32)>,
+    configuring: bool,
+    inconsistent: bool,
     status: i32,
+    error: CString,
+
+    // the state of the search: the values of the variables indexed by the
+    // variable, the assigned literals in order, the decision levels of the
+    // variables, and the trail length, decision and flipped flag of each
+    // decision level
+    values: Vec<i8>,
+    trail: Vec<i32>,
+    levels: Vec<i32>,
+    control: Vec<(usize, i32, bool)>,
+    model: Vec<i8>,
+    failed: Vec<i32>,
+    constraint_failed: bool,
+
+    conflict_limit: i32,
+    decision_limit: i32,
+    conflicts: i64,
+    decisions: i64,
+    propagations: i64,
+    learned: i64,
+    solve_time: f64,
+    search_time: f64,
+
     terminate_data: *const c_void,
     terminate_cbs: Option<extern "C" fn(*const c_void) -> c_int>,
+    terminate_flag: *const u8,
+    terminate_stats: *mut i64,
+    learn_data: *const c_void,
+    learn_max_length: c_int,
+    learn_max_glue: c_int,
+    learn_cbs: Option<extern "C" fn(*const c_void, *const c_int)>,
+    learn_length_cbs: Option<extern "C" fn(*const c_void, *const c_int, usize)>,
 }
 
 impl Mockup {
     fn new() -> Self {
         println!("created");
         Self {
-            vars: Default::default(),
-            clauses: 0,
-            conflicts: -1,
-            decisions: -1,
+            max_var: 0,
+            formula: Default::default(),
+            clause: Default::default(),
+            assumptions: Default::default(),
+            constraint: Default::default(),
+            constraint_clause: None,
+            use_constraint: true,
+            frozen: Default::default(),
+            options: Default::default(),
+            configuring: true,
+            inconsistent: false,
             status: 0,
-            terminate_data: null_mut(),
+            error: Default::default(),
+            values: Default::default(),
+            trail: Default::default(),
+            levels: Default::default(),
+            control: Default::default(),
+            model: Default::default(),
+            failed: Default::default(),
+            constraint_failed: false,
+            conflict_limit: -1,
+            decision_limit: -1,
+            conflicts: 0,
+            decisions: 0,
+            propagations: 0,
+            learned: 0,
+            solve_time: 0.0,
+            search_time: 0.0,
+            terminate_data: null(),
             terminate_cbs: None,
+            terminate_flag: null(),
+            terminate_stats: null_mut(),
+            learn_data: null(),
+            learn_max_length: 0,
+            learn_max_glue: 0,
+            learn_cbs: None,
+            learn_length_cbs: None,
         }
     }
+
+    /// Leaves the configuring state and invalidates the last answer.
+    fn touch(&mut self) {
+        self.configuring = false;
+        self.status = 0;
+    }
+
+    fn import(&mut self, lit: i32) {
+        self.max_var = self.max_var.max(lit.abs());
+    }
+
+    fn value(&self, lit: i32) -> i8 {
+        let val = self.values[lit.unsigned_abs() as usize];
+        if lit < 0 {
+            -val
+        } else {
+            val
+        }
+    }
+
+    fn assign(&mut self, lit: i32) {
+        let var = lit.unsigned_abs() as usize;
+        self.values[var] = if lit > 0 { 1 } else { -1 };
+        self.levels[var] = self.control.len() as i32;
+        self.trail.push(lit);
+    }
+
+    /// Propagates the unit clauses until a fixpoint, and returns `false` if
+    /// a clause became falsified.
+    fn propagate(&mut self) -> bool {
+        loop {
+            let mut units = Vec::new();
+            let constraint = self
+                .constraint_clause
+                .as_ref()
+                .filter(|_| self.use_constraint);
+            for clause in self.formula.iter().chain(constraint) {
+                let mut unassigned = clause.iter().filter(|&&lit| self.value(lit) >= 0);
+                match (unassigned.next(), unassigned.next()) {
+                    (None, _) => return false,
+                    (Some(&lit), None) if self.value(lit) == 0 => units.push(lit),
+                    _ => {}
+                }
+            }
+            if units.is_empty() {
+                return true;
+            }
+            for lit in units {
+                match self.value(lit) {
+                    0 => {
+                        self.propagations += 1;
+                        self.assign(lit);
+                    }
+                    1 => {}
+                    _ => return false,
+                }
+            }
+        }
+    }
+
+    /// Undoes the last decision that was not flipped yet and assigns its
+    /// negation instead. Returns `false` if there is no such decision.
+    fn backtrack(&mut self) -> bool {
+        while let Some((length, lit, flipped)) = self.control.pop() {
+            for lit in self.trail.drain(length..) {
+                self.values[lit.unsigned_abs() as usize] = 0;
+            }
+            if !flipped {
+                self.control.push((length, -lit, true));
+                self.assign(-lit);
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Returns the next assumption that is not satisfied yet, or the next
+    /// unassigned variable with positive phase, or `None` if all variables
+    /// are assigned. Returns `Some(0)` if an assumption is falsified.
+    fn next_decision(&self, assumptions: &[i32]) -> Option<(i32, bool)> {
+        for &lit in assumptions {
+            match self.value(lit) {
+                0 => return Some((lit, true)),
+                -1 => return Some((0, true)),
+                _ => {}
+            }
+        }
+        let var = (1..=self.max_var).find(|&var| self.values[var as usize] == 0)?;
+        Some((var, false))
+    }
+
+    /// The clause learned from the current conflict: the negation of the
+    /// decisions.
+    fn learned_clause(&self) -> Vec<i32> {
+        self.control.iter().map(|&(_, lit, _)| -lit).collect()
+    }
+
+    /// Checks the terminate callback and flag.
+    unsafe fn terminated(this: *mut Mockup) -> bool {
+        let (data, cbs) = ((*this).terminate_data, (*this).terminate_cbs);
+        if let Some(cbs) = cbs {
+            if cbs(data) != 0 {
+                return true;
+            }
+        }
+        if !(*this).terminate_stats.is_null() {
+            ccadical_publish_stats(this as *mut c_void, (*this).terminate_stats);
+        }
+        let flag = (*this).terminate_flag;
+        !flag.is_null() && (*(flag as *const AtomicU8)).load(Ordering::Relaxed) != 0
+    }
+
+    /// Reports a learned clause to the learn callbacks.
+    unsafe fn learn(this: *mut Mockup, clause: &[i32]) {
+        (*this).learned += 1;
+        let (data, length) = ((*this).learn_data, clause.len() as c_int);
+        if let Some(cbs) = (*this).learn_cbs {
+            if length <= (*this).learn_max_length {
+                let mut clause = clause.to_vec();
+                clause.push(0);
+                cbs(data, clause.as_ptr());
+            }
+        }
+        if let Some(cbs) = (*this).learn_length_cbs {
+            if length <= (*this).learn_max_length && length <= (*this).learn_max_glue {
+                cbs(data, clause.as_ptr(), clause.len());
+            }
+        }
+    }
+
+    /// Searches for a model under the given assumptions. The callbacks are
+    /// called and the limits are checked only if `limited` is set. The
+    /// mockup is only accessed through the raw pointer, because the
+    /// callbacks may query it.
+    unsafe fn search(this: *mut Mockup, assumptions: &[i32], limited: bool) -> Answer {
+        let start = Instant::now();
+        {
+            let mockup = &mut *this;
+            let size = mockup.max_var as usize + 1;
+            mockup.values = vec![0; size];
+            mockup.levels = vec![0; size];
+            mockup.trail.clear();
+            mockup.control.clear();
+        }
+        let (mut conflicts, mut decisions) = (0, 0);
+        let answer = loop {
+            if !(*this).propagate() {
+                (*this).conflicts += 1;
+                conflicts += 1;
+                if limited {
+                    let clause = (*this).learned_clause();
+                    if !clause.is_empty() {
+                        Mockup::learn(this, &clause);
+                    }
+                    let limit = (*this).conflict_limit;
+                    if limit >= 0 && conflicts > limit {
+                        break Answer::Unknown;
+                    }
+                }
+                if !(*this).backtrack() {
+                    break Answer::Unsat;
+                }
+                continue;
+            }
+            if limited && Mockup::terminated(this) {
+                break Answer::Unknown;
+            }
+            let (lit, assumption) = match (*this).next_decision(assumptions) {
+                Some((0, _)) => break Answer::Unsat,
+                Some(decision) => decision,
+                None => break Answer::Sat,
+            };
+            (*this).decisions += 1;
+            decisions += 1;
+            let limit = (*this).decision_limit;
+            if limited && limit >= 0 && decisions > limit {
+                break Answer::Unknown;
+            }
+            let mockup = &mut *this;
+            mockup.control.push((mockup.trail.len(), lit, assumption));
+            mockup.assign(lit);
+        };
+        (*this).search_time += start.elapsed().as_secs_f64();
+        answer
+    }
+
+    /// Solves the formula with the assumptions and the constraint, then
+    /// computes the failed assumptions by removing assumptions one by one
+    /// as long as the formula stays unsatisfiable.
+    unsafe fn solve(this: *mut Mockup) -> c_int {
+        let start = Instant::now();
+        let assumptions = std::mem::take(&mut (*this).assumptions);
+        for &lit in assumptions.iter() {
+            (*this).import(lit);
+        }
+        (*this).configuring = false;
+        (*this).failed.clear();
+        (*this).constraint_failed = false;
+        (*this).use_constraint = true;
+
+        let status = match Mockup::search(this, &assumptions, true) {
+            Answer::Sat => {
+                let mockup = &mut *this;
+                mockup.model = mockup.values.clone();
+                10
+            }
+            Answer::Unknown => 0,
+            Answer::Unsat => {
+                let mut core = assumptions.clone();
+                let mut index = 0;
+                while index < core.len() {
+                    let lit = core.remove(index);
+                    if let Answer::Sat = Mockup::search(this, &core, false) {
+                        core.insert(index, lit);
+                        index += 1;
+                    }
+                }
+                if (*this).constraint_clause.is_some() {
+                    (*this).use_constraint = false;
+                    let answer = Mockup::search(this, &core, false);
+                    (*this).constraint_failed = matches!(answer, Answer::Sat);
+                    (*this).use_constraint = true;
+                }
+                let mockup = &mut *this;
+                mockup.inconsistent |= core.is_empty() && !mockup.constraint_failed;
+                mockup.failed = core;
+                20
+            }
+        };
+
+        let mockup = &mut *this;
+        mockup.trail.clear();
+        mockup.control.clear();
+        mockup.constraint.clear();
+        mockup.constraint_clause = None;
+        mockup.conflict_limit = -1;
+        mockup.decision_limit = -1;
+        mockup.status = status;
+        mockup.solve_time += start.elapsed().as_secs_f64();
+        status
+    }
 }
 
 impl Drop for Mockup {
@@ -38,6 +404,10 @@ impl Drop for Mockup {
     }
 }
 
+unsafe fn mockup<'a>(ptr: *mut c_void) -> &'a mut Mockup {
+    &mut *(ptr as *mut Mockup)
+}
+
 pub unsafe fn ccadical_signature() -> *const c_char {
     println!("signature");
     "cadical-mockup\0".as_ptr() as *const c_char
@@ -56,56 +426,51 @@ pub unsafe fn ccadical_release(ptr: *mut c_void) {
 }
 
 pub unsafe fn ccadical_add(ptr: *mut c_void, lit: c_int) {
-    let mockup = &mut *(ptr as *mut Mockup);
+    let mockup = mockup(ptr);
+    mockup.touch();
     if lit == 0 {
-        mockup.clauses += 1;
+        let clause = std::mem::take(&mut mockup.clause);
+        mockup.inconsistent |= clause.is_empty();
+        mockup.formula.push(clause);
     } else {
-        let lit = lit.abs();
-        if (mockup.vars.len() as i32) < lit {
-            mockup.vars.resize(lit as usize, false);
-        }
-        mockup.vars[(lit - 1) as usize] = true;
+        mockup.import(lit);
+        mockup.clause.push(lit);
     }
 }
 
-pub unsafe fn ccadical_assume(ptr: *mut c_void, lit: c_int) {}
+pub unsafe fn ccadical_assume(ptr: *mut c_void, lit: c_int) {
+    let mockup = mockup(ptr);
+    mockup.touch();
+    mockup.import(lit);
+    mockup.assumptions.push(lit);
+}
+
+pub unsafe fn ccadical_solve_checked(ptr: *mut c_void) -> c_int {
+    ccadical_solve(ptr)
+}
 
 pub unsafe fn ccadical_solve(ptr: *mut c_void) -> c_int {
     println!("solve");
-    let mockup = &mut *(ptr as *mut Mockup);
-
-    if let Some(cbs) = mockup.terminate_cbs {
-        loop {
-            std::thread::sleep(std::time::Duration::from_millis(1));
-            let val = cbs(mockup.terminate_data);
-            if val != 0 {
-                return 0;
-            }
-        }
-    }
-
-    mockup.status = if mockup.clauses == 0 || mockup.clauses == 2 {
-        10
-    } else if mockup.conflicts >= 0 || mockup.decisions >= 0 {
-        0
-    } else {
-        20
-    };
-    mockup.status
+    Mockup::solve(ptr as *mut Mockup)
 }
 
 pub unsafe fn ccadical_val(ptr: *mut c_void, lit: c_int) -> c_int {
-    if lit == 2 {
-        2
-    } else if lit == -2 {
-        -2
+    let mockup = mockup(ptr);
+    let val = mockup.model.get(lit.unsigned_abs() as usize).copied();
+    let val = if lit < 0 {
+        -val.unwrap_or(0)
     } else {
-        0
+        val.unwrap_or(0)
+    };
+    if val > 0 {
+        lit.abs()
+    } else {
+        -lit.abs()
     }
 }
 
 pub unsafe fn ccadical_failed(ptr: *mut c_void, lit: c_int) -> c_int {
-    0
+    mockup(ptr).failed.contains(&lit) as c_int
 }
 
 pub unsafe fn ccadical_set_terminate(
@@ -113,9 +478,41 @@ pub unsafe fn ccadical_set_terminate(
     data: *const c_void,
     cbs: Option<extern "C" fn(*const c_void) -> c_int>,
 ) {
-    let mockup = &mut *(ptr as *mut Mockup);
+    let mockup = mockup(ptr);
     mockup.terminate_data = data;
     mockup.terminate_cbs = cbs;
+    mockup.terminate_flag = null();
+    mockup.terminate_stats = null_mut();
+}
+
+pub unsafe fn ccadical_set_terminate_flag(ptr: *mut c_void, flag: *const u8, stats: *mut i64) {
+    let mockup = mockup(ptr);
+    mockup.terminate_flag = flag;
+    mockup.terminate_stats = stats;
+    mockup.terminate_cbs = None;
+}
+
+pub unsafe fn ccadical_copy(ptr: *mut c_void, other: *mut c_void) -> c_int {
+    let (source, target) = (mockup(ptr), mockup(other));
+    target.touch();
+    target.max_var = source.max_var;
+    target.formula = source.formula.clone();
+    target.frozen = source.frozen.clone();
+    target.options = source.options.clone();
+    target.inconsistent = source.inconsistent;
+    0
+}
+
+pub unsafe fn ccadical_publish_stats(ptr: *mut c_void, stats: *mut i64) {
+    let mockup = mockup(ptr);
+    let stats = stats as *const AtomicI64;
+    let sequence = (*stats).load(Ordering::Relaxed);
+    (*stats).store(sequence + 1, Ordering::Relaxed);
+    (*stats.add(1)).store(mockup.conflicts, Ordering::Relaxed);
+    (*stats.add(2)).store(mockup.decisions, Ordering::Relaxed);
+    (*stats.add(3)).store(0, Ordering::Relaxed);
+    (*stats.add(4)).store(0, Ordering::Relaxed);
+    (*stats).store(sequence + 2, Ordering::Release);
 }
 
 pub unsafe fn ccadical_set_learn(
@@ -124,26 +521,58 @@ pub unsafe fn ccadical_set_learn(
     max_len: c_int,
     cbs: Option<extern "C" fn(*const c_void, *const c_int)>,
 ) {
+    let mockup = mockup(ptr);
+    mockup.learn_data = data;
+    mockup.learn_max_length = max_len;
+    mockup.learn_cbs = cbs;
+    mockup.learn_length_cbs = None;
+}
+
+pub unsafe fn ccadical_set_learn_length(
+    ptr: *mut c_void,
+    data: *const c_void,
+    max_len: c_int,
+    max_glue: c_int,
+    cbs: Option<extern "C" fn(*const c_void, *const c_int, usize)>,
+) {
+    let mockup = mockup(ptr);
+    mockup.learn_data = data;
+    mockup.learn_max_length = max_len;
+    mockup.learn_max_glue = max_glue;
+    mockup.learn_length_cbs = cbs;
+    mockup.learn_cbs = None;
 }
 
 pub unsafe fn ccadical_status(ptr: *mut c_void) -> c_int {
-    let mockup = &mut *(ptr as *mut Mockup);
-    mockup.status
+    mockup(ptr).status
 }
 
 pub unsafe fn ccadical_vars(ptr: *mut c_void) -> c_int {
-    let mockup = &mut *(ptr as *mut Mockup);
-    mockup.vars.len() as i32
+    mockup(ptr).max_var
 }
 
 pub unsafe fn ccadical_active(ptr: *mut c_void) -> i64 {
-    let mockup = &mut *(ptr as *mut Mockup);
-    mockup.vars.iter().filter(|v| **v).count() as i64
+    let mockup = mockup(ptr);
+    let mut active = vec![false; mockup.max_var as usize + 1];
+    for &lit in mockup.formula.iter().flatten() {
+        active[lit.unsigned_abs() as usize] = true;
+    }
+    active.iter().filter(|&&active| active).count() as i64
 }
 
 pub unsafe fn ccadical_irredundant(ptr: *mut c_void) -> i64 {
-    let mockup = &mut *(ptr as *mut Mockup);
-    mockup.clauses as i64
+    mockup(ptr).formula.len() as i64
+}
+
+/// Returns the path passed as a C string, which may be arbitrary bytes on
+/// Unix.
+unsafe fn file_path(path: *const c_char) -> PathBuf {
+    let path = CStr::from_ptr(path);
+    #[cfg(unix)]
+    let path = <OsStr as std::os::unix::ffi::OsStrExt>::from_bytes(path.to_bytes());
+    #[cfg(not(unix))]
+    let path = OsStr::new(path.to_str().unwrap_or_default());
+    PathBuf::from(path)
 }
 
 pub unsafe fn ccadical_read_dimacs(
@@ -152,6 +581,43 @@ pub unsafe fn ccadical_read_dimacs(
     vars: *mut c_int,
     strict: c_int,
 ) -> *const c_char {
+    let path = file_path(path);
+    let text = match std::fs::read_to_string(&path) {
+        Ok(text) => text,
+        Err(_) => {
+            let mockup = mockup(ptr);
+            mockup.error = CString::new(format!("could not read '{}'", path.display())).unwrap();
+            return mockup.error.as_ptr();
+        }
+    };
+    for (index, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.starts_with('c') || line.is_empty() {
+            continue;
+        }
+        let mut tokens = line.split_whitespace();
+        if line.starts_with('p') {
+            let count = tokens.nth(2).and_then(|count| count.parse().ok());
+            *vars = count.unwrap_or(0);
+            ccadical_reserve(ptr, *vars);
+            continue;
+        }
+        for token in tokens {
+            match token.parse::<i32>() {
+                Ok(lit) if lit != i32::MIN => ccadical_add(ptr, lit),
+                _ => {
+                    let mockup = mockup(ptr);
+                    let msg = format!(
+                        "{}:{}: parse error: invalid literal",
+                        path.display(),
+                        index + 1
+                    );
+                    mockup.error = CString::new(msg).unwrap();
+                    return mockup.error.as_ptr();
+                }
+            }
+        }
+    }
     null::<c_char>()
 }
 
@@ -160,31 +626,371 @@ pub unsafe fn ccadical_write_dimacs(
     path: *const c_char,
     min_max_var: c_int,
 ) -> *const c_char {
+    let mockup = mockup(ptr);
+    let path = file_path(path);
+    let vars = mockup.max_var.max(min_max_var);
+    let mut text = format!("p cnf {} {}\n", vars, mockup.formula.len());
+    for clause in mockup.formula.iter() {
+        for lit in clause {
+            text += &format!("{} ", lit);
+        }
+        text += "0\n";
+    }
+    if std::fs::write(&path, text).is_err() {
+        mockup.error = CString::new(format!("could not write '{}'", path.display())).unwrap();
+        return mockup.error.as_ptr();
+    }
     null::<c_char>()
 }
 
 pub unsafe fn ccadical_configure(ptr: *mut c_void, name: *const c_char) -> c_int {
-    0
+    let name = CStr::from_ptr(name).to_bytes();
+    [&b"default"[..], b"plain", b"sat", b"unsat"].contains(&name) as c_int
 }
 
 pub unsafe fn ccadical_limit2(ptr: *mut c_void, name: *const c_char, limit: c_int) -> c_int {
-    let mockup = &mut *(ptr as *mut Mockup);
-    let name = CStr::from_ptr(name).to_str().unwrap();
-    if name == "conflicts" {
-        mockup.conflicts = limit;
-        1
-    } else if name == "decisions" {
-        mockup.decisions = limit;
-        1
+    let mockup = mockup(ptr);
+    match CStr::from_ptr(name).to_bytes() {
+        b"conflicts" => mockup.conflict_limit = limit,
+        b"decisions" => mockup.decision_limit = limit,
+        b"preprocessing" | b"localsearch" | b"terminate" => {}
+        _ => return 0,
+    }
+    1
+}
+
+pub unsafe fn ccadical_add_clause(ptr: *mut c_void, lits: *const c_int, len: usize) -> c_int {
+    for i in 0..len {
+        ccadical_add(ptr, *lits.add(i));
+    }
+    ccadical_add(ptr, 0);
+    0
+}
+
+pub unsafe fn ccadical_assume_all(ptr: *mut c_void, lits: *const c_int, len: usize) -> c_int {
+    for i in 0..len {
+        ccadical_assume(ptr, *lits.add(i));
+    }
+    0
+}
+
+pub unsafe fn ccadical_reserve(ptr: *mut c_void, min_max_var: c_int) -> c_int {
+    println!("vars");
+    let mockup = mockup(ptr);
+    mockup.touch();
+    mockup.import(min_max_var);
+    0
+}
+
+pub unsafe fn ccadical_reserve_clauses(ptr: *mut c_void, count: usize) -> c_int {
+    mockup(ptr).formula.reserve(count);
+    0
+}
+
+pub unsafe fn ccadical_traverse_clauses(
+    ptr: *mut c_void,
+    data: *mut c_void,
+    cbs: extern "C" fn(*mut c_void, *const c_int, usize) -> c_int,
+) -> c_int {
+    let formula = mockup(ptr).formula.clone();
+    for clause in formula.iter() {
+        if cbs(data, clause.as_ptr(), clause.len()) == 0 {
+            return 0;
+        }
+    }
+    1
+}
+
+pub unsafe fn ccadical_simplify(ptr: *mut c_void, rounds: c_int) -> c_int {
+    let mockup = mockup(ptr);
+    mockup.touch();
+    // the assumptions and the constraint only last for one call
+    mockup.assumptions.clear();
+    mockup.constraint_clause = None;
+    if mockup.inconsistent {
+        20
     } else {
         0
     }
 }
 
-pub unsafe fn ccadical_reserve(ptr: *mut c_void, min_max_var: c_int) {
-    println!("vars");
-    let mockup = &mut *(ptr as *mut Mockup);
-    if (mockup.vars.len() as i32) < min_max_var {
-        mockup.vars.resize(min_max_var as usize, false);
+pub unsafe fn ccadical_prioritize(ptr: *mut c_void, lit: c_int) {}
+
+pub unsafe fn ccadical_freeze(ptr: *mut c_void, lit: c_int) {
+    let mockup = mockup(ptr);
+    mockup.import(lit);
+    let var = lit.unsigned_abs() as usize;
+    if mockup.frozen.len() <= var {
+        mockup.frozen.resize(var + 1, 0);
+    }
+    mockup.frozen[var] += 1;
+}
+
+pub unsafe fn ccadical_melt(ptr: *mut c_void, lit: c_int) {
+    let mockup = mockup(ptr);
+    let var = lit.unsigned_abs() as usize;
+    assert!(mockup.frozen.get(var).copied().unwrap_or(0) > 0);
+    mockup.frozen[var] -= 1;
+}
+
+pub unsafe fn ccadical_frozen(ptr: *mut c_void, lit: c_int) -> c_int {
+    let mockup = mockup(ptr);
+    let var = lit.unsigned_abs() as usize;
+    (mockup.frozen.get(var).copied().unwrap_or(0) > 0) as c_int
+}
+
+pub unsafe fn ccadical_set_option2(ptr: *mut c_void, name: *const c_char, val: c_int) -> c_int {
+    let mockup = mockup(ptr);
+    let name = CStr::from_ptr(name).to_string_lossy();
+    let option = OPTIONS.iter().find(|option| option.0 == name);
+    let &(_, _, min, max) = match option {
+        Some(option) => option,
+        None => return 0,
+    };
+    mockup.options.retain(|(key, _)| key != &name);
+    mockup
+        .options
+        .push((name.into_owned(), val.max(min).min(max)));
+    1
+}
+
+pub unsafe fn ccadical_option_range(
+    _ptr: *mut c_void,
+    name: *const c_char,
+    min: *mut c_int,
+    max: *mut c_int,
+) -> c_int {
+    let name = CStr::from_ptr(name).to_string_lossy();
+    match OPTIONS.iter().find(|option| option.0 == name) {
+        Some(option) => {
+            *min = option.2;
+            *max = option.3;
+            1
+        }
+        None => 0,
     }
 }
+
+pub unsafe fn ccadical_option_default(_ptr: *mut c_void, name: *const c_char) -> c_int {
+    let name = CStr::from_ptr(name).to_string_lossy();
+    OPTIONS
+        .iter()
+        .find(|option| option.0 == name)
+        .map_or(0, |option| option.1)
+}
+
+pub unsafe fn ccadical_option_name(_ptr: *mut c_void, index: c_int) -> *const c_char {
+    match OPTION_NAMES.get(index as usize) {
+        Some(name) if index >= 0 => name.as_ptr() as *const c_char,
+        _ => std::ptr::null(),
+    }
+}
+
+pub unsafe fn ccadical_set_option_live(ptr: *mut c_void, name: *const c_char, val: c_int) -> c_int {
+    let live = ["log", "quiet", "report", "verbose"];
+    let key = CStr::from_ptr(name).to_string_lossy();
+    if ccadical_state(ptr) != 2 && !live.contains(&key.as_ref()) {
+        return 0;
+    }
+    ccadical_set_option2(ptr, name, val)
+}
+
+pub unsafe fn ccadical_get_option(ptr: *mut c_void, name: *const c_char) -> c_int {
+    let mockup = mockup(ptr);
+    let name = CStr::from_ptr(name).to_string_lossy();
+    let option = mockup.options.iter().find(|(key, _)| key == &name);
+    match option {
+        Some(&(_, val)) => val,
+        None => OPTIONS
+            .iter()
+            .find(|option| option.0 == name)
+            .map_or(0, |option| option.1),
+    }
+}
+
+pub unsafe fn ccadical_constrain(ptr: *mut c_void, lit: c_int) {
+    let mockup = mockup(ptr);
+    mockup.touch();
+    if lit == 0 {
+        mockup.constraint_clause = Some(std::mem::take(&mut mockup.constraint));
+    } else {
+        mockup.import(lit);
+        mockup.constraint.push(lit);
+    }
+}
+
+pub unsafe fn ccadical_constraint_failed(ptr: *mut c_void) -> c_int {
+    mockup(ptr).constraint_failed as c_int
+}
+
+pub unsafe fn ccadical_reset_assumptions(ptr: *mut c_void) {
+    mockup(ptr).assumptions.clear();
+}
+
+pub unsafe fn ccadical_reset_constraint(ptr: *mut c_void) {
+    let mockup = mockup(ptr);
+    mockup.constraint.clear();
+    mockup.constraint_clause = None;
+}
+
+pub unsafe fn ccadical_conflicts(ptr: *mut c_void) -> i64 {
+    mockup(ptr).conflicts
+}
+
+pub unsafe fn ccadical_decisions(ptr: *mut c_void) -> i64 {
+    mockup(ptr).decisions
+}
+
+pub unsafe fn ccadical_state(ptr: *mut c_void) -> c_int {
+    let mockup = mockup(ptr);
+    if mockup.status == 10 {
+        32
+    } else if mockup.status == 20 {
+        64
+    } else if !mockup.clause.is_empty() {
+        8
+    } else if mockup.configuring {
+        2
+    } else {
+        4
+    }
+}
+
+pub unsafe fn ccadical_is_inconsistent(ptr: *mut c_void) -> c_int {
+    mockup(ptr).inconsistent as c_int
+}
+
+#[cfg(feature = "profiling")]
+pub unsafe fn ccadical_profile(ptr: *mut c_void, name: *const c_char) -> f64 {
+    let mockup = mockup(ptr);
+    match CStr::from_ptr(name).to_bytes() {
+        b"solve" => mockup.solve_time,
+        b"search" => mockup.search_time,
+        _ => 0.0,
+    }
+}
+
+pub unsafe fn ccadical_restarts(ptr: *mut c_void) -> i64 {
+    0
+}
+
+pub unsafe fn ccadical_level(ptr: *mut c_void) -> c_int {
+    mockup(ptr).control.len() as c_int
+}
+
+pub unsafe fn ccadical_decision(ptr: *mut c_void) -> c_int {
+    let mockup = mockup(ptr);
+    mockup.control.last().map_or(0, |&(_, lit, _)| lit)
+}
+
+pub unsafe fn ccadical_learned_levels(
+    ptr: *mut c_void,
+    clause: *const c_int,
+    len: usize,
+    jump: *mut c_int,
+) -> c_int {
+    let mockup = mockup(ptr);
+    let mut levels: Vec<i32> = (0..len)
+        .map(|i| *clause.add(i))
+        .map(|lit| mockup.levels.get(lit.unsigned_abs() as usize).copied())
+        .map(|level| level.unwrap_or(0))
+        .collect();
+    levels.sort_unstable();
+    *jump = if len > 1 { levels[len - 2] } else { 0 };
+    levels.dedup();
+    levels.len() as c_int
+}
+
+pub unsafe fn ccadical_propagations(ptr: *mut c_void) -> i64 {
+    mockup(ptr).propagations
+}
+
+pub unsafe fn ccadical_learned(ptr: *mut c_void) -> i64 {
+    mockup(ptr).learned
+}
+
+pub unsafe fn ccadical_report(ptr: *mut c_void, report: *mut i64) {
+    let active = ccadical_active(ptr);
+    let mockup = mockup(ptr);
+    let columns = [
+        mockup.conflicts,
+        0,
+        0,
+        mockup.control.len() as i64,
+        mockup.trail.len() as i64,
+        mockup.formula.len() as i64,
+        mockup.learned,
+        active,
+        mockup.max_var as i64,
+    ];
+    std::ptr::copy_nonoverlapping(columns.as_ptr(), report, columns.len());
+}
+
+pub unsafe fn ccadical_proof_open() -> *mut c_void {
+    Box::into_raw(Box::<Vec<u8>>::default()) as *mut c_void
+}
+
+pub unsafe fn ccadical_trace_proof(ptr: *mut c_void, file: *mut c_void) -> c_int {
+    0
+}
+
+pub unsafe fn ccadical_proof_size(ptr: *mut c_void, file: *mut c_void) -> u64 {
+    0
+}
+
+pub unsafe fn ccadical_proof_read(
+    ptr: *mut c_void,
+    file: *mut c_void,
+    offset: u64,
+    buffer: *mut u8,
+    size: usize,
+) -> usize {
+    0
+}
+
+pub unsafe fn ccadical_proof_close(file: *mut c_void) {
+    drop(Box::from_raw(file as *mut Vec<u8>));
+}
+
+#[cfg(feature = "cpp-tracing")]
+pub unsafe fn ccadical_trace_open(path: *const c_char) -> *mut c_void {
+    match std::fs::File::create(file_path(path)) {
+        Ok(file) => Box::into_raw(Box::new(file)) as *mut c_void,
+        Err(_) => null_mut(),
+    }
+}
+
+#[cfg(feature = "cpp-tracing")]
+pub unsafe fn ccadical_trace_api_calls(ptr: *mut c_void, file: *mut c_void) {
+    use std::io::Write;
+    let file = &mut *(file as *mut std::fs::File);
+    let _ = writeln!(file, "init");
+}
+
+#[cfg(feature = "cpp-tracing")]
+pub unsafe fn ccadical_trace_close(file: *mut c_void) {
+    drop(Box::from_raw(file as *mut std::fs::File));
+}
+
+#[cfg(feature = "memory-accounting")]
+pub unsafe fn ccadical_account_new() -> *mut c_void {
+    null_mut()
+}
+
+#[cfg(feature = "memory-accounting")]
+pub unsafe fn ccadical_account_release(account: *mut c_void) {}
+
+#[cfg(feature = "memory-accounting")]
+pub unsafe fn ccadical_account_enter(account: *mut c_void) -> *mut c_void {
+    null_mut()
+}
+
+#[cfg(feature = "memory-accounting")]
+pub unsafe fn ccadical_account_current(account: *mut c_void) -> i64 {
+    0
+}
+
+#[cfg(feature = "memory-accounting")]
+pub unsafe fn ccadical_account_peak(account: *mut c_void) -> i64 {
+    0
+}
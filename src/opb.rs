@@ -0,0 +1,141 @@
+//! Reading of linear pseudo-Boolean instances in the OPB format of the
+//! pseudo-Boolean competitions.
+
+use super::encode::{PbConstraint, Relation};
+use super::{Callbacks, Error, Solver};
+use std::path::Path;
+
+/// A linear pseudo-Boolean instance with an optional objective function to
+/// be minimized. The OPB variable `xN` is the literal `N`, and `~xN` is the
+/// literal `-N`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct OpbInstance {
+    /// The number of variables declared in the header, or the maximum
+    /// variable index if there is no header.
+    pub variables: i32,
+    /// The terms of the objective function if present.
+    pub objective: Option<Vec<(i64, i32)>>,
+    /// The constraints of the instance.
+    pub constraints: Vec<PbConstraint>,
+}
+
+impl OpbInstance {
+    /// Parses an instance in OPB format. Non-linear (product) terms are not
+    /// supported.
+    /// # Examples
+    /// ```
+    /// let text = "* #variable= 2 #constraint= 1\nmin: +1 x1 +2 x2 ;\n+1 x1 +1 ~x2 >= 1 ;\n";
+    /// let instance = cadical::OpbInstance::parse(text).unwrap();
+    /// assert_eq!(instance.variables, 2);
+    /// assert_eq!(instance.objective, Some(vec![(1, 1), (2, 2)]));
+    /// assert_eq!(instance.constraints[0].terms, vec![(1, 1), (1, -2)]);
+    /// ```
+    pub fn parse(text: &str) -> Result<Self, Error> {
+        let mut instance = OpbInstance::default();
+        let mut declared = None;
+        let mut statements = String::new();
+        for line in text.lines() {
+            let line = line.trim();
+            if let Some(comment) = line.strip_prefix('*') {
+                if declared.is_none() {
+                    declared = parse_header(comment);
+                }
+            } else {
+                statements.push_str(line);
+                statements.push('\n');
+            }
+        }
+
+        let mut parts = statements.split(';');
+        let last = parts.next_back().unwrap_or("");
+        if !last.trim().is_empty() {
+            return Err(Error::new("missing semicolon"));
+        }
+        for statement in parts {
+            let mut tokens: Vec<&str> = statement.split_whitespace().collect();
+            if tokens.first() == Some(&"min:") {
+                if instance.objective.is_some() || !instance.constraints.is_empty() {
+                    return Err(Error::new("misplaced objective"));
+                }
+                instance.objective = Some(parse_terms(&tokens[1..])?);
+                continue;
+            }
+            if tokens.len() < 2 {
+                return Err(Error::new("invalid constraint"));
+            }
+            let bound = tokens.pop().unwrap();
+            let bound = bound.parse().map_err(|_| Error::new("invalid bound"))?;
+            let relation = match tokens.pop().unwrap() {
+                ">=" => Relation::AtLeast,
+                "<=" => Relation::AtMost,
+                "=" => Relation::Equal,
+                _ => return Err(Error::new("invalid relation")),
+            };
+            let terms = parse_terms(&tokens)?;
+            instance.constraints.push(PbConstraint {
+                terms,
+                relation,
+                bound,
+            });
+        }
+
+        let terms = instance.objective.iter().flatten();
+        let terms = terms.chain(instance.constraints.iter().flat_map(|c| c.terms.iter()));
+        let max_var = terms.map(|&(_, lit)| lit.abs()).max().unwrap_or(0);
+        instance.variables = declared.unwrap_or(0).max(max_var);
+        Ok(instance)
+    }
+}
+
+/// Returns the number of variables from the `#variable=` header comment.
+fn parse_header(comment: &str) -> Option<i32> {
+    let mut tokens = comment.split_whitespace();
+    tokens.find(|&token| token == "#variable=")?;
+    tokens.next()?.parse().ok()
+}
+
+/// Parses a list of linear terms, each a coefficient and a literal.
+fn parse_terms(tokens: &[&str]) -> Result<Vec<(i64, i32)>, Error> {
+    if tokens.len() & 1 != 0 {
+        return Err(Error::new("non-linear terms are not supported"));
+    }
+    let mut terms = Vec::new();
+    for pair in tokens.chunks(2) {
+        let coef = pair[0].parse();
+        let coef = coef.map_err(|_| Error::new("invalid coefficient"))?;
+        let (negated, name) = match pair[1].strip_prefix('~') {
+            Some(name) => (true, name),
+            None => (false, pair[1]),
+        };
+        let var: i32 = name
+            .strip_prefix('x')
+            .and_then(|index| index.parse().ok())
+            .filter(|&var| var > 0)
+            .ok_or_else(|| Error::new("invalid literal"))?;
+        terms.push((coef, if negated { -var } else { var }));
+    }
+    Ok(terms)
+}
+
+impl<C: Callbacks> Solver<C> {
+    /// Adds the constraints of the instance to the solver through the
+    /// pseudo-Boolean encoder. The variables of the instance are reserved
+    /// first, so the auxiliary variables of the encoding come after them.
+    /// The objective is not used, pass it to `minimize` for optimization.
+    pub fn add_opb(&mut self, instance: &OpbInstance) {
+        self.reserve(instance.variables);
+        for constraint in instance.constraints.iter() {
+            self.add_pb_constraint(constraint);
+        }
+    }
+
+    /// Reads an instance in OPB format from the given file, adds its
+    /// constraints to the solver with `add_opb`, and returns it, so that its
+    /// objective can be passed to `minimize`.
+    pub fn read_opb(&mut self, path: &Path) -> Result<OpbInstance, Error> {
+        let text = std::fs::read_to_string(path).map_err(|_| Error::new("cannot read file"))?;
+        let instance = OpbInstance::parse(&text)?;
+        self.add_opb(&instance);
+        Ok(instance)
+    }
+}
@@ -0,0 +1,80 @@
+//! Minimization of linear objective functions by repeated solving.
+
+use super::encode::{PbConstraint, Relation};
+use super::{Callbacks, Solver};
+
+/// The best solution found by `Solver::minimize`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Optimum {
+    /// The value of the objective function for the model.
+    pub cost: i64,
+    /// The model as the list of true literals, one for each variable up to
+    /// the maximum variable at the start of the optimization.
+    pub model: Vec<i32>,
+    /// Whether the cost was proved to be minimal. This is `false` if the
+    /// solver was interrupted before the proof was complete.
+    pub optimal: bool,
+}
+
+impl<C: Callbacks> Solver<C> {
+    /// Minimizes the weighted sum of the given literals subject to the
+    /// clauses of the solver by linear search: after each model a
+    /// pseudo-Boolean constraint requiring a strictly smaller cost is added,
+    /// until the formula becomes unsatisfiable. These constraints are added
+    /// in a new assertion scope, which is closed at the end, so the formula
+    /// is left unchanged. Returns `None` if the formula is unsatisfiable or
+    /// no model was found before the solver was interrupted.
+    /// # Examples
+    /// ```
+    /// let mut sat: cadical::Solver = Default::default();
+    /// sat.add_clause([1, 2]);
+    /// sat.add_clause([2, 3]);
+    /// let optimum = sat.minimize(&[(3, 1), (4, 2), (2, 3)]).unwrap();
+    /// assert_eq!(optimum.cost, 4);
+    /// assert!(optimum.optimal);
+    /// ```
+    pub fn minimize(&mut self, objective: &[(i64, i32)]) -> Option<Optimum> {
+        let vars = self.max_variable();
+        let mut best: Option<Optimum> = None;
+        self.push();
+        loop {
+            match self.solve() {
+                Some(true) => {
+                    let model: Vec<i32> = (1..=vars)
+                        .map(|var| {
+                            if self.value(var) == Some(true) {
+                                var
+                            } else {
+                                -var
+                            }
+                        })
+                        .collect();
+                    let cost = objective
+                        .iter()
+                        .filter(|&&(_, lit)| self.value(lit) == Some(true))
+                        .map(|&(coef, _)| coef)
+                        .sum();
+                    best = Some(Optimum {
+                        cost,
+                        model,
+                        optimal: false,
+                    });
+                    self.add_pb_constraint(&PbConstraint {
+                        terms: objective.to_vec(),
+                        relation: Relation::AtMost,
+                        bound: cost - 1,
+                    });
+                }
+                Some(false) => {
+                    if let Some(best) = best.as_mut() {
+                        best.optimal = true;
+                    }
+                    break;
+                }
+                None => break,
+            }
+        }
+        self.pop().unwrap();
+        best
+    }
+}
@@ -0,0 +1,68 @@
+//! Errors of setting the options of the solver.
+
+use super::ffi::*;
+use super::{Callbacks, Error, Solver};
+use std::ffi::CString;
+use std::os::raw::c_int;
+use std::{error, fmt};
+
+/// The error returned by `Solver::set_option`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum OptionError {
+    /// There is no option with the given name.
+    UnknownOption,
+    /// The option exists, but the value is outside of its range.
+    ValueOutOfRange {
+        /// The smallest allowed value of the option.
+        min: i32,
+        /// The largest allowed value of the option.
+        max: i32,
+    },
+    /// The name of the option contains a zero byte.
+    InvalidString,
+    /// Options can only be set before any clauses are added.
+    InvalidState,
+}
+
+impl fmt::Display for OptionError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            OptionError::UnknownOption => write!(f, "unknown option"),
+            OptionError::ValueOutOfRange { min, max } => {
+                write!(f, "value out of range {}..={}", min, max)
+            }
+            OptionError::InvalidString => write!(f, "invalid string"),
+            OptionError::InvalidState => write!(f, "invalid state"),
+        }
+    }
+}
+
+impl error::Error for OptionError {}
+
+impl From<OptionError> for Error {
+    fn from(err: OptionError) -> Self {
+        Error::new(&err.to_string())
+    }
+}
+
+impl<C: Callbacks> Solver<C> {
+    /// Returns the smallest and largest allowed values of the internal
+    /// option with the given name, or `None` if there is no such option.
+    /// # Examples
+    /// ```
+    /// let sat: cadical::Solver = Default::default();
+    /// assert_eq!(sat.option_range("phase"), Some((0, 1)));
+    /// assert_eq!(sat.option_range("bad"), None);
+    /// ```
+    pub fn option_range(&self, name: &str) -> Option<(i32, i32)> {
+        let name = CString::new(name).ok()?;
+        let mut min: c_int = 0;
+        let mut max: c_int = 0;
+        let valid = unsafe { ccadical_option_range(self.ptr, name.as_ptr(), &mut min, &mut max) };
+        if valid != 0 {
+            Some((min, max))
+        } else {
+            None
+        }
+    }
+}
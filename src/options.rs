@@ -0,0 +1,105 @@
+//! Snapshots of the internal options of the solver.
+
+use super::ffi::*;
+use super::{Callbacks, OptionError, Solver, State};
+use std::collections::BTreeMap;
+use std::ffi::{CStr, CString};
+use std::os::raw::c_int;
+
+/// The options that CaDiCaL accepts in every state of the solver, the others
+/// can only be changed before clauses are added.
+const LIVE_OPTIONS: [&str; 4] = ["log", "quiet", "report", "verbose"];
+
+/// The values of the internal options of a solver that differ from their
+/// defaults, returned by `Solver::options_snapshot`, so experiments can switch
+/// between tuned configurations with `Solver::apply`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct OptionsSnapshot {
+    /// The options with values different from their defaults.
+    pub values: BTreeMap<String, i32>,
+}
+
+impl<C: Callbacks> Solver<C> {
+    /// Returns the names of all internal options.
+    fn option_names(&self) -> Vec<&'static CStr> {
+        let mut names = Vec::new();
+        for index in 0.. {
+            let name = unsafe { ccadical_option_name(self.ptr, index as c_int) };
+            if name.is_null() {
+                break;
+            }
+            names.push(unsafe { CStr::from_ptr(name) });
+        }
+        names
+    }
+
+    /// Returns the internal options whose current values differ from their
+    /// defaults, including the ones changed by the configuration preset.
+    /// # Examples
+    /// ```
+    /// let mut sat: cadical::Solver = Default::default();
+    /// sat.set_option("phase", 0).unwrap();
+    /// let tuned = sat.options_snapshot();
+    /// sat.reset_options().unwrap();
+    /// assert_eq!(sat.get_option("phase"), 1);
+    /// let mut other: cadical::Solver = Default::default();
+    /// other.apply(&tuned).unwrap();
+    /// assert_eq!(other.get_option("phase"), 0);
+    /// ```
+    pub fn options_snapshot(&self) -> OptionsSnapshot {
+        let mut values = BTreeMap::new();
+        for name in self.option_names() {
+            let value = unsafe { ccadical_get_option(self.ptr, name.as_ptr()) };
+            let default = unsafe { ccadical_option_default(self.ptr, name.as_ptr()) };
+            if value != default {
+                values.insert(name.to_string_lossy().into_owned(), value);
+            }
+        }
+        OptionsSnapshot { values }
+    }
+
+    /// Sets the internal options to the values of the snapshot and all other
+    /// options to their defaults. Once clauses are added, CaDiCaL only accepts
+    /// changes of the `log`, `quiet`, `report` and `verbose` options, so other
+    /// changes are rejected with `OptionError::InvalidState`. The snapshot is
+    /// checked before any option is changed, so the options are left intact
+    /// on an error.
+    pub fn apply(&mut self, snapshot: &OptionsSnapshot) -> Result<(), OptionError> {
+        for (name, &value) in snapshot.values.iter() {
+            CString::new(name.as_str()).map_err(|_| OptionError::InvalidString)?;
+            let (min, max) = self.option_range(name).ok_or(OptionError::UnknownOption)?;
+            if value < min || value > max {
+                return Err(OptionError::ValueOutOfRange { min, max });
+            }
+        }
+        let configuring = self.state() == State::Configuring;
+        let mut changes = Vec::new();
+        for name in self.option_names() {
+            let key = name.to_string_lossy();
+            let value = match snapshot.values.get(key.as_ref()) {
+                Some(&value) => value,
+                None => unsafe { ccadical_option_default(self.ptr, name.as_ptr()) },
+            };
+            if unsafe { ccadical_get_option(self.ptr, name.as_ptr()) } != value {
+                if !configuring && !LIVE_OPTIONS.contains(&key.as_ref()) {
+                    return Err(OptionError::InvalidState);
+                }
+                changes.push((name, value));
+            }
+        }
+        for (name, value) in changes {
+            unsafe { ccadical_set_option_live(self.ptr, name.as_ptr(), value) };
+        }
+        self.config = None;
+        self.options = snapshot.values.clone();
+        Ok(())
+    }
+
+    /// Sets every internal option back to its default value, including the
+    /// ones changed by the configuration preset, with the same restrictions
+    /// as `apply`.
+    pub fn reset_options(&mut self) -> Result<(), OptionError> {
+        self.apply(&OptionsSnapshot::default())
+    }
+}
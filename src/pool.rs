@@ -0,0 +1,101 @@
+//! Owned learned clauses backed by a pool of reusable buffers.
+
+use super::{Callbacks, Solver};
+use smallvec::SmallVec;
+use std::sync::{Arc, Mutex};
+
+/// An owned learned clause, stored inline up to eight literals.
+pub type LearnedClause = SmallVec<[i32; 8]>;
+
+/// The maximum number of buffers kept by a pool.
+const MAX_POOLED: usize = 1024;
+
+/// A pool of clause buffers for the owned learn mode, where the learned
+/// clauses are passed to `Callbacks::learn_owned` by value. Short clauses
+/// are stored inline, longer ones reuse the buffers returned to the pool
+/// with `recycle`, so callbacks that keep the clauses do not copy or
+/// allocate in the hot path. The pool can be cloned and used from any
+/// thread, for example by the consumer of shared clauses.
+#[derive(Clone, Debug, Default)]
+pub struct ClausePool {
+    free: Arc<Mutex<Vec<LearnedClause>>>,
+}
+
+impl ClausePool {
+    /// Creates a new empty pool.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Returns an owned copy of the given clause, reusing a buffer of the
+    /// pool if the clause does not fit inline.
+    pub fn take(&self, lits: &[i32]) -> LearnedClause {
+        if lits.len() > 8 {
+            let mut free = self.free.lock().unwrap_or_else(|err| err.into_inner());
+            if let Some(mut clause) = free.pop() {
+                clause.clear();
+                clause.extend_from_slice(lits);
+                return clause;
+            }
+        }
+        SmallVec::from_slice(lits)
+    }
+
+    /// Returns the buffer of a clause to the pool. Inline clauses own no
+    /// buffer and are simply dropped.
+    pub fn recycle(&self, clause: LearnedClause) {
+        if clause.spilled() {
+            let mut free = self.free.lock().unwrap_or_else(|err| err.into_inner());
+            if free.len() < MAX_POOLED {
+                free.push(clause);
+            }
+        }
+    }
+
+    /// Returns the number of buffers in the pool.
+    pub fn len(&self) -> usize {
+        self.free
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+            .len()
+    }
+
+    /// Checks if the pool has no buffers.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl<C: Callbacks> Solver<C> {
+    /// Returns the pool that the owned learned clauses of this solver are
+    /// allocated from, where the callbacks or any other thread can recycle
+    /// them.
+    /// # Examples
+    /// ```
+    /// use cadical::{Callbacks, ClausePool, LearnedClause};
+    /// struct Keep(Vec<LearnedClause>);
+    /// impl Callbacks for Keep {
+    ///     fn max_length(&self) -> i32 {
+    ///         100
+    ///     }
+    ///     fn learn_owned_clauses(&self) -> bool {
+    ///         true
+    ///     }
+    ///     fn learn_owned(&mut self, clause: LearnedClause) {
+    ///         self.0.push(clause);
+    ///     }
+    /// }
+    ///
+    /// let mut sat: cadical::Solver<Keep> = cadical::Solver::new();
+    /// sat.set_callbacks(Some(Keep(Vec::new())));
+    /// sat.add_formula(&cadical::generators::pigeon_hole(5));
+    /// assert_eq!(sat.solve(), Some(false));
+    /// let pool: ClausePool = sat.clause_pool();
+    /// for clause in sat.get_callbacks().unwrap().0.drain(..) {
+    ///     pool.recycle(clause);
+    /// }
+    /// ```
+    pub fn clause_pool(&self) -> ClausePool {
+        self.clause_pool.clone()
+    }
+}
@@ -0,0 +1,612 @@
+//! Logging of the DRAT proofs of the solver to a temporary file, and their
+//! trimming to the original clauses used in the refutation.
+
+use super::ffi::*;
+use super::{Callbacks, ClauseStore, CnfFormula, Error, Solver, State};
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::os::raw::c_void;
+
+/// The reason of the decisions of the checker.
+const NO_REASON: usize = usize::MAX;
+
+/// The proof file of the solver together with the clauses and the
+/// constraint added since the logging was enabled. Every clause remembers
+/// the size of the proof when it was added, so the checker sees the clauses
+/// added between incremental solve calls at the right point of the proof.
+pub(crate) struct ProofLog {
+    file: *mut c_void,
+    clauses: ClauseStore,
+    positions: Vec<u64>,
+    constraint: Option<Vec<i32>>,
+    last_constraint: Option<Vec<i32>>,
+}
+
+impl ProofLog {
+    /// Opens a new proof file and starts tracing the proof of the solver,
+    /// which must be in the configuring state.
+    pub(crate) fn open(ptr: *mut c_void) -> Result<Self, Error> {
+        let file = unsafe { ccadical_proof_open() };
+        if file.is_null() {
+            return Err(Error::new("cannot open proof file"));
+        }
+        let log = ProofLog {
+            file,
+            clauses: ClauseStore::new(),
+            positions: Vec::new(),
+            constraint: None,
+            last_constraint: None,
+        };
+        if unsafe { ccadical_trace_proof(ptr, file) } == 0 {
+            return Err(Error::new("proof logging is not supported"));
+        }
+        Ok(log)
+    }
+
+    /// Records a clause that was added to the solver.
+    #[inline]
+    pub(crate) fn record(&mut self, ptr: *mut c_void, clause: &[i32]) {
+        self.positions
+            .push(unsafe { ccadical_proof_size(ptr, self.file) });
+        self.clauses.push(clause);
+    }
+
+    /// Records the constraint of the next solve call.
+    pub(crate) fn set_constraint(&mut self, clause: Option<Vec<i32>>) {
+        self.constraint = clause;
+    }
+
+    /// Moves the constraint to the last solve call when it returns.
+    pub(crate) fn solved(&mut self) {
+        self.last_constraint = self.constraint.take();
+    }
+
+    /// Returns the proof written by the solver so far.
+    fn read(&self, ptr: *mut c_void) -> Vec<u8> {
+        let mut proof = Vec::new();
+        let mut buffer = vec![0u8; 1 << 16];
+        loop {
+            let count = unsafe {
+                ccadical_proof_read(
+                    ptr,
+                    self.file,
+                    proof.len() as u64,
+                    buffer.as_mut_ptr(),
+                    buffer.len(),
+                )
+            };
+            if count == 0 {
+                return proof;
+            }
+            proof.extend_from_slice(&buffer[..count]);
+        }
+    }
+}
+
+impl Drop for ProofLog {
+    fn drop(&mut self) {
+        unsafe { ccadical_proof_close(self.file) };
+    }
+}
+
+/// Parses a binary DRAT proof into its steps together with their offsets in
+/// the proof, where `true` marks the added and `false` the deleted clauses.
+pub(crate) fn parse_binary_drat(bytes: &[u8]) -> Result<Vec<(u64, bool, Vec<i32>)>, Error> {
+    let mut steps = Vec::new();
+    let mut pos = 0;
+    while pos < bytes.len() {
+        let offset = pos as u64;
+        let added = match bytes[pos] {
+            b'a' => true,
+            b'd' => false,
+            _ => return Err(Error::new("invalid proof")),
+        };
+        pos += 1;
+        let mut clause = Vec::new();
+        loop {
+            let (mut code, mut shift) = (0u64, 0);
+            loop {
+                let byte = *bytes.get(pos).ok_or_else(|| Error::new("invalid proof"))?;
+                pos += 1;
+                code |= ((byte & 0x7f) as u64) << shift;
+                if byte & 0x80 == 0 {
+                    break;
+                }
+                shift += 7;
+                if shift > 35 {
+                    return Err(Error::new("invalid proof"));
+                }
+            }
+            if code == 0 {
+                break;
+            }
+            let var = i32::try_from(code >> 1).map_err(|_| Error::new("invalid proof"))?;
+            clause.push(if code & 1 != 0 { -var } else { var });
+        }
+        steps.push((offset, added, clause));
+    }
+    Ok(steps)
+}
+
+/// Where a clause of the checker comes from.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Origin {
+    /// The original clause with the given index.
+    Original(usize),
+    /// A clause added by the proof.
+    Lemma,
+    /// The constraint of the last solve call.
+    Constraint,
+}
+
+struct Clause {
+    lits: Vec<i32>,
+    origin: Origin,
+    active: bool,
+    marked: bool,
+}
+
+/// The clauses added and deleted by the proof and the original clauses added
+/// between them, where additions remember the length of the trail before
+/// them.
+enum Step {
+    Add(usize, usize),
+    Delete(usize),
+}
+
+/// A backward DRAT checker, which verifies the lemmas needed to refute the
+/// original clauses by reverse unit propagation, and marks the clauses used
+/// on the way, in the style of `drat-trim`.
+pub(crate) struct Checker {
+    clauses: Vec<Clause>,
+    index: HashMap<Vec<i32>, Vec<usize>>,
+    deleted: HashMap<Vec<i32>, usize>,
+    watches: Vec<Vec<usize>>,
+    values: Vec<i8>,
+    reasons: Vec<usize>,
+    seen: Vec<bool>,
+    trail: Vec<i32>,
+    propagated: usize,
+    steps: Vec<Step>,
+    conflict: Option<usize>,
+}
+
+fn value_of(values: &[i8], lit: i32) -> i8 {
+    let value = values[lit.unsigned_abs() as usize];
+    if lit < 0 {
+        -value
+    } else {
+        value
+    }
+}
+
+fn watch_index(lit: i32) -> usize {
+    2 * lit.unsigned_abs() as usize + (lit < 0) as usize
+}
+
+/// Returns the sorted literals without duplicates, or `None` for
+/// tautologies.
+fn normalize(clause: &[i32]) -> Option<Vec<i32>> {
+    let mut lits = clause.to_vec();
+    lits.sort_unstable_by_key(|&lit| (lit.unsigned_abs(), lit > 0));
+    lits.dedup();
+    if lits.windows(2).any(|pair| pair[0] == -pair[1]) {
+        None
+    } else {
+        Some(lits)
+    }
+}
+
+impl Checker {
+    pub(crate) fn new() -> Self {
+        Checker {
+            clauses: Vec::new(),
+            index: HashMap::new(),
+            deleted: HashMap::new(),
+            watches: vec![Vec::new(), Vec::new()],
+            values: vec![0],
+            reasons: vec![NO_REASON],
+            seen: vec![false],
+            trail: Vec::new(),
+            propagated: 0,
+            steps: Vec::new(),
+            conflict: None,
+        }
+    }
+
+    fn reserve(&mut self, lits: &[i32]) {
+        let max = lits.iter().map(|lit| lit.unsigned_abs() as usize).max();
+        let len = max.unwrap_or(0) + 1;
+        if self.values.len() < len {
+            self.values.resize(len, 0);
+            self.reasons.resize(len, NO_REASON);
+            self.seen.resize(len, false);
+            self.watches.resize(2 * len, Vec::new());
+        }
+    }
+
+    fn assign(&mut self, lit: i32, reason: usize) {
+        self.values[lit.unsigned_abs() as usize] = if lit > 0 { 1 } else { -1 };
+        self.reasons[lit.unsigned_abs() as usize] = reason;
+        self.trail.push(lit);
+    }
+
+    fn backtrack(&mut self, len: usize) {
+        for lit in self.trail.split_off(len) {
+            self.values[lit.unsigned_abs() as usize] = 0;
+            self.reasons[lit.unsigned_abs() as usize] = NO_REASON;
+        }
+        self.propagated = self.propagated.min(len);
+    }
+
+    /// Activates the clause at the root level by moving two non-false
+    /// literals to the front and watching them, and returns `false` if the
+    /// clause is falsified.
+    fn attach(&mut self, id: usize) -> bool {
+        self.clauses[id].active = true;
+        let mut lits = std::mem::take(&mut self.clauses[id].lits);
+        lits.sort_by_key(|&lit| -value_of(&self.values, lit));
+        let first = lits.first().map_or(-1, |&lit| value_of(&self.values, lit));
+        let second = lits.get(1).map_or(-1, |&lit| value_of(&self.values, lit));
+        for &lit in lits.iter().take(2) {
+            self.watches[watch_index(lit)].push(id);
+        }
+        let unit = first == 0 && second < 0;
+        let lit = lits.first().copied();
+        self.clauses[id].lits = lits;
+        if unit {
+            self.assign(lit.unwrap(), id);
+        }
+        first >= 0
+    }
+
+    /// Propagates the assigned literals with the watch lists of the active
+    /// clauses, and returns the falsified clause if there is a conflict.
+    /// The watches of the inactive clauses are kept, so they are valid
+    /// again when the clauses are reactivated by the backward pass.
+    fn propagate(&mut self) -> Option<usize> {
+        while self.propagated < self.trail.len() {
+            let falsified = -self.trail[self.propagated];
+            self.propagated += 1;
+            let mut watches = std::mem::take(&mut self.watches[watch_index(falsified)]);
+            let mut conflict = None;
+            let (mut index, mut kept) = (0, 0);
+            while index < watches.len() {
+                let id = watches[index];
+                index += 1;
+                let clause = &mut self.clauses[id];
+                if clause.lits.len() < 2
+                    || (clause.lits[0] != falsified && clause.lits[1] != falsified)
+                {
+                    continue;
+                }
+                watches[kept] = id;
+                kept += 1;
+                if !clause.active || conflict.is_some() {
+                    continue;
+                }
+                if clause.lits[0] == falsified {
+                    clause.lits.swap(0, 1);
+                }
+                let first = clause.lits[0];
+                if value_of(&self.values, first) > 0 {
+                    continue;
+                }
+                let values = &self.values;
+                let lits = &mut clause.lits;
+                if let Some(pos) = (2..lits.len()).find(|&pos| value_of(values, lits[pos]) >= 0) {
+                    lits.swap(1, pos);
+                    let other = lits[1];
+                    kept -= 1;
+                    self.watches[watch_index(other)].push(id);
+                } else if value_of(&self.values, first) < 0 {
+                    conflict = Some(id);
+                } else {
+                    self.assign(first, id);
+                }
+            }
+            watches.truncate(kept);
+            self.watches[watch_index(falsified)] = watches;
+            if conflict.is_some() {
+                return conflict;
+            }
+        }
+        None
+    }
+
+    /// Adds a clause with the given origin at the end of the proof.
+    fn add(&mut self, lits: Vec<i32>, origin: Origin) {
+        self.reserve(&lits);
+        if self.conflict.is_some() {
+            return;
+        }
+        let before = self.trail.len();
+        // a deleted original that is added back, like the clauses of the
+        // eliminated variables restored by incremental solving, is reused
+        let restored = match origin {
+            Origin::Lemma => self.deleted.remove(&lits),
+            _ => None,
+        };
+        let id = match restored {
+            Some(id) => id,
+            None => {
+                self.clauses.push(Clause {
+                    lits: lits.clone(),
+                    origin,
+                    active: false,
+                    marked: false,
+                });
+                self.clauses.len() - 1
+            }
+        };
+        if origin != Origin::Constraint {
+            self.index.entry(lits).or_default().push(id);
+        }
+        if origin != Origin::Constraint {
+            self.steps.push(Step::Add(id, before));
+        }
+        let conflict = if self.attach(id) {
+            self.propagate()
+        } else {
+            Some(id)
+        };
+        self.conflict = conflict;
+    }
+
+    /// Adds an original clause at the end of the proof, so the lemmas before
+    /// it are checked without it.
+    pub(crate) fn add_original(&mut self, clause: &[i32], index: usize) {
+        if let Some(lits) = normalize(clause) {
+            self.add(lits, Origin::Original(index));
+        }
+    }
+
+    /// Adds a lemma or deletes a clause of the proof.
+    pub(crate) fn add_step(&mut self, added: bool, clause: &[i32]) {
+        let lits = match normalize(clause) {
+            Some(lits) => lits,
+            None => return,
+        };
+        if added {
+            self.add(lits, Origin::Lemma);
+            return;
+        }
+        if self.conflict.is_some() {
+            return;
+        }
+        let id = match self.index.get_mut(&lits).and_then(|ids| ids.pop()) {
+            Some(id) => id,
+            None => return,
+        };
+        // unit clauses and the reasons of root level literals are kept
+        let reason = lits
+            .iter()
+            .any(|&lit| self.reasons[lit.unsigned_abs() as usize] == id);
+        if lits.len() < 2 || reason {
+            self.index.get_mut(&lits).unwrap().push(id);
+            return;
+        }
+        self.clauses[id].active = false;
+        if let Origin::Original(_) = self.clauses[id].origin {
+            self.deleted.insert(lits, id);
+        }
+        self.steps.push(Step::Delete(id));
+    }
+
+    /// Marks the clauses of the implication graph of the conflict, which
+    /// starts at a falsified clause, or at the reason of a literal whose
+    /// negation is assumed.
+    fn analyze(&mut self, start: usize, implied: i32) {
+        self.clauses[start].marked = true;
+        let mut pending = 0;
+        for &lit in self.clauses[start].lits.iter() {
+            let var = lit.unsigned_abs() as usize;
+            if lit != implied && !self.seen[var] {
+                self.seen[var] = true;
+                pending += 1;
+            }
+        }
+        let mut index = self.trail.len();
+        while pending > 0 {
+            index -= 1;
+            let var = self.trail[index].unsigned_abs() as usize;
+            if !self.seen[var] {
+                continue;
+            }
+            self.seen[var] = false;
+            pending -= 1;
+            let reason = self.reasons[var];
+            if reason == NO_REASON {
+                continue;
+            }
+            self.clauses[reason].marked = true;
+            for &lit in self.clauses[reason].lits.iter() {
+                let other = lit.unsigned_abs() as usize;
+                if other != var && !self.seen[other] {
+                    self.seen[other] = true;
+                    pending += 1;
+                }
+            }
+        }
+    }
+
+    /// Checks that assigning the literals leads to a conflict by unit
+    /// propagation, and marks the clauses used.
+    fn check(&mut self, lits: &[i32]) -> Result<(), Error> {
+        self.reserve(lits);
+        let before = self.trail.len();
+        let mut conflict = None;
+        for &lit in lits.iter() {
+            match value_of(&self.values, lit) {
+                0 => self.assign(lit, NO_REASON),
+                1 => (),
+                _ => {
+                    conflict = Some((self.reasons[lit.unsigned_abs() as usize], -lit));
+                    break;
+                }
+            }
+        }
+        if conflict.is_none() {
+            conflict = self.propagate().map(|id| (id, 0));
+        }
+        if let Some((start, implied)) = conflict {
+            if start != NO_REASON {
+                self.analyze(start, implied);
+            }
+        }
+        self.backtrack(before);
+        match conflict {
+            Some(_) => Ok(()),
+            None => Err(Error::new("proof check failed")),
+        }
+    }
+
+    /// Checks the refutation of the clauses under the assumptions and the
+    /// constraint, and then the lemmas it depends on in reverse order.
+    pub(crate) fn verify(
+        &mut self,
+        assumptions: &[i32],
+        constraint: Option<&[i32]>,
+    ) -> Result<(), Error> {
+        let constraint_id = self.clauses.len();
+        if let (None, Some(clause)) = (self.conflict, constraint) {
+            match normalize(clause) {
+                Some(lits) => self.add(lits, Origin::Constraint),
+                None => return Err(Error::new("proof check failed")),
+            }
+        }
+        match self.conflict {
+            Some(id) => self.analyze(id, 0),
+            None => self.check(assumptions)?,
+        }
+        if let Some(clause) = self.clauses.get_mut(constraint_id) {
+            clause.active = false;
+        }
+        for step in std::mem::take(&mut self.steps).into_iter().rev() {
+            match step {
+                Step::Delete(id) => self.clauses[id].active = true,
+                Step::Add(id, before) => {
+                    self.clauses[id].active = false;
+                    self.backtrack(before);
+                    if self.clauses[id].marked && self.clauses[id].origin == Origin::Lemma {
+                        let lits: Vec<i32> =
+                            self.clauses[id].lits.iter().map(|&lit| -lit).collect();
+                        self.check(&lits)?;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the indices of the original clauses used by the refutation.
+    pub(crate) fn core(&self) -> Vec<usize> {
+        let mut core: Vec<usize> = self
+            .clauses
+            .iter()
+            .filter(|clause| clause.marked)
+            .filter_map(|clause| match clause.origin {
+                Origin::Original(index) => Some(index),
+                _ => None,
+            })
+            .collect();
+        core.sort_unstable();
+        core
+    }
+}
+
+impl<C: Callbacks> Solver<C> {
+    /// Enables the logging of a DRAT proof, which is needed by
+    /// `unsat_core_cnf`. The proof is written to an anonymous temporary file
+    /// on disk, which grows with the search and is only removed when the
+    /// solver is dropped or reset. The added clauses are recorded in memory,
+    /// so the proof can be checked against them. The logging can only be enabled
+    /// before adding any clauses, it stays enabled until the solver is
+    /// dropped (including `reset`), it is not inherited by `fork`, and
+    /// clauses cannot be loaded with `read_dimacs` while it is enabled.
+    pub fn enable_proof_logging(&mut self) -> Result<(), Error> {
+        if self.proof.is_none() {
+            if self.state() != State::Configuring {
+                return Err(Error::new("invalid state"));
+            }
+            self.proof = Some(ProofLog::open(self.ptr)?);
+        }
+        Ok(())
+    }
+
+    /// Returns `true` if the proof logging is enabled.
+    pub fn proof_logging(&self) -> bool {
+        self.proof.is_some()
+    }
+
+    /// Trims the proof of the last unsatisfiable answer and returns the
+    /// subset of the added clauses it actually uses, which is unsatisfiable
+    /// together with the failed assumptions and the constraint. The clauses
+    /// are returned as they were added, but without the activation literals
+    /// of scopes, removable and temporary clauses, and the clauses that only
+    /// consist of activation literals are left out. This requires proof
+    /// logging, and the proof is checked with reverse unit propagation on
+    /// the way, so an error is returned if it is not valid. The returned
+    /// core is not minimal, but usually much smaller than the formula.
+    /// # Examples
+    /// ```
+    /// let mut sat: cadical::Solver = Default::default();
+    /// sat.enable_proof_logging().unwrap();
+    /// sat.add_clause([1, 2]);
+    /// sat.add_clause([-1, 2]);
+    /// sat.add_clause([3, 4]);
+    /// sat.add_clause([-2]);
+    /// assert_eq!(sat.solve(), Some(false));
+    /// let core = sat.unsat_core_cnf().unwrap();
+    /// assert_eq!(core.len(), 3);
+    /// assert!(core.iter().all(|clause| !clause.contains(&3)));
+    /// ```
+    pub fn unsat_core_cnf(&self) -> Result<CnfFormula, Error> {
+        let log = match self.proof.as_ref() {
+            Some(log) => log,
+            None => return Err(Error::new("proof logging is not enabled")),
+        };
+        if self.state() != State::Unsatisfied {
+            return Err(Error::new("invalid state"));
+        }
+        let mut checker = Checker::new();
+        let mut steps = parse_binary_drat(&log.read(self.ptr))?
+            .into_iter()
+            .peekable();
+        for index in 0..log.clauses.len() {
+            // the steps written before the clause was added precede it
+            while matches!(steps.peek(), Some(step) if step.0 < log.positions[index]) {
+                let (_, added, clause) = steps.next().unwrap();
+                checker.add_step(added, &clause);
+            }
+            let clause: Vec<i32> = log.clauses.clause(index).collect();
+            checker.add_original(&clause, index);
+        }
+        for (_, added, clause) in steps {
+            checker.add_step(added, &clause);
+        }
+        let mut failed = Vec::new();
+        for var in 1..=self.max_variable() {
+            for &lit in [var, -var].iter() {
+                if self.failed(lit) {
+                    failed.push(lit);
+                }
+            }
+        }
+        checker.verify(&failed, log.last_constraint.as_deref())?;
+
+        let mut core = CnfFormula::new();
+        for index in checker.core() {
+            let clause: Vec<i32> = log.clauses.clause(index).collect();
+            let lits: Vec<i32> = clause
+                .iter()
+                .copied()
+                .filter(|&lit| !self.is_selector(lit))
+                .collect();
+            if !lits.is_empty() || clause.is_empty() {
+                core.add_clause(lits);
+            }
+        }
+        Ok(core)
+    }
+}
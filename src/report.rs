@@ -0,0 +1,64 @@
+//! Structured versions of the progress reports of the solver.
+
+use super::ffi::*;
+use std::os::raw::c_void;
+use std::time::Instant;
+
+/// The columns of a progress report line of CaDiCaL, passed to
+/// `Callbacks::report` after restarts, so the progress of the search can be
+/// charted without parsing the verbose output of the solver.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ReportEvent {
+    /// The seconds elapsed since the start of the `solve` call.
+    pub seconds: f64,
+    /// The number of conflicts so far.
+    pub conflicts: u64,
+    /// The number of restarts so far.
+    pub restarts: u64,
+    /// The number of reductions of the learned clauses so far.
+    pub reductions: u64,
+    /// The current decision level.
+    pub level: u32,
+    /// The number of assigned literals on the trail.
+    pub trail: u64,
+    /// The number of irredundant clauses.
+    pub irredundant: u64,
+    /// The number of redundant (learned) clauses.
+    pub redundant: u64,
+    /// The number of active variables, which are neither fixed, eliminated
+    /// nor substituted.
+    pub active: u64,
+    /// The number of internal variables.
+    pub variables: u64,
+}
+
+impl ReportEvent {
+    /// Reads the current report of the solver.
+    pub(crate) fn read(ptr: *mut c_void, started: Instant) -> Self {
+        let mut columns = [0i64; 9];
+        unsafe { ccadical_report(ptr, columns.as_mut_ptr()) };
+        ReportEvent {
+            seconds: started.elapsed().as_secs_f64(),
+            conflicts: columns[0] as u64,
+            restarts: columns[1] as u64,
+            reductions: columns[2] as u64,
+            level: columns[3] as u32,
+            trail: columns[4] as u64,
+            irredundant: columns[5] as u64,
+            redundant: columns[6] as u64,
+            active: columns[7] as u64,
+            variables: columns[8] as u64,
+        }
+    }
+
+    /// Returns the percentage of the active variables, which is the
+    /// `remaining` column of the report.
+    pub fn remaining(&self) -> f64 {
+        if self.variables == 0 {
+            0.0
+        } else {
+            100.0 * self.active as f64 / self.variables as f64
+        }
+    }
+}
@@ -0,0 +1,107 @@
+//! Implementation of the solver traits of the `rustsat` crate, which makes
+//! its encodings and MaxSAT algorithms available on top of this solver. This
+//! module is only available with the `rustsat` feature.
+
+use super::{signature, Callbacks, Solver};
+use rustsat::solvers::{Interrupt, InterruptSolver, Solve, SolveIncremental, SolverResult};
+use rustsat::types::{Cl, Clause, Lit, TernaryVal, Var};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+fn literal(lit: Lit) -> i32 {
+    lit.to_ipasir()
+}
+
+impl<C: Callbacks> Extend<Clause> for Solver<C> {
+    fn extend<T: IntoIterator<Item = Clause>>(&mut self, iter: T) {
+        for clause in iter {
+            self.add_clause(clause.iter().copied().map(literal));
+        }
+    }
+}
+
+impl<'a, C: Callbacks> Extend<&'a Clause> for Solver<C> {
+    fn extend<T: IntoIterator<Item = &'a Clause>>(&mut self, iter: T) {
+        for clause in iter {
+            self.add_clause(clause.iter().copied().map(literal));
+        }
+    }
+}
+
+impl<C: Callbacks> Solve for Solver<C> {
+    fn signature(&self) -> &'static str {
+        signature()
+    }
+
+    fn reserve(&mut self, max_var: Var) -> anyhow::Result<()> {
+        Solver::reserve(self, max_var.pos_lit().to_ipasir());
+        Ok(())
+    }
+
+    fn solve(&mut self) -> anyhow::Result<SolverResult> {
+        self.assumptions.clear();
+        Ok(result(Solver::solve(self)))
+    }
+
+    fn lit_val(&self, lit: Lit) -> anyhow::Result<TernaryVal> {
+        if self.status() != Some(true) {
+            anyhow::bail!("the solver is not in the satisfied state");
+        }
+        Ok(match self.value(literal(lit)) {
+            Some(true) => TernaryVal::True,
+            Some(false) => TernaryVal::False,
+            None => TernaryVal::DontCare,
+        })
+    }
+
+    fn add_clause_ref<D>(&mut self, clause: &D) -> anyhow::Result<()>
+    where
+        D: AsRef<Cl> + ?Sized,
+    {
+        self.add_clause(clause.as_ref().iter().copied().map(literal));
+        Ok(())
+    }
+}
+
+impl<C: Callbacks> SolveIncremental for Solver<C> {
+    fn solve_assumps(&mut self, assumps: &[Lit]) -> anyhow::Result<SolverResult> {
+        self.assumptions = assumps.iter().copied().map(literal).collect();
+        let assumptions = self.assumptions.clone();
+        Ok(result(self.solve_with(assumptions)))
+    }
+
+    fn core(&mut self) -> anyhow::Result<Vec<Lit>> {
+        if self.status() != Some(false) {
+            anyhow::bail!("the solver is not in the unsatisfied state");
+        }
+        let failed = self.assumptions.iter().filter(|&&lit| self.failed(lit));
+        failed.map(|&lit| Ok(!Lit::from_ipasir(lit)?)).collect()
+    }
+}
+
+impl<C: Callbacks> Interrupt for Solver<C> {
+    type Interrupter = Interrupter;
+
+    fn interrupter(&mut self) -> Self::Interrupter {
+        Interrupter(self.interrupt_flag())
+    }
+}
+
+/// A handle that terminates the running `solve` call of the solver it was
+/// obtained from, which can be sent to other threads.
+#[derive(Clone, Debug)]
+pub struct Interrupter(Arc<AtomicBool>);
+
+impl InterruptSolver for Interrupter {
+    fn interrupt(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+}
+
+fn result(result: Option<bool>) -> SolverResult {
+    match result {
+        Some(true) => SolverResult::Sat,
+        Some(false) => SolverResult::Unsat,
+        None => SolverResult::Interrupted,
+    }
+}
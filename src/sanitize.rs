@@ -0,0 +1,111 @@
+//! Removal of duplicate literals and tautologies from the added clauses.
+
+use super::{Callbacks, Solver};
+
+/// The counts of the changes made by the clause sanitization mode, returned
+/// by `Solver::sanitize_report`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SanitizeReport {
+    /// The number of clauses checked.
+    pub clauses: u64,
+    /// The number of clauses that contained duplicate literals.
+    pub clauses_with_duplicates: u64,
+    /// The number of duplicate literals removed.
+    pub duplicate_literals: u64,
+    /// The number of tautological clauses skipped, which contained a
+    /// literal together with its negation.
+    pub tautologies: u64,
+}
+
+/// The state of the clause sanitization mode.
+#[derive(Clone, Default)]
+pub(crate) struct Sanitizer {
+    report: SanitizeReport,
+    sorted: Vec<i32>,
+}
+
+impl Sanitizer {
+    /// Removes the duplicate literals of the clause in place, keeping the
+    /// first occurrences, and returns `false` if it is a tautology.
+    pub(crate) fn sanitize(&mut self, clause: &mut Vec<i32>) -> bool {
+        self.report.clauses += 1;
+        self.sorted.clear();
+        self.sorted.extend_from_slice(clause);
+        self.sorted
+            .sort_unstable_by_key(|&lit| (lit.unsigned_abs(), lit));
+        let mut duplicates = 0;
+        for pair in self.sorted.windows(2) {
+            if pair[0] == pair[1] {
+                duplicates += 1;
+            } else if pair[0] == -pair[1] {
+                self.report.tautologies += 1;
+                #[cfg(feature = "tracing")]
+                tracing::debug!(size = clause.len(), "tautology skipped");
+                return false;
+            }
+        }
+        if duplicates > 0 {
+            self.report.clauses_with_duplicates += 1;
+            self.report.duplicate_literals += duplicates;
+            #[cfg(feature = "tracing")]
+            tracing::debug!(
+                size = clause.len(),
+                duplicates,
+                "duplicate literals removed"
+            );
+            let mut len = 0;
+            for index in 0..clause.len() {
+                let lit = clause[index];
+                if !clause[..len].contains(&lit) {
+                    clause[len] = lit;
+                    len += 1;
+                }
+            }
+            clause.truncate(len);
+        }
+        true
+    }
+}
+
+impl<C: Callbacks> Solver<C> {
+    /// Enables or disables the clause sanitization mode. When enabled,
+    /// `add_clause` and `add_clause_slice` remove the duplicate literals of
+    /// every clause and skip tautologies, which contain a literal together
+    /// with its negation, and the changes are counted in `sanitize_report`.
+    /// Such clauses are harmless for the solver, but in machine-generated
+    /// encodings they often point to encoder bugs. Disabling the mode clears
+    /// the report.
+    /// # Examples
+    /// ```
+    /// let mut sat: cadical::Solver = Default::default();
+    /// sat.set_sanitize(true);
+    /// sat.add_clause([1, 2, 1]);
+    /// sat.add_clause([3, -3]);
+    /// let report = sat.sanitize_report();
+    /// assert_eq!(report.duplicate_literals, 1);
+    /// assert_eq!(report.tautologies, 1);
+    /// assert_eq!(sat.num_clauses(), 1);
+    /// ```
+    pub fn set_sanitize(&mut self, enable: bool) {
+        if !enable {
+            self.sanitizer = None;
+        } else if self.sanitizer.is_none() {
+            self.sanitizer = Some(Sanitizer::default());
+        }
+    }
+
+    /// Returns `true` if the clause sanitization mode is enabled.
+    pub fn sanitize(&self) -> bool {
+        self.sanitizer.is_some()
+    }
+
+    /// Returns the changes made by the clause sanitization mode since it was
+    /// enabled, or an empty report if it is disabled.
+    pub fn sanitize_report(&self) -> SanitizeReport {
+        match self.sanitizer.as_ref() {
+            Some(sanitizer) => sanitizer.report,
+            None => SanitizeReport::default(),
+        }
+    }
+}
@@ -0,0 +1,83 @@
+//! Cross-checking of unsatisfiable answers with an independent solver.
+
+use super::{Callbacks, ClauseStore, Error, Solver, State};
+
+/// The clauses and the constraint added since the check was enabled.
+#[derive(Clone, Default)]
+pub(crate) struct UnsatCheck {
+    clauses: ClauseStore,
+    constraint: Option<Vec<i32>>,
+}
+
+impl UnsatCheck {
+    /// Records a clause that was added to the solver.
+    #[inline]
+    pub(crate) fn record(&mut self, clause: &[i32]) {
+        self.clauses.push(clause);
+    }
+
+    /// Records the constraint of the next solve call.
+    pub(crate) fn set_constraint(&mut self, clause: Option<Vec<i32>>) {
+        self.constraint = clause;
+    }
+}
+
+impl<C: Callbacks> Solver<C> {
+    /// Enables or disables the cross-checking of unsatisfiable answers. When
+    /// enabled, the added clauses are recorded, and every `Some(false)`
+    /// result is confirmed by solving the recorded clauses under the failed
+    /// assumptions with a fresh solver in the `plain` configuration, which
+    /// does no preprocessing or inprocessing at all. If the check finds a
+    /// model, then `None` is returned instead, so a wrong answer is never
+    /// acted on, while `status` still reports the answer of CaDiCaL. This
+    /// roughly doubles the cost of unsatisfiable queries. The check can only
+    /// be enabled before adding any clauses, and clauses cannot be loaded
+    /// with `read_dimacs` while it is enabled.
+    /// # Examples
+    /// ```
+    /// let mut sat: cadical::Solver = Default::default();
+    /// sat.set_unsat_check(true).unwrap();
+    /// sat.add_clause([1, 2]);
+    /// sat.add_clause([-1, 2]);
+    /// assert_eq!(sat.solve_with([-2]), Some(false));
+    /// assert_eq!(sat.solve(), Some(true));
+    /// ```
+    pub fn set_unsat_check(&mut self, enable: bool) -> Result<(), Error> {
+        if !enable {
+            self.unsat_check = None;
+        } else if self.unsat_check.is_none() {
+            if self.state() != State::Configuring {
+                return Err(Error::new("invalid state"));
+            }
+            self.unsat_check = Some(UnsatCheck::default());
+        }
+        Ok(())
+    }
+
+    /// Returns `true` if the cross-checking of unsatisfiable answers is
+    /// enabled.
+    pub fn unsat_check(&self) -> bool {
+        self.unsat_check.is_some()
+    }
+
+    /// Confirms the unsatisfiability of the last query with a fresh solver.
+    pub(crate) fn check_unsat(&self, check: &UnsatCheck) -> bool {
+        let mut core = Vec::new();
+        for var in 1..=self.max_variable() {
+            for &lit in [var, -var].iter() {
+                if self.failed(lit) {
+                    core.push(lit);
+                }
+            }
+        }
+
+        let mut sat: Solver = Solver::with_config("plain").unwrap();
+        for index in 0..check.clauses.len() {
+            sat.add_clause(check.clauses.clause(index));
+        }
+        if let Some(clause) = check.constraint.as_ref() {
+            sat.add_clause(clause.iter().copied());
+        }
+        sat.solve_with(core) == Some(false)
+    }
+}
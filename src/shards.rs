@@ -0,0 +1,115 @@
+//! Concurrent loading of formulas split into several DIMACS files.
+
+use super::{Callbacks, CnfFormula, Error, Solver};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{mpsc, Arc, Condvar, Mutex};
+use std::thread;
+
+/// The number of shards added by the calling thread, or `usize::MAX` once
+/// it has stopped, which the workers wait for so that they do not run too
+/// far ahead.
+struct Progress {
+    written: Mutex<usize>,
+    changed: Condvar,
+}
+
+impl Progress {
+    fn set(&self, written: usize) {
+        *self.written.lock().unwrap() = written;
+        self.changed.notify_all();
+    }
+
+    /// Waits until the shard is less than `window` shards ahead of the
+    /// writer, and returns `false` if the writer has stopped.
+    fn wait(&self, index: usize, window: usize) -> bool {
+        let mut written = self.written.lock().unwrap();
+        while *written != usize::MAX && index >= *written + window {
+            written = self.changed.wait(written).unwrap();
+        }
+        *written != usize::MAX
+    }
+}
+
+/// Reads and parses a single shard.
+fn parse_shard(path: &Path) -> Result<CnfFormula, Error> {
+    let text = std::fs::read_to_string(path).map_err(|_| Error::new("cannot read file"))?;
+    CnfFormula::parse_dimacs(&text)
+}
+
+impl<C: Callbacks> Solver<C> {
+    /// Reads the shards of a formula, each in DIMACS CNF format, and adds
+    /// their clauses to the solver. The shards are read and parsed
+    /// concurrently by up to `threads` worker threads (all available cores
+    /// if zero), while the calling thread adds the parsed shards to the
+    /// solver in the given order, so the result is the same as reading the
+    /// shards one after the other. On error the clauses of the shards before
+    /// the failing one are already added.
+    pub fn read_dimacs_shards<P>(&mut self, paths: &[P], threads: usize) -> Result<(), Error>
+    where
+        P: AsRef<Path>,
+    {
+        let paths: Arc<Vec<PathBuf>> =
+            Arc::new(paths.iter().map(|p| p.as_ref().to_path_buf()).collect());
+        let threads = if threads == 0 {
+            thread::available_parallelism().map_or(1, |n| n.get())
+        } else {
+            threads
+        };
+        let threads = threads.min(paths.len());
+
+        // each worker takes the next unread shard, but only starts parsing
+        // it when it is less than `window` shards ahead of the writer, so at
+        // most `window` parsed shards wait in `pending` even if an early
+        // shard is slow
+        let window = 2 * threads;
+        let next = Arc::new(AtomicUsize::new(0));
+        let progress = Arc::new(Progress {
+            written: Mutex::new(0),
+            changed: Condvar::new(),
+        });
+        let (sender, receiver) = mpsc::channel();
+        for _ in 0..threads {
+            let paths = paths.clone();
+            let next = next.clone();
+            let progress = progress.clone();
+            let sender = sender.clone();
+            thread::spawn(move || loop {
+                let index = next.fetch_add(1, Ordering::Relaxed);
+                if index >= paths.len()
+                    || !progress.wait(index, window)
+                    || sender.send((index, parse_shard(&paths[index]))).is_err()
+                {
+                    break;
+                }
+            });
+        }
+        drop(sender);
+
+        let mut pending = BTreeMap::new();
+        let mut result = Ok(());
+        for index in 0..paths.len() {
+            let formula = loop {
+                if let Some(formula) = pending.remove(&index) {
+                    break formula;
+                }
+                match receiver.recv() {
+                    Ok((other, formula)) => pending.insert(other, formula),
+                    Err(_) => break Err(Error::new("shard reader failed")),
+                };
+            };
+            match formula {
+                Ok(formula) => self.add_formula(&formula),
+                Err(err) => {
+                    result = Err(err);
+                    break;
+                }
+            }
+            progress.set(index + 1);
+        }
+        // the waiting workers stop after an error
+        progress.set(usize::MAX);
+        result
+    }
+}
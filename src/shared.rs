@@ -0,0 +1,114 @@
+//! A solver that can be shared between threads.
+
+use super::{Callbacks, Solver, Timeout};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+
+/// A synchronized wrapper around a solver that can be shared between
+/// threads by reference, for example in an `Arc`. The solver is protected by
+/// a mutex, so its methods are called by one thread at a time, but the
+/// running solve call can be terminated from any thread without waiting for
+/// the lock, and the model of the last satisfiable answer is kept in a
+/// separate snapshot that can be read concurrently.
+/// # Examples
+/// ```
+/// use std::sync::Arc;
+/// let sat: cadical::Solver = Default::default();
+/// let shared = Arc::new(cadical::SharedSolver::new(sat));
+/// shared.add_clause([1, 2]);
+/// shared.add_clause([-1, 2]);
+/// assert_eq!(shared.solve(), Some(true));
+/// let reader = Arc::clone(&shared);
+/// let handle = std::thread::spawn(move || reader.value(2));
+/// assert_eq!(handle.join().unwrap(), Some(true));
+/// ```
+pub struct SharedSolver<C: Callbacks = Timeout> {
+    solver: Mutex<Solver<C>>,
+    interrupt: Arc<AtomicBool>,
+    model: RwLock<Option<Vec<i32>>>,
+}
+
+impl<C: Callbacks> SharedSolver<C> {
+    /// Wraps the given solver.
+    pub fn new(mut solver: Solver<C>) -> Self {
+        let interrupt = solver.interrupt_flag();
+        SharedSolver {
+            solver: Mutex::new(solver),
+            interrupt,
+            model: RwLock::new(None),
+        }
+    }
+
+    /// Returns the wrapped solver.
+    pub fn into_inner(self) -> Solver<C> {
+        self.solver.into_inner().unwrap()
+    }
+
+    /// Locks the solver and calls the given function with it. The model
+    /// snapshot is cleared before, and it is taken again afterwards if the
+    /// state of the solver is `Some(true)`.
+    pub fn with<F, R>(&self, func: F) -> R
+    where
+        F: FnOnce(&mut Solver<C>) -> R,
+    {
+        let mut solver = self.solver.lock().unwrap();
+        *self.model.write().unwrap() = None;
+        let ret = func(&mut solver);
+        if solver.status() == Some(true) {
+            let model = (1..=solver.max_variable()).map(|var| {
+                if solver.value(var) == Some(false) {
+                    -var
+                } else {
+                    var
+                }
+            });
+            *self.model.write().unwrap() = Some(model.collect());
+        }
+        ret
+    }
+
+    /// Adds the given clause to the solver.
+    pub fn add_clause<I>(&self, clause: I)
+    where
+        I: IntoIterator<Item = i32>,
+    {
+        self.solver.lock().unwrap().add_clause(clause);
+        *self.model.write().unwrap() = None;
+    }
+
+    /// Solves the formula defined by the added clauses.
+    pub fn solve(&self) -> Option<bool> {
+        self.with(|solver| solver.solve())
+    }
+
+    /// Solves the formula under the given assumptions.
+    pub fn solve_with<I>(&self, assumptions: I) -> Option<bool>
+    where
+        I: IntoIterator<Item = i32>,
+    {
+        self.with(|solver| solver.solve_with(assumptions))
+    }
+
+    /// Terminates the running solve call, or the next one if no call is
+    /// running at the moment.
+    pub fn terminate(&self) {
+        self.interrupt.store(true, Ordering::Relaxed);
+    }
+
+    /// Returns the value of the given literal in the model of the last
+    /// satisfiable answer, or `None` if there is no model because the
+    /// formula was changed or solved again since then.
+    pub fn value(&self, lit: i32) -> Option<bool> {
+        debug_assert!(lit != 0 && lit != i32::MIN);
+        let model = self.model.read().unwrap();
+        let model = model.as_ref()?;
+        let val = *model.get(lit.unsigned_abs() as usize - 1)?;
+        Some((val > 0) == (lit > 0))
+    }
+
+    /// Returns the model of the last satisfiable answer as the list of true
+    /// literals, one for each variable up to the maximum variable.
+    pub fn model(&self) -> Option<Vec<i32>> {
+        self.model.read().unwrap().clone()
+    }
+}
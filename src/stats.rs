@@ -0,0 +1,254 @@
+//! Statistics about the formula and the solving process.
+
+use super::*;
+#[cfg(target_has_atomic = "64")]
+use std::sync::atomic::{fence, AtomicI64};
+
+/// Shape statistics of the irredundant clauses of a formula.
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FormulaStats {
+    /// The number of clauses.
+    pub clauses: usize,
+    /// The total number of literals in all clauses.
+    pub literals: usize,
+    /// The number of clauses of each length, indexed by the length.
+    pub length_histogram: Vec<usize>,
+    /// The number of unit clauses.
+    pub units: usize,
+    /// The number of binary clauses.
+    pub binaries: usize,
+    /// The number of ternary clauses.
+    pub ternaries: usize,
+    /// The number of variables occurring in at least one clause.
+    pub variables: usize,
+    /// The number of variables occurring only positively or only negatively.
+    pub pure_variables: usize,
+    /// The maximum number of clauses a variable occurs in.
+    pub max_occurrences: usize,
+    /// The average number of occurrences of the occurring variables.
+    pub mean_occurrences: f64,
+}
+
+/// The effort spent by a single `solve_limited` call.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct EffortStats {
+    /// The number of conflicts encountered.
+    pub conflicts: u64,
+    /// The number of decisions made.
+    pub decisions: u64,
+    /// The number of literals propagated during search.
+    pub propagations: u64,
+    /// The wall clock time spent in the solver.
+    pub time: Duration,
+}
+
+/// The counters of a solver at a point in time, read by `LiveStats`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct StatsSnapshot {
+    /// The number of conflicts so far.
+    pub conflicts: u64,
+    /// The number of decisions so far.
+    pub decisions: u64,
+    /// The number of restarts so far.
+    pub restarts: u64,
+    /// The number of bytes of native memory in use, which is only known
+    /// with the `memory-accounting` feature and zero otherwise.
+    pub memory: u64,
+}
+
+/// A handle for reading the counters of a solver from other threads while
+/// it is solving, returned by `Solver::live_stats`. The counters are
+/// published by the native code whenever CaDiCaL polls for termination, and
+/// at the end of each solve call, behind a sequence number, so reading them
+/// never blocks the search and always returns a consistent snapshot.
+#[cfg(target_has_atomic = "64")]
+#[derive(Clone, Debug)]
+pub struct LiveStats {
+    block: Arc<[AtomicI64; 5]>,
+}
+
+#[cfg(target_has_atomic = "64")]
+impl LiveStats {
+    pub(crate) fn new() -> Self {
+        LiveStats {
+            block: Arc::new(Default::default()),
+        }
+    }
+
+    /// Returns the pointer to the block written by the native code.
+    pub(crate) fn as_ptr(&self) -> *mut i64 {
+        self.block.as_ptr() as *mut i64
+    }
+
+    /// Returns the last published counters of the solver.
+    pub fn snapshot(&self) -> StatsSnapshot {
+        loop {
+            let sequence = self.block[0].load(Ordering::Acquire);
+            if sequence & 1 != 0 {
+                std::hint::spin_loop();
+                continue;
+            }
+            let snapshot = StatsSnapshot {
+                conflicts: self.block[1].load(Ordering::Relaxed) as u64,
+                decisions: self.block[2].load(Ordering::Relaxed) as u64,
+                restarts: self.block[3].load(Ordering::Relaxed) as u64,
+                memory: self.block[4].load(Ordering::Relaxed) as u64,
+            };
+            fence(Ordering::Acquire);
+            if self.block[0].load(Ordering::Relaxed) == sequence {
+                return snapshot;
+            }
+        }
+    }
+}
+
+/// The process time in seconds spent in the main phases of solving, as
+/// measured by the internal profiler of CaDiCaL. The phases are nested, for
+/// example `propagate` is part of `search`, which is part of `solve`.
+/// Only phases up to the level of the `profile` option (defaults to `2`) are
+/// measured, the others remain zero.
+#[cfg(feature = "profiling")]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Profile {
+    /// Seconds spent in `solve` calls, including search and simplification.
+    pub solve: f64,
+    /// Seconds spent in the CDCL search loop, including the inprocessing it
+    /// schedules.
+    pub search: f64,
+    /// Seconds spent in stable mode, which uses few restarts and target
+    /// phases.
+    pub stable: f64,
+    /// Seconds spent in unstable (focused) mode, which restarts frequently.
+    pub unstable: f64,
+    /// Seconds spent in unit propagation during search.
+    pub propagate: f64,
+    /// Seconds spent analyzing conflicts and learning clauses.
+    pub analyze: f64,
+    /// Seconds spent picking decision variables and their phases.
+    pub decide: f64,
+    /// Seconds spent removing useless learned clauses.
+    pub reduce: f64,
+    /// Seconds spent restarting the search.
+    pub restart: f64,
+    /// Seconds spent resetting the saved phases.
+    pub rephase: f64,
+    /// Seconds spent in preprocessing and inprocessing together.
+    pub simplify: f64,
+    /// Seconds spent in bounded variable elimination.
+    pub elim: f64,
+    /// Seconds spent in clause subsumption and strengthening.
+    pub subsume: f64,
+    /// Seconds spent probing failed literals.
+    pub probe: f64,
+    /// Seconds spent vivifying (shortening) clauses.
+    pub vivify: f64,
+    /// Seconds spent in local search.
+    pub walk: f64,
+    /// Seconds spent substituting equivalent literals found as strongly
+    /// connected components of the binary implication graph.
+    pub decompose: f64,
+    /// Seconds spent in hyper ternary resolution.
+    pub ternary: f64,
+    /// Seconds spent in the transitive reduction of binary clauses.
+    pub transred: f64,
+    /// Seconds spent trying the lucky phases, such as all variables false,
+    /// before the search.
+    pub lucky: f64,
+    /// Seconds spent parsing DIMACS input with the native parser.
+    pub parse: f64,
+}
+
+impl<C: Callbacks> Solver<C> {
+    /// Computes the shape statistics of the current formula as seen by
+    /// `traverse_clauses`.
+    /// # Examples
+    /// ```
+    /// let mut sat: cadical::Solver = Default::default();
+    /// sat.add_clause([1, 2]);
+    /// sat.add_clause([-1, 2, 3]);
+    /// let stats = sat.formula_stats();
+    /// assert_eq!(stats.binaries, 1);
+    /// assert_eq!(stats.ternaries, 1);
+    /// assert_eq!(stats.max_occurrences, 2);
+    /// ```
+    pub fn formula_stats(&self) -> FormulaStats {
+        let size = self.max_variable().max(0) as usize + 1;
+        let mut positive = vec![0; size];
+        let mut negative = vec![0; size];
+        let mut stats = FormulaStats::default();
+
+        self.traverse_clauses(|clause| {
+            stats.clauses += 1;
+            stats.literals += clause.len();
+            if stats.length_histogram.len() <= clause.len() {
+                stats.length_histogram.resize(clause.len() + 1, 0);
+            }
+            stats.length_histogram[clause.len()] += 1;
+            for &lit in clause {
+                if lit > 0 {
+                    positive[lit as usize] += 1;
+                } else {
+                    negative[lit.unsigned_abs() as usize] += 1;
+                }
+            }
+            true
+        });
+
+        let histogram = &stats.length_histogram;
+        stats.units = histogram.get(1).copied().unwrap_or(0);
+        stats.binaries = histogram.get(2).copied().unwrap_or(0);
+        stats.ternaries = histogram.get(3).copied().unwrap_or(0);
+
+        for (&pos, &neg) in positive.iter().zip(negative.iter()) {
+            if pos + neg > 0 {
+                stats.variables += 1;
+                stats.max_occurrences = stats.max_occurrences.max(pos + neg);
+                if pos == 0 || neg == 0 {
+                    stats.pure_variables += 1;
+                }
+            }
+        }
+        if stats.variables > 0 {
+            stats.mean_occurrences = stats.literals as f64 / stats.variables as f64;
+        }
+        stats
+    }
+
+    /// Returns the time spent in the main solving phases so far, summed over
+    /// all `solve` calls. This requires the `profiling` feature, which builds
+    /// CaDiCaL with its profiler (and its message functions) enabled.
+    #[cfg(feature = "profiling")]
+    pub fn profile(&self) -> Profile {
+        let get = |name: &str| {
+            let name = CString::new(name).unwrap();
+            unsafe { ccadical_profile(self.ptr, name.as_ptr()) }
+        };
+        Profile {
+            solve: get("solve"),
+            search: get("search"),
+            stable: get("stable"),
+            unstable: get("unstable"),
+            propagate: get("propagate"),
+            analyze: get("analyze"),
+            decide: get("decide"),
+            reduce: get("reduce"),
+            restart: get("restart"),
+            rephase: get("rephase"),
+            simplify: get("simplify"),
+            elim: get("elim"),
+            subsume: get("subsume"),
+            probe: get("probe"),
+            vivify: get("vivify"),
+            walk: get("walk"),
+            decompose: get("decompose"),
+            ternary: get("ternary"),
+            transred: get("transred"),
+            lucky: get("lucky"),
+            parse: get("parse"),
+        }
+    }
+}
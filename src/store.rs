@@ -0,0 +1,100 @@
+//! A compact store of clauses for keeping a shadow copy of a formula.
+
+/// A memory efficient list of clauses, intended for keeping a copy of the
+/// clauses of a solver on the Rust side for model verification and
+/// introspection. The literals of all clauses are packed into a single
+/// buffer of `u32` values, with the variable in the upper bits and the sign
+/// in the lowest bit, and the clauses are given by their end offsets. This
+/// costs four bytes per literal and eight per clause, instead of a separate
+/// allocation for each clause.
+/// # Examples
+/// ```
+/// let mut store = cadical::ClauseStore::new();
+/// store.push(&[1, -2]);
+/// store.push(&[2]);
+/// assert_eq!(store.clause(0).collect::<Vec<_>>(), vec![1, -2]);
+/// assert_eq!(store.find_violated(|lit| lit == 1 || lit == -2), Some(1));
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ClauseStore {
+    literals: Vec<u32>,
+    ends: Vec<usize>,
+}
+
+/// Packs a literal into a `u32` value.
+#[inline]
+fn pack(lit: i32) -> u32 {
+    debug_assert!(lit != 0 && lit != i32::MIN);
+    (lit.unsigned_abs() << 1) | (lit < 0) as u32
+}
+
+/// Unpacks a literal packed by `pack`.
+#[inline]
+fn unpack(packed: u32) -> i32 {
+    let var = (packed >> 1) as i32;
+    if packed & 1 != 0 {
+        -var
+    } else {
+        var
+    }
+}
+
+impl ClauseStore {
+    /// Creates a new empty store.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Appends a clause to the store. The literals must be nonzero and
+    /// different from `i32::MIN`.
+    pub fn push(&mut self, clause: &[i32]) {
+        self.literals.extend(clause.iter().map(|&lit| pack(lit)));
+        self.ends.push(self.literals.len());
+    }
+
+    /// Returns the number of clauses.
+    pub fn len(&self) -> usize {
+        self.ends.len()
+    }
+
+    /// Returns `true` if the store has no clauses.
+    pub fn is_empty(&self) -> bool {
+        self.ends.is_empty()
+    }
+
+    /// Returns the total number of literals in all clauses.
+    pub fn num_literals(&self) -> usize {
+        self.literals.len()
+    }
+
+    /// Removes all clauses but keeps the allocated memory.
+    pub fn clear(&mut self) {
+        self.literals.clear();
+        self.ends.clear();
+    }
+
+    /// Returns the literals of the clause at the given index.
+    pub fn clause(&self, index: usize) -> impl Iterator<Item = i32> + '_ {
+        let start = if index == 0 { 0 } else { self.ends[index - 1] };
+        self.literals[start..self.ends[index]]
+            .iter()
+            .map(|&packed| unpack(packed))
+    }
+
+    /// Returns the index of the first clause that has no true literal under
+    /// the given assignment, or `None` if all clauses are satisfied.
+    pub fn find_violated<F>(&self, mut value: F) -> Option<usize>
+    where
+        F: FnMut(i32) -> bool,
+    {
+        let mut start = 0;
+        for (index, &end) in self.ends.iter().enumerate() {
+            let clause = &self.literals[start..end];
+            if !clause.iter().any(|&packed| value(unpack(packed))) {
+                return Some(index);
+            }
+            start = end;
+        }
+        None
+    }
+}
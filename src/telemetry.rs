@@ -0,0 +1,38 @@
+//! Publishing of solver counters through the `metrics` facade. This module is
+//! only available with the `metrics` feature.
+
+use super::*;
+use metrics::{counter, histogram};
+
+impl<C: Callbacks> Solver<C> {
+    /// Sets the value of the `solver` label attached to all metrics published
+    /// by this solver instance, so that several solvers in the same process
+    /// can be told apart. By default no label is attached.
+    pub fn set_metrics_label(&mut self, name: &str) {
+        self.metrics_labels = vec![("solver".to_string(), name.to_string())];
+    }
+
+    /// Returns the number of conflicts and learned clauses so far.
+    pub(crate) fn metrics_snapshot(&self) -> (i64, i64) {
+        unsafe { (ccadical_conflicts(self.ptr), ccadical_learned(self.ptr)) }
+    }
+
+    /// Publishes the outcome and the effort of a single `solve` call.
+    pub(crate) fn record_metrics(&self, before: (i64, i64), status: c_int, time: Duration) {
+        let labels = self.metrics_labels.as_slice();
+        let (conflicts, learned) = self.metrics_snapshot();
+        counter!("cadical_solves_total", labels).increment(1);
+        counter!("cadical_conflicts_total", labels).increment((conflicts - before.0) as u64);
+        counter!("cadical_learned_clauses_total", labels).increment((learned - before.1) as u64);
+        histogram!("cadical_solve_seconds", labels).record(time.as_secs_f64());
+
+        let result = match status {
+            10 => "sat",
+            20 => "unsat",
+            _ => "unknown",
+        };
+        let mut labels = self.metrics_labels.clone();
+        labels.push(("result".to_string(), result.to_string()));
+        counter!("cadical_results_total", labels.as_slice()).increment(1);
+    }
+}
@@ -0,0 +1,226 @@
+//! Utilities for property testing code built on the solver: `quickcheck` and
+//! `proptest` generators for small clauses, formulas and assumption sets, and
+//! a brute force reference solver to cross-check the results against. This
+//! module is only available with the `testing` feature.
+
+use super::{Error, Solver, Timeout};
+use proptest::collection::vec;
+use proptest::prelude::{any, BoxedStrategy, Strategy};
+use quickcheck::{Arbitrary, Gen};
+
+/// The maximum number of variables used by the generators, which keeps the
+/// brute force solver fast.
+pub const MAX_VARIABLES: i32 = 8;
+
+/// A nonempty clause over the variables `1..=MAX_VARIABLES`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Clause(pub Vec<i32>);
+
+/// A formula in conjunctive normal form with at most `MAX_VARIABLES`
+/// variables, where every literal refers to one of the first `variables`
+/// variables.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Formula {
+    pub variables: i32,
+    pub clauses: Vec<Vec<i32>>,
+}
+
+/// A set of assumed literals over the variables `1..=MAX_VARIABLES`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Assumptions(pub Vec<i32>);
+
+/// Returns a random literal over the variables `1..=variables`.
+fn literal(g: &mut Gen, variables: i32) -> i32 {
+    let var = (u32::arbitrary(g) % variables as u32) as i32 + 1;
+    if bool::arbitrary(g) {
+        var
+    } else {
+        -var
+    }
+}
+
+/// Returns a random clause of length `1..=3` over `1..=variables`.
+fn clause(g: &mut Gen, variables: i32) -> Vec<i32> {
+    let len = u32::arbitrary(g) % 3 + 1;
+    (0..len).map(|_| literal(g, variables)).collect()
+}
+
+/// Returns the copies of the list with one element removed.
+fn removals<T: Clone>(list: &[T]) -> Vec<Vec<T>> {
+    (0..list.len())
+        .map(|i| {
+            let mut list = list.to_vec();
+            list.remove(i);
+            list
+        })
+        .collect()
+}
+
+impl Arbitrary for Clause {
+    fn arbitrary(g: &mut Gen) -> Self {
+        Clause(clause(g, MAX_VARIABLES))
+    }
+
+    fn shrink(&self) -> Box<dyn Iterator<Item = Self>> {
+        let list = removals(&self.0).into_iter().filter(|c| !c.is_empty());
+        Box::new(list.map(Clause))
+    }
+}
+
+impl Arbitrary for Formula {
+    fn arbitrary(g: &mut Gen) -> Self {
+        let variables = (u32::arbitrary(g) % MAX_VARIABLES as u32) as i32 + 1;
+        let count = u32::arbitrary(g) as usize % (4 * variables as usize + 1);
+        let clauses = (0..count).map(|_| clause(g, variables)).collect();
+        Formula { variables, clauses }
+    }
+
+    fn shrink(&self) -> Box<dyn Iterator<Item = Self>> {
+        let variables = self.variables;
+        let mut list: Vec<Formula> = removals(&self.clauses)
+            .into_iter()
+            .map(|clauses| Formula { variables, clauses })
+            .collect();
+        for (i, clause) in self.clauses.iter().enumerate() {
+            for shorter in removals(clause) {
+                let mut clauses = self.clauses.clone();
+                clauses[i] = shorter;
+                list.push(Formula { variables, clauses });
+            }
+        }
+        Box::new(list.into_iter())
+    }
+}
+
+impl Arbitrary for Assumptions {
+    fn arbitrary(g: &mut Gen) -> Self {
+        let len = u32::arbitrary(g) % 4;
+        Assumptions((0..len).map(|_| literal(g, MAX_VARIABLES)).collect())
+    }
+
+    fn shrink(&self) -> Box<dyn Iterator<Item = Self>> {
+        Box::new(removals(&self.0).into_iter().map(Assumptions))
+    }
+}
+
+/// Returns the `proptest` strategy of literals over `1..=variables`.
+fn literal_strategy(variables: i32) -> impl Strategy<Value = i32> {
+    (1..=variables, any::<bool>()).prop_map(|(var, sign)| if sign { var } else { -var })
+}
+
+impl proptest::arbitrary::Arbitrary for Clause {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: ()) -> Self::Strategy {
+        vec(literal_strategy(MAX_VARIABLES), 1..=3)
+            .prop_map(Clause)
+            .boxed()
+    }
+}
+
+impl proptest::arbitrary::Arbitrary for Formula {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: ()) -> Self::Strategy {
+        (1..=MAX_VARIABLES)
+            .prop_flat_map(|variables| {
+                let clause = vec(literal_strategy(variables), 1..=3);
+                vec(clause, 0..=4 * variables as usize)
+                    .prop_map(move |clauses| Formula { variables, clauses })
+            })
+            .boxed()
+    }
+}
+
+impl proptest::arbitrary::Arbitrary for Assumptions {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: ()) -> Self::Strategy {
+        vec(literal_strategy(MAX_VARIABLES), 0..=3)
+            .prop_map(Assumptions)
+            .boxed()
+    }
+}
+
+/// Finds a model of the clauses over the variables `1..=variables` that
+/// satisfies all assumptions by enumerating all assignments. The model is
+/// returned as the list of true literals, one for each variable.
+pub fn brute_force(variables: i32, clauses: &[Vec<i32>], assumptions: &[i32]) -> Option<Vec<i32>> {
+    assert!((0..=20).contains(&variables));
+    let holds = |bits: u32, lit: i32| ((bits >> (lit.abs() - 1)) & 1 == 1) == (lit > 0);
+    (0..1u32 << variables)
+        .find(|&bits| {
+            assumptions.iter().all(|&lit| holds(bits, lit))
+                && clauses
+                    .iter()
+                    .all(|clause| clause.iter().any(|&lit| holds(bits, lit)))
+        })
+        .map(|bits| {
+            (1..=variables)
+                .map(|var| if holds(bits, var) { var } else { -var })
+                .collect()
+        })
+}
+
+/// Solves the formula under the assumptions with the solver and checks the
+/// result against `brute_force`: the satisfiability must agree, the model
+/// must satisfy all clauses and assumptions, and the failed assumptions alone
+/// must already make the formula unsatisfiable.
+/// # Examples
+/// ```
+/// use cadical::testing::{cross_check, Formula};
+/// let formula = Formula {
+///     variables: 2,
+///     clauses: vec![vec![1, 2], vec![-1, 2]],
+/// };
+/// assert!(cross_check(&formula, &[-2]).is_ok());
+/// ```
+pub fn cross_check(formula: &Formula, assumptions: &[i32]) -> Result<(), Error> {
+    let variables = assumptions
+        .iter()
+        .map(|lit| lit.abs())
+        .fold(formula.variables, i32::max);
+    let expected = brute_force(variables, &formula.clauses, assumptions);
+
+    let mut sat: Solver<Timeout> = Solver::new();
+    for clause in formula.clauses.iter() {
+        sat.add_clause(clause.iter().copied());
+    }
+    match sat.solve_with(assumptions.iter().copied()) {
+        Some(true) => {
+            if expected.is_none() {
+                return Err(Error::new("unsatisfiable formula solved"));
+            }
+            let holds = |lit: i32| sat.value(lit) == Some(true);
+            if !assumptions.iter().all(|&lit| holds(lit)) {
+                return Err(Error::new("assumption violated by model"));
+            }
+            if !formula
+                .clauses
+                .iter()
+                .all(|c| c.iter().any(|&lit| holds(lit)))
+            {
+                return Err(Error::new("clause violated by model"));
+            }
+            Ok(())
+        }
+        Some(false) => {
+            if expected.is_some() {
+                return Err(Error::new("satisfiable formula refuted"));
+            }
+            let failed: Vec<i32> = assumptions
+                .iter()
+                .copied()
+                .filter(|&lit| sat.failed(lit))
+                .collect();
+            if brute_force(variables, &formula.clauses, &failed).is_some() {
+                return Err(Error::new("failed assumptions are satisfiable"));
+            }
+            Ok(())
+        }
+        None => Err(Error::new("solver interrupted")),
+    }
+}
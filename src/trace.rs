@@ -0,0 +1,183 @@
+//! Recording of the search events of the solver for visualization. This
+//! module is only available with the `trace-viz` feature.
+
+use super::Callbacks;
+use std::fmt::Write;
+use std::time::{Duration, Instant};
+
+/// A single search event recorded by `Trace`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TraceEvent {
+    /// A sampled decision with the decision literal and its level.
+    Decision { lit: i32, level: i32 },
+    /// A learned clause with its length, glue and the backjump level.
+    Conflict { size: usize, glue: i32, jump: i32 },
+    /// A restart with the total number of restarts and the current level.
+    Restart { restarts: u64, level: i32 },
+}
+
+/// Callbacks recording the decisions, conflicts and restarts of the solver
+/// into an event log, which can be exported as a Graphviz DOT search tree or
+/// as a JSON timeline.
+/// # Examples
+/// ```
+/// use cadical::{Solver, Trace};
+/// let mut sat: Solver<Trace> = Solver::new();
+/// sat.add_clause([1, 2]);
+/// sat.add_clause([-1, 2]);
+/// sat.set_callbacks(Some(Trace::new(1, 1000)));
+/// assert_eq!(sat.solve(), Some(true));
+/// let trace = sat.get_callbacks().unwrap();
+/// assert!(trace.to_dot().starts_with("digraph"));
+/// assert!(trace.to_json().starts_with('['));
+/// ```
+#[derive(Clone, Debug)]
+pub struct Trace {
+    started: Instant,
+    interval: u64,
+    capacity: usize,
+    events: Vec<(Duration, TraceEvent)>,
+}
+
+impl Trace {
+    /// Creates a new recorder that samples one out of every `interval`
+    /// decisions and keeps at most `capacity` events, dropping the later
+    /// ones. An interval of zero disables the recording of decisions.
+    pub fn new(interval: u64, capacity: usize) -> Self {
+        Trace {
+            started: Instant::now(),
+            interval,
+            capacity,
+            events: Vec::new(),
+        }
+    }
+
+    /// Returns the recorded events together with their time relative to the
+    /// start of the first `solve` call.
+    pub fn events(&self) -> &[(Duration, TraceEvent)] {
+        &self.events
+    }
+
+    /// Removes all recorded events.
+    pub fn clear(&mut self) {
+        self.events.clear();
+    }
+
+    fn record(&mut self, event: TraceEvent) {
+        if self.events.len() < self.capacity {
+            self.events.push((self.started.elapsed(), event));
+        }
+    }
+
+    /// Returns the search tree in Graphviz DOT format. Decisions are boxes
+    /// attached to the decision of the previous level, conflicts are red
+    /// leaves under the decision where they were found, and restarts are
+    /// diamonds attached to the root.
+    pub fn to_dot(&self) -> String {
+        let mut out = String::from("digraph trace {\n  n0 [label=\"root\"];\n");
+        let mut stack: Vec<(i32, usize)> = Vec::new();
+        for (index, (_, event)) in self.events.iter().enumerate() {
+            let node = index + 1;
+            let (label, shape, level) = match *event {
+                TraceEvent::Decision { lit, level } => {
+                    (format!("{} @{}", lit, level), "box", level)
+                }
+                TraceEvent::Conflict { size, glue, jump } => {
+                    (format!("size {} glue {}", size, glue), "ellipse", jump)
+                }
+                TraceEvent::Restart { restarts, level } => {
+                    (format!("restart {}", restarts), "diamond", level)
+                }
+            };
+            let color = match event {
+                TraceEvent::Conflict { .. } => "red",
+                _ => "black",
+            };
+            let parent = match event {
+                TraceEvent::Decision { .. } => {
+                    stack.retain(|&(lvl, _)| lvl < level);
+                    stack.last().map_or(0, |&(_, node)| node)
+                }
+                TraceEvent::Conflict { .. } => stack.last().map_or(0, |&(_, node)| node),
+                TraceEvent::Restart { .. } => 0,
+            };
+            writeln!(
+                out,
+                "  n{} [label=\"{}\", shape={}, color={}];\n  n{} -> n{};",
+                node, label, shape, color, parent, node
+            )
+            .unwrap();
+            match event {
+                TraceEvent::Decision { .. } => stack.push((level, node)),
+                _ => stack.retain(|&(lvl, _)| lvl <= level),
+            }
+        }
+        out.push_str("}\n");
+        out
+    }
+
+    /// Returns the events as a JSON array of objects, ordered by time, where
+    /// the time is given in seconds.
+    pub fn to_json(&self) -> String {
+        let mut out = String::from("[");
+        for (index, (time, event)) in self.events.iter().enumerate() {
+            if index > 0 {
+                out.push(',');
+            }
+            let time = time.as_secs_f64();
+            match *event {
+                TraceEvent::Decision { lit, level } => write!(
+                    out,
+                    "\n{{\"time\":{},\"event\":\"decision\",\"lit\":{},\"level\":{}}}",
+                    time, lit, level
+                ),
+                TraceEvent::Conflict { size, glue, jump } => write!(
+                    out,
+                    "\n{{\"time\":{},\"event\":\"conflict\",\"size\":{},\"glue\":{},\"jump\":{}}}",
+                    time, size, glue, jump
+                ),
+                TraceEvent::Restart { restarts, level } => write!(
+                    out,
+                    "\n{{\"time\":{},\"event\":\"restart\",\"restarts\":{},\"level\":{}}}",
+                    time, restarts, level
+                ),
+            }
+            .unwrap();
+        }
+        out.push_str("\n]\n");
+        out
+    }
+}
+
+impl Callbacks for Trace {
+    fn started(&mut self) {
+        if self.events.is_empty() {
+            self.started = Instant::now();
+        }
+    }
+
+    fn observe_conflicts(&self) -> bool {
+        true
+    }
+
+    fn conflict(&mut self, size: usize, glue: i32, jump: i32) {
+        self.record(TraceEvent::Conflict { size, glue, jump });
+    }
+
+    fn observe_restarts(&self) -> bool {
+        true
+    }
+
+    fn restart(&mut self, restarts: u64, level: i32) {
+        self.record(TraceEvent::Restart { restarts, level });
+    }
+
+    fn decision_interval(&self) -> u64 {
+        self.interval
+    }
+
+    fn decision(&mut self, lit: i32, level: i32) {
+        self.record(TraceEvent::Decision { lit, level });
+    }
+}
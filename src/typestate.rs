@@ -0,0 +1,197 @@
+//! An alternative interface where the state of the solver is part of its
+//! type, so calls that are only valid in some states are checked at compile
+//! time instead of with debug assertions. The model can only be queried
+//! after a satisfiable answer, and the failed assumptions only after an
+//! unsatisfiable one.
+//! # Examples
+//! ```
+//! use cadical::typestate::{Outcome, Solver};
+//! let mut sat: Solver = Solver::new();
+//! sat.add_clause([1, 2]);
+//! sat.add_clause([-1, 2]);
+//! let sat = match sat.solve_with([-2]) {
+//!     Outcome::Unsat(sat) => {
+//!         assert!(sat.failed(-2));
+//!         sat.resume()
+//!     }
+//!     _ => unreachable!(),
+//! };
+//! match sat.solve() {
+//!     Outcome::Sat(sat) => assert_eq!(sat.value(2), Some(true)),
+//!     _ => unreachable!(),
+//! }
+//! ```
+
+use super::{Callbacks, CnfFormula, OptionError, Timeout};
+use std::marker::PhantomData;
+
+/// The state where clauses and assumptions are added.
+pub enum Configuring {}
+
+/// The state after a satisfiable answer, where the model can be queried.
+pub enum Sat {}
+
+/// The state after an unsatisfiable answer, where the failed assumptions
+/// can be queried.
+pub enum Unsat {}
+
+/// A solver whose state `S` is one of `Configuring` (the default), `Sat` or
+/// `Unsat`.
+pub struct Solver<S = Configuring, C: Callbacks = Timeout> {
+    inner: super::Solver<C>,
+    state: PhantomData<S>,
+}
+
+/// The result of solving, with the solver in the corresponding state.
+pub enum Outcome<C: Callbacks = Timeout> {
+    /// The formula is satisfiable.
+    Sat(Solver<Sat, C>),
+    /// The formula is unsatisfiable.
+    Unsat(Solver<Unsat, C>),
+    /// The solver ran out of resources or was terminated.
+    Unknown(Solver<Configuring, C>),
+}
+
+impl<S, C: Callbacks> Solver<S, C> {
+    fn with_state<T>(self) -> Solver<T, C> {
+        Solver {
+            inner: self.inner,
+            state: PhantomData,
+        }
+    }
+
+    /// Returns to the configuring state to add more clauses or solve again.
+    pub fn resume(self) -> Solver<Configuring, C> {
+        self.with_state()
+    }
+
+    /// Returns the underlying solver with the unchecked interface.
+    pub fn into_inner(self) -> super::Solver<C> {
+        self.inner
+    }
+
+    /// Returns the maximum variable index.
+    pub fn max_variable(&self) -> i32 {
+        self.inner.max_variable()
+    }
+
+    /// Returns the number of active irredundant clauses.
+    pub fn num_clauses(&self) -> usize {
+        self.inner.num_clauses()
+    }
+
+    /// Returns a mutable reference to the callbacks.
+    pub fn get_callbacks(&mut self) -> Option<&mut C> {
+        self.inner.get_callbacks()
+    }
+}
+
+impl<C: Callbacks> Solver<Configuring, C> {
+    /// Constructs a new solver instance.
+    pub fn new() -> Self {
+        super::Solver::new().into()
+    }
+
+    /// Adds the given clause to the solver.
+    pub fn add_clause<I>(&mut self, clause: I)
+    where
+        I: IntoIterator<Item = i32>,
+    {
+        self.inner.add_clause(clause);
+    }
+
+    /// Adds all clauses of the formula to the solver.
+    pub fn add_formula(&mut self, formula: &CnfFormula) {
+        self.inner.add_formula(formula);
+    }
+
+    /// Sets a constraint clause for the next solve call only.
+    pub fn constrain<I>(&mut self, clause: I)
+    where
+        I: IntoIterator<Item = i32>,
+    {
+        self.inner.constrain(clause);
+    }
+
+    /// Returns a fresh variable, the next one after `max_variable`.
+    pub fn new_variable(&mut self) -> i32 {
+        self.inner.new_variable()
+    }
+
+    /// Sets the value of the given option.
+    pub fn set_option(&mut self, name: &str, value: i32) -> Result<(), OptionError> {
+        self.inner.set_option(name, value)
+    }
+
+    /// Sets the callbacks used during solving.
+    pub fn set_callbacks(&mut self, cbs: Option<C>) {
+        self.inner.set_callbacks(cbs);
+    }
+
+    /// Solves the formula defined by the added clauses.
+    pub fn solve(self) -> Outcome<C> {
+        self.solve_with(None)
+    }
+
+    /// Solves the formula under the given assumptions.
+    pub fn solve_with<I>(mut self, assumptions: I) -> Outcome<C>
+    where
+        I: IntoIterator<Item = i32>,
+    {
+        match self.inner.solve_with(assumptions) {
+            Some(true) => Outcome::Sat(self.with_state()),
+            Some(false) => Outcome::Unsat(self.with_state()),
+            None => Outcome::Unknown(self),
+        }
+    }
+}
+
+impl<C: Callbacks> Default for Solver<Configuring, C> {
+    fn default() -> Self {
+        Solver::new()
+    }
+}
+
+impl<C: Callbacks> From<super::Solver<C>> for Solver<Configuring, C> {
+    fn from(inner: super::Solver<C>) -> Self {
+        Solver {
+            inner,
+            state: PhantomData,
+        }
+    }
+}
+
+impl<C: Callbacks> Solver<Sat, C> {
+    /// Returns the value of the given literal in the model.
+    pub fn value(&self, lit: i32) -> Option<bool> {
+        self.inner.value(lit)
+    }
+
+    /// Returns the literals of the variables up to `max_variable` that are
+    /// true in the model.
+    pub fn model(&self) -> Vec<i32> {
+        (1..=self.max_variable())
+            .map(|var| {
+                if self.value(var) == Some(false) {
+                    -var
+                } else {
+                    var
+                }
+            })
+            .collect()
+    }
+}
+
+impl<C: Callbacks> Solver<Unsat, C> {
+    /// Checks if the given assumed literal was used in the proof of
+    /// unsatisfiability.
+    pub fn failed(&self, lit: i32) -> bool {
+        self.inner.failed(lit)
+    }
+
+    /// Checks if the constraint clause was used in the proof of
+    /// unsatisfiability.
+    pub fn constraint_failed(&self) -> bool {
+        self.inner.constraint_failed()
+    }
+}
@@ -0,0 +1,28 @@
+//! Conversion from the formula types of the `varisat-formula` crate. This
+//! module is only available with the `varisat` feature.
+
+use super::{Callbacks, Solver};
+use varisat_formula::CnfFormula;
+
+impl<C: Callbacks> Solver<C> {
+    /// Adds all clauses of the given varisat formula to the solver, and
+    /// makes sure that all of its variables are initialized.
+    pub fn add_varisat_formula(&mut self, formula: &CnfFormula) {
+        if formula.var_count() > 0 {
+            self.reserve(formula.var_count() as i32);
+        }
+        for clause in formula.iter() {
+            self.add_clause(clause.iter().map(|lit| lit.to_dimacs() as i32));
+        }
+    }
+}
+
+impl<C: Callbacks> From<&CnfFormula> for Solver<C> {
+    /// Constructs a new solver containing the clauses of the given varisat
+    /// formula.
+    fn from(formula: &CnfFormula) -> Self {
+        let mut sat = Solver::new();
+        sat.add_varisat_formula(formula);
+        sat
+    }
+}
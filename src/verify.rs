@@ -0,0 +1,38 @@
+//! Verification of the models returned by the solver against a shadow copy
+//! of the added clauses.
+
+use super::{Callbacks, Solver};
+
+impl<C: Callbacks> Solver<C> {
+    /// Checks that the last solution satisfies every clause added to the
+    /// solver, and returns the first violated clause otherwise. The clauses
+    /// are recorded as seen by CaDiCaL, including the activation literals of
+    /// scopes and removable clauses, but clauses loaded with `read_dimacs`
+    /// are not recorded. The state of the solver must be `Some(true)`. A
+    /// violated clause points to a bug in the wrapper or to a misuse of the
+    /// incremental interface, such as relying on a variable that was
+    /// eliminated while it was not frozen.
+    /// # Examples
+    /// ```
+    /// let mut sat: cadical::Solver = Default::default();
+    /// sat.add_clause([1, 2]);
+    /// sat.add_clause([-1, 2]);
+    /// assert_eq!(sat.solve(), Some(true));
+    /// assert_eq!(sat.verify_model(), Ok(()));
+    /// ```
+    pub fn verify_model(&self) -> Result<(), Vec<i32>> {
+        debug_assert!(self.status() == Some(true));
+        match self
+            .shadow
+            .find_violated(|lit| self.value(lit) == Some(true))
+        {
+            Some(index) => Err(self.shadow.clause(index).collect()),
+            None => Ok(()),
+        }
+    }
+
+    /// Returns the number of clauses recorded for model verification.
+    pub fn num_recorded_clauses(&self) -> usize {
+        self.shadow.len()
+    }
+}
@@ -0,0 +1,80 @@
+//! Handling of zero literals in the clauses passed to `add_clause`.
+
+use super::{Callbacks, Solver};
+
+/// The handling of zeros inside the clauses passed to `add_clause`, set with
+/// `Solver::set_zero_literal_policy`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum ZeroLiteralPolicy {
+    /// Zeros are not checked, except by a debug assertion, and are passed
+    /// to CaDiCaL, which terminates the clause at the zero and starts a new
+    /// one with the rest of the literals.
+    #[default]
+    Unchecked,
+    /// A clause containing a zero is rejected: `add_clause` panics and
+    /// `try_add_clause` returns an error.
+    Reject,
+    /// Zeros separate clauses like in the raw IPASIR interface, so
+    /// DIMACS-style streams such as `[1, 2, 0, -1, 0]` can be added at once.
+    /// A zero at the end terminates the last clause, while two consecutive
+    /// zeros add the empty clause.
+    Split,
+    /// Zeros are dropped from the clause.
+    Skip,
+}
+
+impl<C: Callbacks> Solver<C> {
+    /// Sets how zeros inside the clauses passed to `add_clause`,
+    /// `add_clause_slice` and `try_add_clause` are handled. The policy is
+    /// kept by `reset` and copied by `fork`.
+    /// # Examples
+    /// ```
+    /// use cadical::ZeroLiteralPolicy;
+    /// let mut sat: cadical::Solver = Default::default();
+    /// sat.set_zero_literal_policy(ZeroLiteralPolicy::Split);
+    /// sat.add_clause([1, 2, 0, -1, 0, -2, 3, 0]);
+    /// assert_eq!(sat.num_clauses(), 3);
+    /// sat.set_zero_literal_policy(ZeroLiteralPolicy::Reject);
+    /// assert!(sat.try_add_clause([-3, 0]).is_err());
+    /// assert_eq!(sat.solve(), Some(true));
+    /// ```
+    pub fn set_zero_literal_policy(&mut self, policy: ZeroLiteralPolicy) {
+        self.zero_policy = policy;
+    }
+
+    /// Returns the handling of zeros inside the added clauses.
+    #[inline]
+    pub fn zero_literal_policy(&self) -> ZeroLiteralPolicy {
+        self.zero_policy
+    }
+
+    /// Adds the clause in the buffer, which might contain zeros, according
+    /// to the policy.
+    pub(crate) fn add_zero_separated(&mut self) {
+        match self.zero_policy {
+            ZeroLiteralPolicy::Unchecked => self.add_buffered_clause(),
+            ZeroLiteralPolicy::Reject => {
+                if self.clause.contains(&0) {
+                    panic!("zero literal in clause");
+                }
+                self.add_buffered_clause();
+            }
+            ZeroLiteralPolicy::Split => {
+                let lits = std::mem::take(&mut self.clause);
+                let end = match lits.last() {
+                    Some(0) => lits.len() - 1,
+                    _ => lits.len(),
+                };
+                for part in lits[..end].split(|&lit| lit == 0) {
+                    self.clause.clear();
+                    self.clause.extend_from_slice(part);
+                    self.add_buffered_clause();
+                }
+            }
+            ZeroLiteralPolicy::Skip => {
+                self.clause.retain(|&lit| lit != 0);
+                self.add_buffered_clause();
+            }
+        }
+    }
+}